@@ -1,6 +1,17 @@
 //! Shared clap argument builders for problem and competition args.
 
-use clap::{Arg, ArgAction};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use clap::{value_parser, Arg, ArgAction, ArgMatches, ValueHint};
+
+use crate::problem::comparator::Comparator;
+use crate::problem::difficulty::DifficultyRange;
+use crate::problem::export::{ExportFormat, EXPORT_FORMAT_NAMES};
+use crate::problem::report::ReportFormat;
+use crate::problem::run::{memory_limit_from_mb, RunLimits};
+use crate::util::resolve_jobs;
 
 pub(crate) const PROBLEM_VALUE_NAME: &str = "PROBLEM";
 pub(crate) const COMPETITION_VALUE_NAME: &str = "COMP";
@@ -62,6 +73,152 @@ pub(crate) fn category_option_arg_required() -> Arg {
     category_option_arg_optional().required(true)
 }
 
+pub(crate) fn time_limit_option_arg() -> Arg {
+    Arg::new("time-limit")
+        .long("time-limit")
+        .help(
+            "Time limit for each test case in seconds, e.g. 1 or 2.5 (default: from problem.toml)",
+        )
+        .value_name("SECONDS")
+        .value_parser(value_parser!(f64))
+        .action(ArgAction::Set)
+}
+
+pub(crate) fn memory_limit_option_arg() -> Arg {
+    Arg::new("memory-limit")
+        .long("memory-limit")
+        .help("Memory limit for each test case in megabytes, e.g. 256 (default: from problem.toml)")
+        .value_name("MB")
+        .value_parser(value_parser!(u64))
+        .action(ArgAction::Set)
+}
+
+pub(crate) fn comparator_option_arg() -> Arg {
+    Arg::new("comparator")
+        .long("comparator")
+        .help(
+            "Comparator used to check outputs when there is no custom checker: exact, tokens, lines, case-insensitive, or float[:ABS_EPS[:REL_EPS]] (default: from problem.toml, otherwise exact)",
+        )
+        .value_name("COMPARATOR")
+        .value_parser(|s: &str| s.parse::<Comparator>())
+        .action(ArgAction::Set)
+}
+
+pub(crate) fn difficulty_option_arg() -> Arg {
+    Arg::new("difficulty")
+        .long("difficulty")
+        .help("Only include problems with a difficulty rating in this range (e.g. 800..1200, 1600.., 1000)")
+        .value_name("RANGE")
+        .value_parser(|s: &str| s.parse::<DifficultyRange>())
+        .action(ArgAction::Set)
+}
+
+pub(crate) fn export_format_option_arg() -> Arg {
+    Arg::new("format")
+        .long("format")
+        .help("Package format of the judge to export to")
+        .value_parser(EXPORT_FORMAT_NAMES.to_vec())
+        .required(true)
+        .action(ArgAction::Set)
+}
+
+pub(crate) fn report_format_option_arg() -> Arg {
+    Arg::new("format")
+        .long("format")
+        .help("Format of the test report written to stdout or the output file")
+        .value_parser(["text", "json", "junit"])
+        .default_value("text")
+        .action(ArgAction::Set)
+}
+
+pub(crate) fn report_output_option_arg() -> Arg {
+    Arg::new("output")
+        .long("output")
+        .short('o')
+        .help("File to write the test report to (default: stdout)")
+        .value_name("FILE")
+        .value_hint(ValueHint::FilePath)
+        .value_parser(value_parser!(PathBuf))
+        .action(ArgAction::Set)
+}
+
+pub(crate) fn no_pdf_option_arg() -> Arg {
+    Arg::new("no-pdf")
+        .long("no-pdf")
+        .help("Only render HTML, even if a typesetter is available to create a PDF")
+        .action(ArgAction::SetTrue)
+}
+
+pub(crate) fn jobs_option_arg() -> Arg {
+    Arg::new("jobs")
+        .long("jobs")
+        .short('j')
+        .help("Number of test cases to run at the same time (0 uses one per CPU)")
+        .value_name("N")
+        .value_parser(value_parser!(usize))
+        .default_value("1")
+        .action(ArgAction::Set)
+}
+
+/// The `--jobs` arg for competition commands, which run the problems one
+/// after another and only run the test cases of each problem in parallel.
+pub(crate) fn comp_jobs_option_arg() -> Arg {
+    jobs_option_arg().help(
+        "Number of test cases of a problem to run at the same time, with problems run one after another (0 uses one per CPU)",
+    )
+}
+
+/// Get the number of worker threads from the `--jobs` arg.
+pub(crate) fn jobs_from_args(args: &ArgMatches) -> Result<usize> {
+    let jobs = args.try_get_one::<usize>("jobs")?.copied().unwrap_or(1);
+    Ok(resolve_jobs(jobs))
+}
+
+/// Get the output comparator from the `--comparator` arg, if given.
+pub(crate) fn comparator_from_args(args: &ArgMatches) -> Result<Option<Comparator>> {
+    Ok(args.try_get_one::<Comparator>("comparator")?.copied())
+}
+
+/// Get the package format from the `--format` arg.
+pub(crate) fn export_format_from_args(args: &ArgMatches) -> Result<ExportFormat> {
+    args.try_get_one::<String>("format")?
+        .and_then(|name| ExportFormat::from_name(name))
+        .context("Invalid export format")
+}
+
+/// Get the report format and output file from the `--format` and `--output` args.
+pub(crate) fn report_options_from_args(
+    args: &ArgMatches,
+) -> Result<(ReportFormat, Option<PathBuf>)> {
+    let format = args
+        .try_get_one::<String>("format")?
+        .map_or(Some(ReportFormat::Text), |name| {
+            ReportFormat::from_name(name)
+        })
+        .context("Invalid report format")?;
+    let output = args.try_get_one::<PathBuf>("output")?.cloned();
+
+    Ok((format, output))
+}
+
+/// Build the run limits from the `--time-limit` and `--memory-limit` args.
+pub(crate) fn run_limits_from_args(args: &ArgMatches) -> Result<RunLimits> {
+    let time_limit = args
+        .try_get_one::<f64>("time-limit")?
+        .map(|secs| Duration::try_from_secs_f64(*secs))
+        .transpose()
+        .context("The time limit must be a non-negative number of seconds")?;
+    let memory_limit = args
+        .try_get_one::<u64>("memory-limit")?
+        .map(|mb| memory_limit_from_mb(*mb))
+        .transpose()?;
+
+    Ok(RunLimits {
+        time_limit,
+        memory_limit,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,4 +273,54 @@ mod tests {
         assert!(matches!(arg.get_action(), ArgAction::Set));
         assert!(arg.is_required_set());
     }
+
+    #[test]
+    fn run_limits_are_parsed_from_args() {
+        let cmd =
+            clap::Command::new("test").args([time_limit_option_arg(), memory_limit_option_arg()]);
+        let matches = cmd
+            .try_get_matches_from(["test", "--time-limit", "1.5", "--memory-limit", "256"])
+            .expect("args should parse");
+
+        let limits = run_limits_from_args(&matches).expect("limits should be valid");
+
+        assert_eq!(limits.time_limit, Some(Duration::from_millis(1500)));
+        assert_eq!(limits.memory_limit, Some(256 * 1024 * 1024));
+
+        let matches = clap::Command::new("test")
+            .arg(memory_limit_option_arg())
+            .try_get_matches_from(["test", "--memory-limit", &u64::MAX.to_string()])
+            .expect("args should parse");
+        assert!(run_limits_from_args(&matches).is_err());
+    }
+
+    #[test]
+    fn comparator_is_parsed_from_args() {
+        let cmd = clap::Command::new("test").arg(comparator_option_arg());
+
+        let matches = cmd
+            .clone()
+            .try_get_matches_from(["test", "--comparator", "float:1e-3"])
+            .expect("args should parse");
+        assert_eq!(
+            comparator_from_args(&matches).expect("comparator should be valid"),
+            Some(Comparator::Float {
+                abs_epsilon: 1e-3,
+                rel_epsilon: 1e-3
+            })
+        );
+
+        let matches = cmd
+            .clone()
+            .try_get_matches_from(["test"])
+            .expect("args should parse");
+        assert_eq!(
+            comparator_from_args(&matches).expect("comparator should be valid"),
+            None
+        );
+
+        assert!(cmd
+            .try_get_matches_from(["test", "--comparator", "regex"])
+            .is_err());
+    }
 }
@@ -1,15 +1,25 @@
 use std::fs;
+use std::path::PathBuf;
 
 use anyhow::{Context, Result};
-use clap::{Arg, ArgAction, ArgMatches, Command};
+use clap::{value_parser, Arg, ArgAction, ArgGroup, ArgMatches, Command, ValueHint};
 
 use crate::cli::arg_builders::{
-    competition_arg_required, competition_option_arg_optional, competition_option_arg_required,
-    configure_competition_arg, problem_option_arg_required,
+    comp_jobs_option_arg, comparator_from_args, comparator_option_arg, competition_arg_required,
+    competition_option_arg_optional, competition_option_arg_required, configure_competition_arg,
+    configure_problem_arg, export_format_from_args, export_format_option_arg, jobs_from_args,
+    memory_limit_option_arg, no_pdf_option_arg, problem_option_arg_required,
+    report_format_option_arg, report_options_from_args, report_output_option_arg,
+    run_limits_from_args, time_limit_option_arg,
+};
+use crate::comp::{
+    add, check, create, difficulty, export, finish, list, remove, rename, render, reopen, reorder,
+    set, solve, test,
 };
-use crate::comp::{add, create, finish, list, remove, rename, solve, test};
 use crate::config::get_settings;
+use crate::problem::report;
 use crate::problem::run::{RunnableCategory, RunnableFile};
+use crate::publish::DIST_DIR;
 use crate::util::get_project_root;
 
 pub fn cli() -> Command {
@@ -24,6 +34,19 @@ pub fn cli() -> Command {
                     problem_option_arg_required(),
                 ]),
         )
+        .subcommand(
+            Command::new("check")
+                .about("Check every problem in a competition and show whether each one is ready")
+                .arg_required_else_help(true)
+                .args([
+                    competition_arg_required(),
+                    Arg::new("lang")
+                        .long("lang")
+                        .help("Language of the reference solution (e.g. cpp, py)")
+                        .action(ArgAction::Set),
+                    comp_jobs_option_arg(),
+                ]),
+        )
         .subcommand(
             Command::new("create")
                 .about("Create a new competition")
@@ -35,6 +58,28 @@ pub fn cli() -> Command {
                         .required(true),
                 ),
         )
+        .subcommand(
+            Command::new("difficulty")
+                .about("Show the difficulty of each problem and check the difficulty spread of a competition")
+                .args([competition_arg_required()]),
+        )
+        .subcommand(
+            Command::new("export")
+                .about("Export all problems in a competition to the package format of another judge")
+                .arg_required_else_help(true)
+                .args([
+                    competition_arg_required(),
+                    export_format_option_arg(),
+                    Arg::new("output")
+                        .long("output")
+                        .short('o')
+                        .help("Folder to write the packages to (default: dist/<comp>-<format>)")
+                        .value_name("DIR")
+                        .value_hint(ValueHint::DirPath)
+                        .value_parser(value_parser!(PathBuf))
+                        .action(ArgAction::Set),
+                ]),
+        )
         .subcommand(
             Command::new("finish")
                 .about("Finish a competition and archive problems from the competition")
@@ -54,6 +99,23 @@ pub fn cli() -> Command {
                     problem_option_arg_required(),
                 ]),
         )
+        .subcommand(
+            Command::new("render")
+                .about("Render the problem statements of a competition to a booklet, with one problem per page")
+                .arg_required_else_help(true)
+                .args([
+                    competition_arg_required(),
+                    Arg::new("output")
+                        .long("output")
+                        .short('o')
+                        .help("Path to write the HTML to, with the PDF next to it (default: dist/<comp>.html)")
+                        .value_name("FILE")
+                        .value_hint(ValueHint::FilePath)
+                        .value_parser(value_parser!(PathBuf))
+                        .action(ArgAction::Set),
+                    no_pdf_option_arg(),
+                ]),
+        )
         .subcommand(
             Command::new("rename")
                 .about("Rename a competition")
@@ -70,6 +132,56 @@ pub fn cli() -> Command {
                         .required(true),
                 ]),
         )
+        .subcommand(
+            Command::new("reopen")
+                .about("Reopen a finished competition and unarchive its problems")
+                .args([competition_arg_required()]),
+        )
+        .subcommand(
+            Command::new("reorder")
+                .about("Change the order of the problems in a competition, lettering them A, B, C, ... in that order")
+                .arg_required_else_help(true)
+                .args([
+                    competition_arg_required(),
+                    configure_problem_arg(Arg::new("problems"))
+                        .help("Every problem of the competition, in the new order")
+                        .num_args(1..)
+                        .action(ArgAction::Append)
+                        .required(true),
+                ]),
+        )
+        .subcommand(
+            Command::new("set")
+                .about("Set the title, description, start or end time of a competition")
+                .arg_required_else_help(true)
+                .args([
+                    competition_arg_required(),
+                    Arg::new("title")
+                        .long("title")
+                        .help("Title of the competition (an empty value clears it)")
+                        .action(ArgAction::Set),
+                    Arg::new("description")
+                        .long("description")
+                        .help("Description of the competition (an empty value clears it)")
+                        .action(ArgAction::Set),
+                    Arg::new("start")
+                        .long("start")
+                        .help("Start time in RFC 3339 format, e.g. 2025-03-01T10:00:00+10:30 (an empty value clears it)")
+                        .value_name("TIME")
+                        .action(ArgAction::Set),
+                    Arg::new("end")
+                        .long("end")
+                        .help("End time in RFC 3339 format (an empty value clears it)")
+                        .value_name("TIME")
+                        .action(ArgAction::Set),
+                ])
+                .group(
+                    ArgGroup::new("details")
+                        .args(["title", "description", "start", "end"])
+                        .multiple(true)
+                        .required(true),
+                ),
+        )
         .subcommand(
             Command::new("solve")
                 .about("Generate output test cases for all problems in a competition")
@@ -80,6 +192,7 @@ pub fn cli() -> Command {
                         .long("lang")
                         .help("Language of the solution file (e.g. cpp, py)")
                         .action(ArgAction::Set),
+                    comp_jobs_option_arg(),
                 ]),
         )
         .subcommand(
@@ -92,6 +205,12 @@ pub fn cli() -> Command {
                         .long("lang")
                         .help("Language of the solution file (e.g. cpp, py)")
                         .action(ArgAction::Set),
+                    time_limit_option_arg(),
+                    memory_limit_option_arg(),
+                    comparator_option_arg(),
+                    report_format_option_arg(),
+                    report_output_option_arg(),
+                    comp_jobs_option_arg(),
                 ]),
         )
         .subcommand_required(true)
@@ -115,6 +234,23 @@ pub fn exec(args: &ArgMatches) -> Result<()> {
 
             add::add(&problems_dir, comp_name, problem_name)?;
         }
+        Some(("check", cmd)) => {
+            let comp_name = cmd
+                .try_get_one::<String>("comp")?
+                .context("Competition name is required")?;
+            let solution_lang = cmd.try_get_one::<String>("lang")?;
+
+            let solution_file =
+                RunnableFile::new(&settings, RunnableCategory::Solution, None, solution_lang)?;
+
+            check::check(
+                &settings,
+                &problems_dir,
+                comp_name,
+                solution_file,
+                jobs_from_args(cmd)?,
+            )?;
+        }
         Some(("create", cmd)) => {
             let comp_name = cmd
                 .try_get_one::<String>("name")?
@@ -122,6 +258,27 @@ pub fn exec(args: &ArgMatches) -> Result<()> {
 
             create::create(&problems_dir, comp_name)?;
         }
+        Some(("difficulty", cmd)) => {
+            let comp_name = cmd
+                .try_get_one::<String>("comp")?
+                .context("Competition name is required")?;
+
+            difficulty::difficulty(&problems_dir, comp_name)?;
+        }
+        Some(("export", cmd)) => {
+            let comp_name = cmd
+                .try_get_one::<String>("comp")?
+                .context("Competition name is required")?;
+            let format = export_format_from_args(cmd)?;
+            let output_dir = match cmd.try_get_one::<PathBuf>("output")? {
+                Some(path) => path.to_owned(),
+                None => get_project_root()?
+                    .join(DIST_DIR)
+                    .join(format!("{comp_name}-{}", format.name())),
+            };
+
+            export::export(&settings, &problems_dir, comp_name, format, &output_dir)?;
+        }
         Some(("finish", cmd)) => {
             let comp_name = cmd
                 .try_get_one::<String>("comp")?
@@ -144,6 +301,25 @@ pub fn exec(args: &ArgMatches) -> Result<()> {
 
             remove::remove(&problems_dir, comp_name, problem_name)?;
         }
+        Some(("render", cmd)) => {
+            let comp_name = cmd
+                .try_get_one::<String>("comp")?
+                .context("Competition name is required")?;
+            let output = match cmd.try_get_one::<PathBuf>("output")? {
+                Some(path) => path.to_owned(),
+                None => get_project_root()?
+                    .join(DIST_DIR)
+                    .join(format!("{comp_name}.html")),
+            };
+
+            render::render(
+                &settings,
+                &problems_dir,
+                comp_name,
+                &output,
+                !cmd.get_flag("no-pdf"),
+            )?;
+        }
         Some(("rename", cmd)) => {
             let old_comp_name = cmd
                 .try_get_one::<String>("old_name")?
@@ -154,6 +330,38 @@ pub fn exec(args: &ArgMatches) -> Result<()> {
 
             rename::rename(&problems_dir, old_comp_name, new_comp_name)?;
         }
+        Some(("reopen", cmd)) => {
+            let comp_name = cmd
+                .try_get_one::<String>("comp")?
+                .context("Competition name is required")?;
+
+            reopen::reopen(&problems_dir, comp_name)?;
+        }
+        Some(("reorder", cmd)) => {
+            let comp_name = cmd
+                .try_get_one::<String>("comp")?
+                .context("Competition name is required")?;
+            let problem_names: Vec<String> = cmd
+                .try_get_many::<String>("problems")?
+                .context("Problem names are required")?
+                .cloned()
+                .collect();
+
+            reorder::reorder(&problems_dir, comp_name, &problem_names)?;
+        }
+        Some(("set", cmd)) => {
+            let comp_name = cmd
+                .try_get_one::<String>("comp")?
+                .context("Competition name is required")?;
+            let details = set::CompetitionDetails {
+                title: cmd.try_get_one::<String>("title")?.cloned(),
+                description: cmd.try_get_one::<String>("description")?.cloned(),
+                start: cmd.try_get_one::<String>("start")?.cloned(),
+                end: cmd.try_get_one::<String>("end")?.cloned(),
+            };
+
+            set::set(&problems_dir, comp_name, &details)?;
+        }
         Some(("solve", cmd)) => {
             let comp_name = cmd
                 .try_get_one::<String>("comp")?
@@ -163,7 +371,13 @@ pub fn exec(args: &ArgMatches) -> Result<()> {
             let solution_file =
                 RunnableFile::new(&settings, RunnableCategory::Solution, None, solution_lang)?;
 
-            solve::solve(&settings, &problems_dir, comp_name, solution_file)?;
+            solve::solve(
+                &settings,
+                &problems_dir,
+                comp_name,
+                solution_file,
+                jobs_from_args(cmd)?,
+            )?;
         }
         Some(("test", cmd)) => {
             let comp_name = cmd
@@ -174,7 +388,18 @@ pub fn exec(args: &ArgMatches) -> Result<()> {
             let solution_file =
                 RunnableFile::new(&settings, RunnableCategory::Solution, None, solution_lang)?;
 
-            test::test(&settings, &problems_dir, comp_name, solution_file)?;
+            let (format, output) = report_options_from_args(cmd)?;
+
+            let reports = test::test(
+                &settings,
+                &problems_dir,
+                comp_name,
+                solution_file,
+                run_limits_from_args(cmd)?,
+                comparator_from_args(cmd)?,
+                jobs_from_args(cmd)?,
+            )?;
+            report::finish_report(&reports, format, output.as_deref())?;
         }
         _ => {}
     }
@@ -1,13 +1,26 @@
 use std::fs;
+use std::path::PathBuf;
 
 use anyhow::{bail, Context, Result};
-use clap::{Arg, ArgAction, ArgMatches, Command, ValueHint};
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command, ValueHint};
 
-use crate::cli::arg_builders::{category_option_arg_required, problem_option_arg_optional};
+use crate::cli::arg_builders::{
+    category_option_arg_required, comparator_from_args, comparator_option_arg,
+    difficulty_option_arg, export_format_from_args, export_format_option_arg, jobs_from_args,
+    jobs_option_arg, memory_limit_option_arg, no_pdf_option_arg, problem_option_arg_optional,
+    report_format_option_arg, report_options_from_args, report_output_option_arg,
+    run_limits_from_args, time_limit_option_arg,
+};
 use crate::config::get_settings;
+use crate::problem::difficulty::DifficultyRange;
 use crate::problem::fuzz;
+use crate::problem::list::{ListFilters, ListFormat};
 use crate::problem::run::{RunnableCategory, RunnableFile};
-use crate::problem::{archive, check, compare, create, generate, reclassify, solve, test};
+use crate::problem::{
+    archive, check, compare, create, difficulty, export, generate, import, list, reclassify,
+    render, report, solve, statement, test, validate,
+};
+use crate::publish::DIST_DIR;
 use crate::util::{get_problem_from_cwd, get_project_root};
 
 pub fn cli() -> Command {
@@ -21,7 +34,17 @@ pub fn cli() -> Command {
         .subcommand(
             Command::new("check")
                 .about("Check that the problem folder and test files are valid")
-                .arg(problem_option_arg_optional()),
+                .args([
+                    problem_option_arg_optional(),
+                    Arg::new("fix")
+                        .long("fix")
+                        .help("Remove build artefacts left in the problem folder")
+                        .action(ArgAction::SetTrue),
+                    Arg::new("deep")
+                        .long("deep")
+                        .help("Run the reference solution on every test to find stale outputs")
+                        .action(ArgAction::SetTrue),
+                ]),
         )
         .subcommand(
             Command::new("compare")
@@ -33,6 +56,9 @@ pub fn cli() -> Command {
                         .value_hint(ValueHint::FilePath)
                         .action(ArgAction::Append),
                     problem_option_arg_optional(),
+                    time_limit_option_arg(),
+                    memory_limit_option_arg(),
+                    comparator_option_arg(),
                 ]),
         )
         .subcommand(
@@ -47,6 +73,22 @@ pub fn cli() -> Command {
                         .required(true),
                 ),
         )
+        .subcommand(
+            Command::new("export")
+                .about("Export a problem to the package format of another judge")
+                .args([
+                    export_format_option_arg(),
+                    problem_option_arg_optional(),
+                    Arg::new("output")
+                        .long("output")
+                        .short('o')
+                        .help("Path to write the package to (default: dist/<problem>-<format>.zip)")
+                        .value_name("FILE")
+                        .value_hint(ValueHint::FilePath)
+                        .value_parser(value_parser!(PathBuf))
+                        .action(ArgAction::Set),
+                ]),
+        )
         .subcommand(
             Command::new("fuzz")
                 .about("Find potential edge cases in two or more solutions")
@@ -66,6 +108,9 @@ pub fn cli() -> Command {
                         .help("Language of the generator file (e.g. cpp, py)")
                         .action(ArgAction::Set),
                     problem_option_arg_optional(),
+                    time_limit_option_arg(),
+                    memory_limit_option_arg(),
+                    comparator_option_arg(),
                 ]),
         )
         .subcommand(
@@ -90,11 +135,107 @@ pub fn cli() -> Command {
                         .action(ArgAction::Set),
                 ]),
         )
+        .subcommand(
+            Command::new("import")
+                .about("Import a problem from a Kattis, DOMjudge or Polygon package")
+                .arg_required_else_help(true)
+                .args([
+                    Arg::new("package")
+                        .help("Path to the package zip archive or folder")
+                        .value_name("PACKAGE")
+                        .value_hint(ValueHint::AnyPath)
+                        .value_parser(value_parser!(PathBuf))
+                        .action(ArgAction::Set)
+                        .required(true),
+                    category_option_arg_required(),
+                    Arg::new("name")
+                        .long("name")
+                        .help("Problem name to import as (default: the name of the package)")
+                        .action(ArgAction::Set),
+                ]),
+        )
+        .subcommand(
+            Command::new("list")
+                .about("List problems")
+                .args([
+                    Arg::new("status")
+                        .long("status")
+                        .help("Only include new or archived problems")
+                        .value_parser(["new", "archive"])
+                        .action(ArgAction::Set),
+                    Arg::new("category")
+                        .long("category")
+                        .help("Only include problems in this category")
+                        .action(ArgAction::Set),
+                    Arg::new("comp")
+                        .long("comp")
+                        .help("Only include problems in this competition")
+                        .action(ArgAction::Set)
+                        .conflicts_with("no-comp"),
+                    Arg::new("no-comp")
+                        .long("no-comp")
+                        .help("Only include problems that are not in any competition")
+                        .action(ArgAction::SetTrue),
+                    Arg::new("tag")
+                        .long("tag")
+                        .help("Only include problems with this tag")
+                        .action(ArgAction::Set),
+                    Arg::new("min-tests")
+                        .long("min-tests")
+                        .help("Only include problems with at least this many test cases")
+                        .value_name("N")
+                        .value_parser(value_parser!(usize))
+                        .action(ArgAction::Set),
+                    Arg::new("max-tests")
+                        .long("max-tests")
+                        .help("Only include problems with at most this many test cases")
+                        .value_name("N")
+                        .value_parser(value_parser!(usize))
+                        .action(ArgAction::Set),
+                    difficulty_option_arg(),
+                    Arg::new("format")
+                        .long("format")
+                        .help("Format of the list written to stdout")
+                        .value_parser(["table", "json"])
+                        .default_value("table")
+                        .action(ArgAction::Set),
+                ]),
+        )
+        .subcommand(
+            Command::new("rate")
+                .about("Set the difficulty rating of a problem")
+                .arg_required_else_help(true)
+                .args([
+                    Arg::new("rating")
+                        .help("Difficulty rating, a multiple of 100 from 800 to 3500")
+                        .value_name("RATING")
+                        .value_parser(value_parser!(u32))
+                        .action(ArgAction::Set)
+                        .required(true),
+                    problem_option_arg_optional(),
+                ]),
+        )
         .subcommand(
             Command::new("reclassify")
                 .about("Move a problem to a different category")
                 .args([category_option_arg_required(), problem_option_arg_optional()]),
         )
+        .subcommand(
+            Command::new("render")
+                .about("Render the problem statement to HTML, and to PDF if a typesetter is installed")
+                .args([
+                    problem_option_arg_optional(),
+                    Arg::new("output")
+                        .long("output")
+                        .short('o')
+                        .help("Path to write the HTML to, with the PDF next to it (default: dist/<problem>.html)")
+                        .value_name("FILE")
+                        .value_hint(ValueHint::FilePath)
+                        .value_parser(value_parser!(PathBuf))
+                        .action(ArgAction::Set),
+                    no_pdf_option_arg(),
+                ]),
+        )
         .subcommand(
             Command::new("solve")
                 .about("Automatically generate test outputs for a problem, given pre-existing input files")
@@ -109,8 +250,14 @@ pub fn cli() -> Command {
                         .help("Language of the solution file (e.g. cpp, py)")
                         .action(ArgAction::Set),
                     problem_option_arg_optional(),
+                    jobs_option_arg(),
                 ]),
         )
+        .subcommand(
+            Command::new("sync-samples")
+                .about("Rewrite the examples in the problem statement from the sample tests (tests/sample*.in)")
+                .arg(problem_option_arg_optional()),
+        )
         .subcommand(
             Command::new("test")
                 .about("Run tests on a given problem")
@@ -125,8 +272,30 @@ pub fn cli() -> Command {
                         .help("Language of the solution file (e.g. cpp, py)")
                         .action(ArgAction::Set),
                     problem_option_arg_optional(),
+                    time_limit_option_arg(),
+                    memory_limit_option_arg(),
+                    comparator_option_arg(),
+                    report_format_option_arg(),
+                    report_output_option_arg(),
+                    jobs_option_arg(),
+                    Arg::new("expect-score")
+                        .long("expect-score")
+                        .help("Pass only if the solution earns exactly this many subtask points, even if some tests fail")
+                        .value_name("POINTS")
+                        .value_parser(value_parser!(u32))
+                        .action(ArgAction::Set),
                 ]),
         )
+        .subcommand(
+            Command::new("unarchive")
+                .about("Move an archived problem back to the new problems folder")
+                .arg(problem_option_arg_optional()),
+        )
+        .subcommand(
+            Command::new("validate")
+                .about("Check that every test input satisfies the constraints, using the problem's validator")
+                .arg(problem_option_arg_optional()),
+        )
         .subcommand_required(true)
 }
 
@@ -153,7 +322,11 @@ pub fn exec(args: &ArgMatches) -> Result<()> {
                 None => &get_problem_from_cwd(&problems_dir)?,
             };
 
-            check::check(problems_dir, problem_name)?;
+            let options = check::CheckOptions {
+                fix: cmd.get_flag("fix"),
+                deep: cmd.get_flag("deep"),
+            };
+            check::check(&settings, problems_dir, problem_name, options)?;
         }
         Some(("compare", cmd)) => {
             let problem_name = match cmd.try_get_one::<String>("problem")? {
@@ -181,6 +354,8 @@ pub fn exec(args: &ArgMatches) -> Result<()> {
                 problems_dir: &problems_dir,
                 problem_name: problem_name.to_owned(),
                 solution_files,
+                limits: run_limits_from_args(cmd)?,
+                comparator: comparator_from_args(cmd)?,
             };
 
             compare::compare(&settings, &compare_args)?;
@@ -196,6 +371,21 @@ pub fn exec(args: &ArgMatches) -> Result<()> {
 
             create::create(&problems_dir, problem_name, category)?;
         }
+        Some(("export", cmd)) => {
+            let problem_name = match cmd.try_get_one::<String>("problem")? {
+                Some(name) => name,
+                None => &get_problem_from_cwd(&problems_dir)?,
+            };
+            let format = export_format_from_args(cmd)?;
+            let output = match cmd.try_get_one::<PathBuf>("output")? {
+                Some(path) => path.to_owned(),
+                None => get_project_root()?
+                    .join(DIST_DIR)
+                    .join(format!("{problem_name}-{}.zip", format.name())),
+            };
+
+            export::export(&settings, &problems_dir, problem_name, format, &output)?;
+        }
         Some(("fuzz", cmd)) => {
             let problem_name = match cmd.try_get_one::<String>("problem")? {
                 Some(name) => name,
@@ -230,6 +420,8 @@ pub fn exec(args: &ArgMatches) -> Result<()> {
                 problem_name: problem_name.to_owned(),
                 solution_files,
                 generator,
+                limits: run_limits_from_args(cmd)?,
+                comparator: comparator_from_args(cmd)?,
             };
 
             fuzz::fuzz(&settings, &fuzz_args)?;
@@ -260,6 +452,51 @@ pub fn exec(args: &ArgMatches) -> Result<()> {
                 test_name,
             )?;
         }
+        Some(("import", cmd)) => {
+            let package_path = cmd
+                .try_get_one::<PathBuf>("package")?
+                .context("Package path is required")?;
+            let category = cmd
+                .try_get_one::<String>("category")?
+                .context("Problem category is required")?;
+            let problem_name = cmd.try_get_one::<String>("name")?;
+
+            import::import(
+                &problems_dir,
+                package_path,
+                category,
+                problem_name.map(|s| s.as_str()),
+            )?;
+        }
+        Some(("list", cmd)) => {
+            let filters = ListFilters {
+                status: cmd.try_get_one::<String>("status")?.cloned(),
+                category: cmd.try_get_one::<String>("category")?.cloned(),
+                comp: cmd.try_get_one::<String>("comp")?.cloned(),
+                no_comp: cmd.get_flag("no-comp"),
+                tag: cmd.try_get_one::<String>("tag")?.cloned(),
+                min_tests: cmd.try_get_one::<usize>("min-tests")?.copied(),
+                max_tests: cmd.try_get_one::<usize>("max-tests")?.copied(),
+                difficulty: cmd.try_get_one::<DifficultyRange>("difficulty")?.copied(),
+            };
+            let format = cmd
+                .try_get_one::<String>("format")?
+                .map_or(Some(ListFormat::Table), |name| ListFormat::from_name(name))
+                .context("Invalid list format")?;
+
+            list::list(&problems_dir, &filters, format)?;
+        }
+        Some(("rate", cmd)) => {
+            let problem_name = match cmd.try_get_one::<String>("problem")? {
+                Some(name) => name,
+                None => &get_problem_from_cwd(&problems_dir)?,
+            };
+            let rating = cmd
+                .try_get_one::<u32>("rating")?
+                .context("Rating is required")?;
+
+            difficulty::rate(&problems_dir, problem_name, *rating)?;
+        }
         Some(("reclassify", cmd)) => {
             let problem_name = match cmd.try_get_one::<String>("problem")? {
                 Some(name) => name,
@@ -272,6 +509,26 @@ pub fn exec(args: &ArgMatches) -> Result<()> {
 
             reclassify::reclassify(&problems_dir, problem_name, category)?;
         }
+        Some(("render", cmd)) => {
+            let problem_name = match cmd.try_get_one::<String>("problem")? {
+                Some(name) => name,
+                None => &get_problem_from_cwd(&problems_dir)?,
+            };
+            let output = match cmd.try_get_one::<PathBuf>("output")? {
+                Some(path) => path.to_owned(),
+                None => get_project_root()?
+                    .join(DIST_DIR)
+                    .join(format!("{problem_name}.html")),
+            };
+
+            render::render(
+                &settings,
+                &problems_dir,
+                problem_name,
+                &output,
+                !cmd.get_flag("no-pdf"),
+            )?;
+        }
         Some(("solve", cmd)) => {
             let problem_name = match cmd.try_get_one::<String>("problem")? {
                 Some(name) => name,
@@ -285,7 +542,21 @@ pub fn exec(args: &ArgMatches) -> Result<()> {
                 cmd.try_get_one::<String>("lang")?,
             )?;
 
-            solve::solve(&settings, &problems_dir, problem_name, &solution_file)?;
+            solve::solve(
+                &settings,
+                &problems_dir,
+                problem_name,
+                &solution_file,
+                jobs_from_args(cmd)?,
+            )?;
+        }
+        Some(("sync-samples", cmd)) => {
+            let problem_name = match cmd.try_get_one::<String>("problem")? {
+                Some(name) => name,
+                None => &get_problem_from_cwd(&problems_dir)?,
+            };
+
+            statement::sync_samples(&problems_dir, problem_name)?;
         }
         Some(("test", cmd)) => {
             let problem_name = match cmd.try_get_one::<String>("problem")? {
@@ -300,7 +571,43 @@ pub fn exec(args: &ArgMatches) -> Result<()> {
                 cmd.try_get_one::<String>("lang")?,
             )?;
 
-            test::test(&settings, &problems_dir, problem_name, &solution_file)?;
+            let (format, output) = report_options_from_args(cmd)?;
+
+            let report = test::test(
+                &settings,
+                &problems_dir,
+                problem_name,
+                &solution_file,
+                run_limits_from_args(cmd)?,
+                comparator_from_args(cmd)?,
+                jobs_from_args(cmd)?,
+            )?;
+            match cmd.try_get_one::<u32>("expect-score")? {
+                Some(&expected_score) => report::finish_scored_report(
+                    &report,
+                    expected_score,
+                    format,
+                    output.as_deref(),
+                )?,
+                None => report::finish_report(&[report], format, output.as_deref())?,
+            }
+        }
+        Some(("unarchive", cmd)) => {
+            let problem_name = match cmd.try_get_one::<String>("problem")? {
+                Some(name) => name,
+                None => &get_problem_from_cwd(&problems_dir)?,
+            };
+
+            archive::unarchive(&problems_dir, problem_name)?;
+            eprintln!("Unarchived problem '{problem_name}'");
+        }
+        Some(("validate", cmd)) => {
+            let problem_name = match cmd.try_get_one::<String>("problem")? {
+                Some(name) => name,
+                None => &get_problem_from_cwd(&problems_dir)?,
+            };
+
+            validate::validate(&settings, &problems_dir, problem_name)?;
         }
         _ => {}
     }
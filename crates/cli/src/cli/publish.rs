@@ -1,12 +1,66 @@
+use std::fs;
+use std::path::PathBuf;
+
 use anyhow::Result;
-use clap::{ArgMatches, Command};
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command, ValueHint};
+
+use crate::cli::arg_builders::{competition_option_arg_optional, problem_option_arg_optional};
+use crate::config::get_settings;
+use crate::publish::{publish, PublishArgs, PublishTarget};
+use crate::util::{get_problem_from_cwd, get_project_root};
 
 pub fn cli() -> Command {
-    Command::new("publish").about("Package test files and publish them to the remote server")
+    Command::new("publish")
+        .about("Package test files and publish them to the remote server")
+        .args([
+            problem_option_arg_optional().conflicts_with("comp"),
+            competition_option_arg_optional(),
+            Arg::new("output")
+                .long("output")
+                .short('o')
+                .help("Path to write the archive to (default: dist/<name>.zip)")
+                .value_name("FILE")
+                .value_hint(ValueHint::FilePath)
+                .value_parser(value_parser!(PathBuf))
+                .action(ArgAction::Set),
+            Arg::new("endpoint")
+                .long("endpoint")
+                .help("URL to upload the archive to (default: from the settings file)")
+                .value_name("URL")
+                .action(ArgAction::Set),
+            Arg::new("no-upload")
+                .long("no-upload")
+                .help("Only build the archive, without uploading it")
+                .action(ArgAction::SetTrue),
+        ])
 }
 
 pub fn exec(args: &ArgMatches) -> Result<()> {
-    _ = args;
+    let settings = get_settings()?;
+    let problems_dir = get_project_root()?.join(&settings.problems_dir);
+    if !fs::exists(&problems_dir).expect("Failed to check if path exists") {
+        fs::create_dir(&problems_dir).expect("Failed to create directory");
+    }
+
+    let target = match (
+        args.try_get_one::<String>("problem")?,
+        args.try_get_one::<String>("comp")?,
+    ) {
+        (_, Some(comp_name)) => PublishTarget::Competition(comp_name.to_owned()),
+        (Some(problem_name), None) => PublishTarget::Problem(problem_name.to_owned()),
+        (None, None) => PublishTarget::Problem(get_problem_from_cwd(&problems_dir)?),
+    };
+
+    publish(
+        &settings,
+        &PublishArgs {
+            problems_dir: &problems_dir,
+            target,
+            output: args.try_get_one::<PathBuf>("output")?.cloned(),
+            endpoint: args.try_get_one::<String>("endpoint")?.cloned(),
+            no_upload: args.get_flag("no-upload"),
+        },
+    )?;
 
     Ok(())
 }
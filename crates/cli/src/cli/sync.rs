@@ -1,18 +1,50 @@
+use std::env;
 use std::fs;
 
-use anyhow::Result;
-use clap::{ArgMatches, Command};
+use anyhow::{bail, Result};
+use clap::{Arg, ArgAction, ArgMatches, Command};
 
+use crate::cli::arg_builders::problem_option_arg_optional;
 use crate::config::get_settings;
 use crate::problem::sync_mappings;
+use crate::sync::remote::Remote;
+use crate::sync::{sync, Direction, SyncArgs};
 use crate::util::get_project_root;
 
 pub fn cli() -> Command {
-    Command::new("sync").about("Generate or update the problem mappings file")
+    Command::new("sync")
+        .about("Update the problem mappings file and sync problems with the remote problem store")
+        .args([
+            Arg::new("pull")
+                .long("pull")
+                .help("Apply remote changes to the local problems")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("push"),
+            Arg::new("push")
+                .long("push")
+                .help("Apply local changes to the remote problem store")
+                .action(ArgAction::SetTrue),
+            Arg::new("status")
+                .long("status")
+                .help("Show the problems changed locally and in the remote problem store since the last sync, without applying them")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["pull", "push"]),
+            problem_option_arg_optional()
+                .help("Only sync this problem (can be given more than once)")
+                .action(ArgAction::Append),
+            Arg::new("force")
+                .long("force")
+                .help("Also apply problems changed on both sides, overwriting the other side")
+                .action(ArgAction::SetTrue),
+            Arg::new("endpoint")
+                .long("endpoint")
+                .help("URL of the remote problem store (default: from the settings file)")
+                .value_name("URL")
+                .action(ArgAction::Set),
+        ])
 }
 
 pub fn exec(args: &ArgMatches) -> Result<()> {
-    _ = args;
     let settings = get_settings()?;
 
     let problems_dir = get_project_root()?.join(&settings.problems_dir);
@@ -23,5 +55,40 @@ pub fn exec(args: &ArgMatches) -> Result<()> {
     sync_mappings::sync_mappings(&problems_dir)?;
     eprintln!("Updated problem mappings file");
 
+    let direction = match (args.get_flag("pull"), args.get_flag("push")) {
+        (true, _) => Some(Direction::Pull),
+        (_, true) => Some(Direction::Push),
+        _ => None,
+    };
+
+    // Only contact the remote problem store when asked to, so that a plain
+    // `aucpl sync` works offline
+    if direction.is_none() && !args.get_flag("status") {
+        return Ok(());
+    }
+
+    let endpoint = args
+        .try_get_one::<String>("endpoint")?
+        .or(settings.sync.endpoint.as_ref());
+    let Some(endpoint) = endpoint else {
+        bail!("No remote problem store is set. Set `endpoint` under [sync] in the settings file or pass --endpoint");
+    };
+
+    let token = settings
+        .sync
+        .token_env
+        .as_ref()
+        .and_then(|var| env::var(var).ok());
+    let sync_args = SyncArgs {
+        direction,
+        problems: args
+            .try_get_many::<String>("problem")?
+            .map(|names| names.cloned().collect())
+            .unwrap_or_default(),
+        force: args.get_flag("force"),
+    };
+
+    sync(&problems_dir, &Remote::new(endpoint, token), &sync_args)?;
+
     Ok(())
 }
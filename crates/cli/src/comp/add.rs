@@ -1,12 +1,11 @@
-use std::fs::{self, File};
+use std::fs;
 use std::path::Path;
 
 use anyhow::{bail, Context, Result};
-use serde_json::{from_reader, to_writer_pretty};
 
 use crate::problem::sync_mappings::problem_exists;
 
-use super::{Competitions, COMPETITIONS_FILE};
+use super::{update_competitions, COMPETITIONS_FILE};
 
 pub fn add(problems_dir: &Path, comp_name: &str, problem_name: &str) -> Result<()> {
     let comp_file_path = problems_dir.join(COMPETITIONS_FILE);
@@ -14,35 +13,28 @@ pub fn add(problems_dir: &Path, comp_name: &str, problem_name: &str) -> Result<(
         bail!("Competitions file does not exist");
     }
 
-    let comp_file = File::open(&comp_file_path)?;
-    let mut data: Competitions = from_reader(&comp_file)?;
-
-    let comp_data = data
-        .get_mut(comp_name)
-        .context(format!("Competition '{comp_name}' not found"))?;
-
-    if !problem_exists(problems_dir, problem_name)? {
-        eprintln!("The problem '{problem_name}' does not exist");
-        return Ok(());
-    }
-    if comp_data.finished {
-        eprintln!("Cannot add problems to an archived competition");
-        return Ok(());
-    }
-
-    if comp_data.problems.contains(&problem_name.to_string()) {
-        eprintln!("The problem '{problem_name}' already exists in the competition");
-        return Ok(());
-    }
-    comp_data.problems.push(problem_name.to_string());
-    comp_data.problems.sort_unstable();
-
-    let comp_file = File::options()
-        .write(true)
-        .truncate(true)
-        .open(comp_file_path)?;
-    to_writer_pretty(&comp_file, &data)?;
-    eprintln!("Added problem '{problem_name}' to the competition '{comp_name}'");
-
-    Ok(())
+    update_competitions(problems_dir, |data| {
+        let comp_data = data
+            .get_mut(comp_name)
+            .context(format!("Competition '{comp_name}' not found"))?;
+
+        if !problem_exists(problems_dir, problem_name)? {
+            eprintln!("The problem '{problem_name}' does not exist");
+            return Ok(());
+        }
+        if comp_data.finished {
+            eprintln!("Cannot add problems to an archived competition");
+            return Ok(());
+        }
+
+        if comp_data.contains_problem(problem_name) {
+            eprintln!("The problem '{problem_name}' already exists in the competition");
+            return Ok(());
+        }
+        comp_data.add_problem(problem_name);
+
+        eprintln!("Added problem '{problem_name}' to the competition '{comp_name}'");
+
+        Ok(())
+    })
 }
@@ -0,0 +1,333 @@
+//! Check that every problem in a competition is ready before contest day.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use super::{read_competitions, Competitions, COMPETITIONS_FILE};
+use crate::config::Settings;
+use crate::problem::check::{check as check_problem, CheckOptions};
+use crate::problem::checker::PYTHON_CHECKER_FILE;
+use crate::problem::metadata::{CheckerType, ProblemMetadata};
+use crate::problem::problem_location_from_path;
+use crate::problem::run::{find_file_langs, RunLimits, RunnableFile};
+use crate::problem::sync_mappings::get_problem;
+use crate::problem::test::test as problem_test;
+use crate::util::get_project_root;
+
+/// Whether a problem is ready to be used in a competition.
+#[derive(Debug, Default, PartialEq)]
+pub struct ProblemReadiness {
+    pub problem: String,
+    /// The statement passes all lint rules and its examples match the sample tests.
+    pub statement_ok: bool,
+    /// `problem check` found no invalid tests or files.
+    pub tests_ok: bool,
+    /// The reference solution passes every test.
+    pub reference_accepted: bool,
+    /// The custom checker or built-in comparator that judges the problem, or
+    /// `None` if the problem requires a custom checker that is missing.
+    pub checker: Option<String>,
+}
+
+impl ProblemReadiness {
+    pub fn ready(&self) -> bool {
+        self.statement_ok && self.tests_ok && self.reference_accepted && self.checker.is_some()
+    }
+}
+
+/// Describe how the outputs of a problem are judged.
+fn checker_description(settings: &Settings, problem_path: &Path) -> Result<Option<String>> {
+    if problem_path.join(PYTHON_CHECKER_FILE).exists()
+        || !find_file_langs(settings, problem_path, "checker")?.is_empty()
+    {
+        return Ok(Some("custom".to_owned()));
+    }
+
+    match ProblemMetadata::load_or_default(problem_path)?.checker {
+        CheckerType::Builtin(comparator) => Ok(Some(comparator.to_string())),
+        CheckerType::Custom => Ok(None),
+    }
+}
+
+/// Find problems of an unfinished competition that are missing, archived, or
+/// also in another competition that isn't finished.
+fn competition_issues(
+    problems_dir: &Path,
+    competitions: &Competitions,
+    comp_name: &str,
+) -> Result<Vec<String>> {
+    let comp_data = competitions
+        .get(comp_name)
+        .context(format!("Competition '{comp_name}' not found"))?;
+
+    let project_root = get_project_root()?;
+    let mut issues = Vec::new();
+    for problem_name in comp_data.problem_names() {
+        let problem_path = match get_problem(problems_dir, problem_name) {
+            Ok(path) => project_root.join(path),
+            Err(_) => {
+                issues.push(format!("Problem '{problem_name}' doesn't exist"));
+                continue;
+            }
+        };
+
+        // The problems of a finished competition have been archived by `comp finish`,
+        // and may since have been reused in another competition
+        if comp_data.finished {
+            continue;
+        }
+
+        let location = problem_location_from_path(problems_dir, &problem_path)?;
+        if location.status == "archive" {
+            issues.push(format!("Problem '{problem_name}' is archived"));
+        }
+
+        for (other_name, other_data) in competitions {
+            if other_name != comp_name
+                && !other_data.finished
+                && other_data.contains_problem(problem_name)
+            {
+                issues.push(format!(
+                    "Problem '{problem_name}' is also in the unfinished competition '{other_name}'"
+                ));
+            }
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Format the readiness of each problem as a table.
+fn readiness_table(rows: &[ProblemReadiness]) -> String {
+    fn status(ok: bool) -> &'static str {
+        if ok {
+            "OK"
+        } else {
+            "FAIL"
+        }
+    }
+
+    let header = ["Problem", "Statement", "Tests", "Reference", "Checker"];
+    let cells: Vec<[String; 5]> = rows
+        .iter()
+        .map(|row| {
+            [
+                row.problem.clone(),
+                status(row.statement_ok).to_owned(),
+                status(row.tests_ok).to_owned(),
+                if row.reference_accepted { "AC" } else { "FAIL" }.to_owned(),
+                row.checker.clone().unwrap_or("MISSING".to_owned()),
+            ]
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            cells
+                .iter()
+                .map(|row| row[i].len())
+                .chain([header[i].len()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let format_row = |row: &[&str]| {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        line.trim_end().to_owned() + "\n"
+    };
+
+    let mut table = format_row(&header);
+    for row in &cells {
+        table.push_str(&format_row(&row.each_ref().map(String::as_str)));
+    }
+    table
+}
+
+/// Check a problem, and run the reference solution on its tests.
+fn problem_readiness(
+    settings: &Settings,
+    problems_dir: &Path,
+    problem_name: &str,
+    solution_file: &RunnableFile,
+    jobs: usize,
+) -> Result<ProblemReadiness> {
+    let mut readiness = ProblemReadiness {
+        problem: problem_name.to_owned(),
+        ..ProblemReadiness::default()
+    };
+
+    match check_problem(
+        settings,
+        problems_dir.to_path_buf(),
+        problem_name,
+        CheckOptions::default(),
+    ) {
+        Ok(summary) => {
+            readiness.statement_ok = summary.statement_issues == 0;
+            readiness.tests_ok = summary.errors() == 0;
+        }
+        Err(err) => eprintln!("Failed to check problem '{problem_name}': {err:#}"),
+    }
+
+    match problem_test(
+        settings,
+        problems_dir,
+        problem_name,
+        solution_file,
+        RunLimits::default(),
+        None,
+        jobs,
+    ) {
+        Ok(report) => {
+            readiness.reference_accepted = !report.tests.is_empty() && report.failures() == 0
+        }
+        Err(err) => eprintln!("Failed to test problem '{problem_name}': {err:#}"),
+    }
+
+    let problem_path = get_project_root()?.join(get_problem(problems_dir, problem_name)?);
+    readiness.checker = checker_description(settings, &problem_path)?;
+
+    Ok(readiness)
+}
+
+/// Check every problem in the competition and print a readiness table,
+/// failing if any problem is not ready.
+pub fn check(
+    settings: &Settings,
+    problems_dir: &Path,
+    comp_name: &str,
+    solution_file: RunnableFile,
+    jobs: usize,
+) -> Result<Vec<ProblemReadiness>> {
+    let comp_file_path = problems_dir.join(COMPETITIONS_FILE);
+    if !fs::exists(&comp_file_path)? {
+        bail!("Competitions file does not exist");
+    }
+
+    let data = read_competitions(problems_dir)?;
+
+    let issues = competition_issues(problems_dir, &data, comp_name)?;
+    let comp_data = &data[comp_name];
+
+    eprintln!("Checking all problems in competition '{comp_name}'");
+    let mut rows = Vec::new();
+    for problem_name in comp_data.problem_names() {
+        eprintln!("\nChecking problem '{problem_name}'...");
+        match problem_readiness(settings, problems_dir, problem_name, &solution_file, jobs) {
+            Ok(readiness) => rows.push(readiness),
+            Err(err) => {
+                eprintln!("Failed to check problem '{problem_name}': {err:#}");
+                rows.push(ProblemReadiness {
+                    problem: problem_name.to_owned(),
+                    ..ProblemReadiness::default()
+                });
+            }
+        }
+    }
+
+    eprintln!("\nReadiness of '{comp_name}':");
+    eprint!("{}", readiness_table(&rows));
+    for issue in &issues {
+        eprintln!("  ! {issue}");
+    }
+
+    let not_ready = rows.iter().filter(|row| !row.ready()).count();
+    if not_ready > 0 || !issues.is_empty() {
+        bail!(
+            "'{comp_name}' is not ready: {not_ready} problem(s) failed their checks and there are {} competition issue(s)",
+            issues.len()
+        );
+    }
+    eprintln!("All problems in '{comp_name}' are ready");
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{competition_issues, readiness_table, ProblemReadiness};
+    use crate::comp::{CompetitionData, Competitions};
+    use crate::problem::sync_mappings::sync_mappings;
+    use crate::problem::test_support::{create_problem_dir, with_test_project};
+
+    fn competition(finished: bool, problems: &[&str]) -> CompetitionData {
+        let mut data = CompetitionData {
+            finished,
+            ..CompetitionData::default()
+        };
+        for problem in problems {
+            data.add_problem(problem);
+        }
+        data
+    }
+
+    #[test]
+    fn finds_archived_and_shared_problems() {
+        with_test_project(|problems_dir| {
+            create_problem_dir(problems_dir, "new", "easy", "alpha");
+            create_problem_dir(problems_dir, "new", "easy", "beta");
+            create_problem_dir(problems_dir, "archive", "easy", "gamma");
+            sync_mappings(problems_dir).expect("mappings should sync");
+
+            let competitions = Competitions::from([
+                (
+                    "spring".to_owned(),
+                    competition(false, &["alpha", "beta", "gamma", "delta"]),
+                ),
+                ("summer".to_owned(), competition(false, &["beta"])),
+                ("winter".to_owned(), competition(true, &["alpha", "gamma"])),
+            ]);
+
+            assert_eq!(
+                competition_issues(problems_dir, &competitions, "spring")
+                    .expect("issues should be found"),
+                vec![
+                    "Problem 'beta' is also in the unfinished competition 'summer'",
+                    "Problem 'gamma' is archived",
+                    "Problem 'delta' doesn't exist",
+                ]
+            );
+            assert!(competition_issues(problems_dir, &competitions, "winter")
+                .expect("issues should be found")
+                .is_empty());
+            assert!(competition_issues(problems_dir, &competitions, "autumn").is_err());
+        });
+    }
+
+    #[test]
+    fn formats_readiness_table() {
+        let rows = [
+            ProblemReadiness {
+                problem: "two-sum".to_owned(),
+                statement_ok: true,
+                tests_ok: true,
+                reference_accepted: true,
+                checker: Some("exact".to_owned()),
+            },
+            ProblemReadiness {
+                problem: "a".to_owned(),
+                statement_ok: false,
+                tests_ok: true,
+                reference_accepted: false,
+                checker: None,
+            },
+        ];
+
+        assert!(rows[0].ready());
+        assert!(!rows[1].ready());
+        assert_eq!(
+            readiness_table(&rows),
+            "Problem  Statement  Tests  Reference  Checker\n\
+             two-sum  OK         OK     AC         exact\n\
+             a        FAIL       OK     FAIL       MISSING\n"
+        );
+    }
+}
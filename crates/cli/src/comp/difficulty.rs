@@ -0,0 +1,56 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use super::{read_competitions, COMPETITIONS_FILE};
+use crate::problem::difficulty::spread_issues;
+use crate::problem::metadata::ProblemMetadata;
+use crate::problem::sync_mappings::get_problem;
+use crate::util::get_project_root;
+
+/// Show the difficulty of each problem in a competition, and check that the
+/// problem set has a sensible difficulty spread.
+pub fn difficulty(problems_dir: &Path, comp_name: &str) -> Result<()> {
+    let comp_file_path = problems_dir.join(COMPETITIONS_FILE);
+    if !fs::exists(&comp_file_path)? {
+        bail!("Competitions file does not exist");
+    }
+
+    let data = read_competitions(problems_dir)?;
+
+    let comp_data = data
+        .get(comp_name)
+        .context(format!("Competition '{comp_name}' not found"))?;
+
+    let project_root = get_project_root()?;
+    let mut ratings: Vec<(String, Option<u32>)> = Vec::new();
+    for problem_name in comp_data.problem_names() {
+        let problem_path = project_root.join(get_problem(problems_dir, problem_name)?);
+        let metadata = ProblemMetadata::load_or_default(&problem_path)?;
+        ratings.push((problem_name.to_owned(), metadata.difficulty));
+    }
+
+    let mut sorted_ratings = ratings.clone();
+    sorted_ratings.sort_by_key(|(_, rating)| rating.unwrap_or(u32::MAX));
+
+    eprintln!("Problems in '{comp_name}' by difficulty:");
+    for (problem_name, rating) in &sorted_ratings {
+        let rating = rating.map_or("unrated".to_owned(), |r| r.to_string());
+        eprintln!("  - {problem_name}: {rating}");
+    }
+
+    let issues = spread_issues(&ratings);
+    if !issues.is_empty() {
+        for issue in &issues {
+            eprintln!("  ! {issue}");
+        }
+        bail!(
+            "The difficulty spread of '{comp_name}' has {} issue(s)",
+            issues.len()
+        );
+    }
+    eprintln!("The difficulty spread of '{comp_name}' looks good");
+
+    Ok(())
+}
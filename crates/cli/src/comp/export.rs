@@ -0,0 +1,36 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::config::Settings;
+use crate::problem::export::{export as problem_export, ExportFormat};
+
+use super::read_competitions;
+
+/// Export every problem in the competition to a package for the given judge,
+/// each written as `<problem>.zip` in the output folder.
+pub fn export(
+    settings: &Settings,
+    problems_dir: &Path,
+    comp_name: &str,
+    format: ExportFormat,
+    output_dir: &Path,
+) -> Result<()> {
+    let competitions = read_competitions(problems_dir)?;
+    let comp_data = competitions
+        .get(comp_name)
+        .context(format!("Competition '{comp_name}' not found"))?;
+
+    eprintln!("Exporting all problems in competition '{comp_name}'");
+    for problem_name in comp_data.problem_names() {
+        problem_export(
+            settings,
+            problems_dir,
+            problem_name,
+            format,
+            &output_dir.join(format!("{problem_name}.zip")),
+        )?;
+    }
+
+    Ok(())
+}
@@ -1,44 +1,175 @@
-use std::fs::{self, File};
+use std::fs;
 use std::path::Path;
 
 use anyhow::{bail, Context, Result};
-use serde_json::{from_reader, to_writer_pretty};
 
-use crate::problem::archive::archive;
+use crate::problem::archive::{execute_all, undo_all, ProblemMove};
+use crate::problem::sync_mappings::sync_mappings;
 
-use super::{Competitions, COMPETITIONS_FILE};
+use super::{update_competitions, COMPETITIONS_FILE};
 
+/// Finish a competition, archiving all of its problems. Either every problem is
+/// archived and the competition is marked as finished, or nothing changes.
 pub fn finish(problems_dir: &Path, comp_name: &str) -> Result<()> {
     let comp_file_path = problems_dir.join(COMPETITIONS_FILE);
     if !fs::exists(&comp_file_path)? {
         bail!("Competitions file does not exist");
     }
 
-    let comp_file = File::open(&comp_file_path)?;
-    let mut data: Competitions = from_reader(&comp_file)?;
-    let comp_data = data
-        .get_mut(comp_name)
-        .context(format!("Competition '{comp_name}' not found"))?;
+    // The moves that were made, so they can be undone if a later step fails,
+    // including writing the competitions file
+    let mut moved = Vec::new();
+    let result = update_competitions(problems_dir, |data| {
+        let comp_data = data
+            .get_mut(comp_name)
+            .context(format!("Competition '{comp_name}' not found"))?;
 
-    let comp_problems = &comp_data.problems;
+        // Check every move before making any, so that a problem that can't be
+        // archived doesn't leave the others archived in an unfinished competition
+        sync_mappings(problems_dir)?;
+        let mut moves = Vec::new();
+        let mut clashes = Vec::new();
+        for problem in comp_data.problem_names() {
+            match ProblemMove::plan(problems_dir, problem, "archive") {
+                Ok(problem_move) => moves.push(problem_move),
+                Err(err) => clashes.push(format!("{err:#}")),
+            }
+        }
+        if !clashes.is_empty() {
+            bail!(
+                "Cannot finish the competition '{comp_name}':\n{}",
+                clashes.join("\n")
+            );
+        }
 
-    // Archive problems
-    eprintln!("Archiving problems...");
-    for problem in comp_problems {
-        archive(problems_dir, problem)?;
-        eprintln!(" - Archived problem {problem}");
+        eprintln!("Archiving problems...");
+        execute_all(&moves)?;
+        moved = moves;
+        for problem_move in &moved {
+            eprintln!(" - Archived problem {}", problem_move.problem_name);
+        }
+        sync_mappings(problems_dir)?;
+        eprintln!("Archived problems: {}", moved.len());
+
+        comp_data.finished = true;
+
+        eprintln!("Marked competition '{comp_name}' as finished");
+
+        Ok(())
+    });
+
+    match result {
+        Err(err) if !moved.is_empty() => match undo_all(problems_dir, &moved) {
+            Ok(()) => Err(err).context(format!(
+                "Failed to finish the competition '{comp_name}', so the archived problems were moved back"
+            )),
+            Err(undo_err) => Err(err).context(format!(
+                "Failed to finish the competition '{comp_name}': {undo_err:#}"
+            )),
+        },
+        result => result,
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::finish;
+    use crate::comp::{read_competitions, COMPETITIONS_FILE};
+    use crate::problem::archive::unarchive;
+    use crate::problem::sync_mappings::{get_problem, sync_mappings};
+    use crate::problem::test_support::{create_problem_dir, with_test_project};
 
-    eprintln!("Archived problems: {}", &comp_problems.len());
+    #[test]
+    fn archives_nothing_unless_every_problem_can_be_archived() {
+        with_test_project(|problems_dir| {
+            create_problem_dir(problems_dir, "new", "easy", "alpha");
+            create_problem_dir(problems_dir, "archive", "graphs", "beta");
+            sync_mappings(problems_dir).expect("mappings should sync");
+            fs::write(
+                problems_dir.join(COMPETITIONS_FILE),
+                r#"{"spring":{"finished":false,"problems":["alpha","beta","gamma"]}}"#,
+            )
+            .expect("competitions file should be written");
 
-    comp_data.finished = true;
-    let comp_file = File::options()
-        .write(true)
-        .truncate(true)
-        .open(comp_file_path)?;
-    to_writer_pretty(&comp_file, &data)?;
+            let err = finish(problems_dir, "spring").expect_err("finish should fail");
+            assert_eq!(
+                err.to_string(),
+                "Cannot finish the competition 'spring':\n\
+                 Problem 'beta' is already archived\n\
+                 Failed to archive the problem 'gamma'. Does the problem exist?"
+            );
+            assert!(problems_dir.join("new/easy/alpha").is_dir());
+            assert!(
+                !read_competitions(problems_dir).expect("competitions should load")["spring"]
+                    .finished
+            );
 
-    eprintln!("Marked competition '{comp_name}' as finished");
+            unarchive(problems_dir, "beta").expect("beta should be unarchived");
+            create_problem_dir(problems_dir, "new", "easy", "gamma");
+            finish(problems_dir, "spring").expect("competition should finish");
 
-    Ok(())
+            assert!(
+                read_competitions(problems_dir).expect("competitions should load")["spring"]
+                    .finished
+            );
+            for (problem, path) in [
+                ("alpha", "problems/archive/easy/alpha"),
+                ("beta", "problems/archive/graphs/beta"),
+                ("gamma", "problems/archive/easy/gamma"),
+            ] {
+                assert_eq!(
+                    get_problem(problems_dir, problem).expect("problem should exist"),
+                    path
+                );
+            }
+            assert!(!problems_dir.join("new/easy").exists());
+        });
+    }
+
+    #[test]
+    fn moves_problems_back_if_a_later_step_fails() {
+        with_test_project(|problems_dir| {
+            create_problem_dir(problems_dir, "new", "easy", "alpha");
+            create_problem_dir(problems_dir, "new", "graphs", "beta");
+            sync_mappings(problems_dir).expect("mappings should sync");
+            fs::write(
+                problems_dir.join(COMPETITIONS_FILE),
+                r#"{"spring":{"finished":false,"problems":["alpha","beta"]}}"#,
+            )
+            .expect("competitions file should be written");
+
+            // A folder in the way of the backup makes writing the file fail after
+            // the problems are moved, first the mappings and then the competitions
+            for file in ["problem-mappings.json", COMPETITIONS_FILE] {
+                let backup_path = problems_dir.join(format!("{file}.bak"));
+                fs::create_dir(&backup_path).expect("folder should be created");
+
+                let err = finish(problems_dir, "spring").expect_err("finish should fail");
+                assert!(err
+                    .to_string()
+                    .contains("so the archived problems were moved back"));
+                for (problem, path) in [
+                    ("alpha", "problems/new/easy/alpha"),
+                    ("beta", "problems/new/graphs/beta"),
+                ] {
+                    assert_eq!(
+                        get_problem(problems_dir, problem).expect("problem should exist"),
+                        path
+                    );
+                    assert!(problems_dir
+                        .join(path.trim_start_matches("problems/"))
+                        .is_dir());
+                }
+                assert!(!problems_dir.join("archive/easy").exists());
+
+                fs::remove_dir(&backup_path).expect("folder should be removed");
+            }
+            assert!(
+                !read_competitions(problems_dir).expect("competitions should load")["spring"]
+                    .finished
+            );
+        });
+    }
 }
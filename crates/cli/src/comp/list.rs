@@ -1,10 +1,29 @@
-use std::fs::{self, File};
+use std::fs;
 use std::path::Path;
 
 use anyhow::{bail, Context, Result};
-use serde_json::from_reader;
 
-use super::{Competitions, COMPETITIONS_FILE};
+use super::{read_competitions, COMPETITIONS_FILE};
+use crate::problem::metadata::ProblemMetadata;
+use crate::problem::problem_location_from_path;
+use crate::problem::sync_mappings::get_problem;
+use crate::util::get_project_root;
+
+/// Describe a problem by its title, category and difficulty, if known.
+fn describe_problem(problems_dir: &Path, problem_name: &str) -> Result<String> {
+    let problem_path = get_project_root()?.join(get_problem(problems_dir, problem_name)?);
+    let location = problem_location_from_path(problems_dir, &problem_path)?;
+
+    let mut description = format!("{problem_name} [{}]", location.category);
+    if let Some(metadata) = ProblemMetadata::load(&problem_path)? {
+        description.push_str(&format!(": {}", metadata.title));
+        if let Some(difficulty) = metadata.difficulty {
+            description.push_str(&format!(" (difficulty {difficulty})"));
+        }
+    }
+
+    Ok(description)
+}
 
 pub fn list(problems_dir: &Path, comp_name: Option<&String>) -> Result<()> {
     let comp_file_path = problems_dir.join(COMPETITIONS_FILE);
@@ -12,8 +31,7 @@ pub fn list(problems_dir: &Path, comp_name: Option<&String>) -> Result<()> {
         bail!("Competitions file does not exist");
     }
 
-    let comp_file = File::open(&comp_file_path)?;
-    let data: Competitions = from_reader(&comp_file)?;
+    let data = read_competitions(problems_dir)?;
 
     match comp_name {
         Some(name) => {
@@ -21,11 +39,29 @@ pub fn list(problems_dir: &Path, comp_name: Option<&String>) -> Result<()> {
                 .get(name)
                 .context(format!("Competition '{name}' not found"))?;
 
-            // TODO: Print category as well
-            // NOTE: More metadata can be listed once we actually store them
+            if let Some(title) = &comp_data.title {
+                eprintln!("Title: {title}");
+            }
+            if let Some(description) = &comp_data.description {
+                eprintln!("Description: {description}");
+            }
+            if let Some(start) = comp_data.start {
+                eprintln!("Start: {}", start.to_rfc3339());
+            }
+            if let Some(end) = comp_data.end {
+                eprintln!("End: {}", end.to_rfc3339());
+            }
+
             eprintln!("Problems in '{name}':");
-            for p in &comp_data.problems {
-                eprintln!("  - {p}")
+            for problem in &comp_data.problems {
+                let letter = &problem.letter;
+                match describe_problem(problems_dir, &problem.name) {
+                    Ok(description) => eprintln!("  {letter}. {description}"),
+                    Err(err) => eprintln!(
+                        "  {letter}. {} (could not read problem: {err:#})",
+                        problem.name
+                    ),
+                }
             }
             eprintln!("Total problems: {}", comp_data.problems.len());
         }
@@ -1,25 +1,337 @@
 //! CLI commands and helper functions related to competitions.
 
 use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
 
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, FixedOffset};
 use serde::{Deserialize, Serialize};
+use serde_json::{from_value, json, Value};
+
+use crate::store::{read_json, write_json, StoreLock};
 
 pub mod add;
+pub mod check;
 pub mod create;
+pub mod difficulty;
+pub mod export;
 pub mod finish;
 pub mod list;
 pub mod remove;
 pub mod rename;
+pub mod render;
+pub mod reopen;
+pub mod reorder;
+pub mod set;
 pub mod solve;
 pub mod test;
 
 pub const COMPETITIONS_FILE: &str = "competitions.json";
 
 /// Map competition names to their data.
-type Competitions = BTreeMap<String, CompetitionData>;
+pub(crate) type Competitions = BTreeMap<String, CompetitionData>;
+
+/// A problem in a competition, with the letter it is shown under.
+#[derive(Clone, Deserialize, Debug, Serialize, PartialEq)]
+pub(crate) struct CompetitionProblem {
+    pub letter: String,
+    pub name: String,
+}
 
-#[derive(Clone, Deserialize, Debug, Serialize)]
-struct CompetitionData {
+#[derive(Clone, Default, Deserialize, Debug, Serialize, PartialEq)]
+pub(crate) struct CompetitionData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<DateTime<FixedOffset>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<DateTime<FixedOffset>>,
     pub finished: bool,
-    pub problems: Vec<String>,
+    /// Problems in competition order, lettered A, B, C, ...
+    pub problems: Vec<CompetitionProblem>,
+}
+
+impl CompetitionData {
+    /// The names of the problems in competition order.
+    pub fn problem_names(&self) -> impl Iterator<Item = &str> {
+        self.problems.iter().map(|problem| problem.name.as_str())
+    }
+
+    pub fn contains_problem(&self, problem_name: &str) -> bool {
+        self.problem_names().any(|name| name == problem_name)
+    }
+
+    /// Add a problem after the last problem, with the next letter.
+    pub fn add_problem(&mut self, problem_name: &str) {
+        self.problems.push(CompetitionProblem {
+            letter: problem_letter(self.problems.len()),
+            name: problem_name.to_owned(),
+        });
+    }
+
+    /// Remove a problem, moving the problems after it up a letter.
+    pub fn remove_problem(&mut self, problem_name: &str) {
+        self.problems.retain(|problem| problem.name != problem_name);
+        self.reletter();
+    }
+
+    /// Put the problems in the given order and letter them again. Every
+    /// problem of the competition must be given exactly once.
+    pub fn reorder(&mut self, problem_names: &[String]) -> Result<()> {
+        let mut problems = Vec::new();
+        for name in problem_names {
+            if !self.contains_problem(name) {
+                bail!("Problem '{name}' is not in the competition");
+            }
+            if problems.contains(name) {
+                bail!("Problem '{name}' is given more than once");
+            }
+            problems.push(name.to_owned());
+        }
+        let missing: Vec<_> = self
+            .problem_names()
+            .filter(|name| !problems.iter().any(|problem| problem == name))
+            .collect();
+        if !missing.is_empty() {
+            bail!(
+                "Every problem of the competition must be given, but {} are missing",
+                missing.join(", ")
+            );
+        }
+
+        self.problems = problems
+            .into_iter()
+            .map(|name| CompetitionProblem {
+                letter: String::new(),
+                name,
+            })
+            .collect();
+        self.reletter();
+
+        Ok(())
+    }
+
+    fn reletter(&mut self) {
+        for (i, problem) in self.problems.iter_mut().enumerate() {
+            problem.letter = problem_letter(i);
+        }
+    }
+}
+
+/// Get the letter of the problem at the given index, e.g. `A` for the first
+/// problem and `AA` for the 27th.
+pub(crate) fn problem_letter(index: usize) -> String {
+    let mut letter = String::new();
+    let mut n = index + 1;
+    while n > 0 {
+        n -= 1;
+        letter.insert(0, (b'A' + (n % 26) as u8) as char);
+        n /= 26;
+    }
+    letter
+}
+
+/// Upgrade competitions written by older versions, where `problems` was a
+/// sorted list of problem names, keeping the problems in the same order.
+/// Returns whether anything was upgraded.
+fn migrate_competitions(data: &mut Value) -> bool {
+    let Some(competitions) = data.as_object_mut() else {
+        return false;
+    };
+
+    let mut migrated = false;
+    for comp_data in competitions.values_mut() {
+        let Some(problems) = comp_data.get_mut("problems").and_then(Value::as_array_mut) else {
+            continue;
+        };
+        for (i, problem) in problems.iter_mut().enumerate() {
+            if let Value::String(name) = problem {
+                *problem = json!({ "letter": problem_letter(i), "name": name });
+                migrated = true;
+            }
+        }
+    }
+
+    migrated
+}
+
+/// Load the competitions file, upgrading it in memory if it was written by an
+/// older version. Returns whether it was upgraded.
+fn load_competitions(comp_file_path: &Path) -> Result<(Competitions, bool)> {
+    let Some(mut data) = read_json::<Value>(comp_file_path)? else {
+        return Ok((Competitions::new(), false));
+    };
+
+    let migrated = migrate_competitions(&mut data);
+    let competitions =
+        from_value(data).context(format!("Failed to parse {}", comp_file_path.display()))?;
+    Ok((competitions, migrated))
+}
+
+/// Read all competitions, or none if the competitions file does not exist yet.
+/// Files written by older versions are upgraded to the current format.
+pub(crate) fn read_competitions(problems_dir: &Path) -> Result<Competitions> {
+    let (competitions, migrated) = load_competitions(&problems_dir.join(COMPETITIONS_FILE))?;
+    if migrated {
+        update_competitions(problems_dir, |_| Ok(()))?;
+        eprintln!("Upgraded {COMPETITIONS_FILE} to the current format");
+    }
+
+    Ok(competitions)
+}
+
+/// Change the competitions while holding the lock on the competitions file,
+/// then write them back if they changed. Nothing is written if `change` fails.
+pub(crate) fn update_competitions<T>(
+    problems_dir: &Path,
+    change: impl FnOnce(&mut Competitions) -> Result<T>,
+) -> Result<T> {
+    let comp_file_path = problems_dir.join(COMPETITIONS_FILE);
+    let _lock = StoreLock::acquire(&comp_file_path)?;
+
+    let (mut competitions, migrated) = load_competitions(&comp_file_path)?;
+    let original = competitions.clone();
+    let result = change(&mut competitions)?;
+    if migrated || competitions != original || !fs::exists(&comp_file_path)? {
+        write_json(&comp_file_path, &competitions)?;
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    use anyhow::bail;
+
+    use super::{
+        problem_letter, read_competitions, update_competitions, CompetitionData,
+        CompetitionProblem, COMPETITIONS_FILE,
+    };
+
+    fn competition(problems: &[&str]) -> CompetitionData {
+        let mut data = CompetitionData::default();
+        for problem in problems {
+            data.add_problem(problem);
+        }
+        data
+    }
+
+    fn letters(data: &CompetitionData) -> Vec<String> {
+        data.problems
+            .iter()
+            .map(|problem| format!("{}: {}", problem.letter, problem.name))
+            .collect()
+    }
+
+    #[test]
+    fn letters_problems_in_order() {
+        assert_eq!(problem_letter(0), "A");
+        assert_eq!(problem_letter(25), "Z");
+        assert_eq!(problem_letter(26), "AA");
+        assert_eq!(problem_letter(27), "AB");
+    }
+
+    #[test]
+    fn keeps_problem_letters_in_order() {
+        let mut data = competition(&["zeta", "alpha", "mu"]);
+        assert_eq!(letters(&data), vec!["A: zeta", "B: alpha", "C: mu"]);
+
+        data.remove_problem("zeta");
+        assert_eq!(letters(&data), vec!["A: alpha", "B: mu"]);
+
+        data.reorder(&["mu".to_owned(), "alpha".to_owned()])
+            .expect("problems should be reordered");
+        assert_eq!(letters(&data), vec!["A: mu", "B: alpha"]);
+
+        assert!(data.reorder(&["mu".to_owned()]).is_err());
+        assert!(data
+            .reorder(&["mu".to_owned(), "mu".to_owned(), "alpha".to_owned()])
+            .is_err());
+        assert!(data
+            .reorder(&["mu".to_owned(), "alpha".to_owned(), "beta".to_owned()])
+            .is_err());
+    }
+
+    #[test]
+    fn upgrades_old_competitions_file() {
+        let dir = TempDir::new().expect("tempdir should be created");
+        fs::write(
+            dir.path().join(COMPETITIONS_FILE),
+            r#"{"spring":{"finished":false,"problems":["alpha","beta"]}}"#,
+        )
+        .expect("competitions file should be written");
+
+        let competitions = read_competitions(dir.path()).expect("competitions should be read");
+        assert_eq!(
+            competitions["spring"].problems,
+            vec![
+                CompetitionProblem {
+                    letter: "A".to_owned(),
+                    name: "alpha".to_owned(),
+                },
+                CompetitionProblem {
+                    letter: "B".to_owned(),
+                    name: "beta".to_owned(),
+                },
+            ]
+        );
+
+        let contents = fs::read_to_string(dir.path().join(COMPETITIONS_FILE))
+            .expect("competitions file should be read");
+        let upgraded: serde_json::Value =
+            serde_json::from_str(&contents).expect("competitions file should be valid JSON");
+        assert_eq!(
+            upgraded,
+            json!({
+                "spring": {
+                    "finished": false,
+                    "problems": [
+                        { "letter": "A", "name": "alpha" },
+                        { "letter": "B", "name": "beta" },
+                    ],
+                },
+            })
+        );
+        assert_eq!(
+            read_competitions(dir.path()).expect("competitions should be read again"),
+            competitions
+        );
+    }
+
+    #[test]
+    fn writes_competitions_only_when_changed() {
+        let dir = TempDir::new().expect("tempdir should be created");
+        let comp_file_path = dir.path().join(COMPETITIONS_FILE);
+
+        update_competitions(dir.path(), |data| {
+            data.insert("spring".to_owned(), competition(&["alpha"]));
+            Ok(())
+        })
+        .expect("competitions should be updated");
+        assert!(comp_file_path.exists());
+
+        let result: anyhow::Result<()> = update_competitions(dir.path(), |data| {
+            data.insert("summer".to_owned(), competition(&["beta"]));
+            bail!("Something went wrong");
+        });
+        assert!(result.is_err());
+        assert_eq!(
+            read_competitions(dir.path())
+                .expect("competitions should be read")
+                .into_keys()
+                .collect::<Vec<_>>(),
+            vec!["spring"]
+        );
+
+        update_competitions(dir.path(), |_| Ok(())).expect("competitions should be read");
+        assert!(!dir.path().join(format!("{COMPETITIONS_FILE}.bak")).exists());
+    }
 }
@@ -1,13 +1,11 @@
-use std::fs::{self, File};
+use std::fs;
 use std::path::Path;
 
 use anyhow::{bail, Context, Result};
-use serde_json::{from_reader, to_writer_pretty};
 
-use crate::comp::Competitions;
 use crate::problem::sync_mappings::problem_exists;
 
-use super::COMPETITIONS_FILE;
+use super::{update_competitions, COMPETITIONS_FILE};
 
 pub fn remove(problems_dir: &Path, comp_name: &str, problem_name: &str) -> Result<()> {
     let comp_file_path = problems_dir.join(COMPETITIONS_FILE);
@@ -15,30 +13,24 @@ pub fn remove(problems_dir: &Path, comp_name: &str, problem_name: &str) -> Resul
         bail!("Competitions file does not exist");
     }
 
-    let comp_file = File::open(&comp_file_path)?;
-    let mut data: Competitions = from_reader(&comp_file)?;
+    update_competitions(problems_dir, |data| {
+        let comp_data = data
+            .get_mut(comp_name)
+            .context(format!("Competition '{comp_name}' not found"))?;
 
-    let comp_data = data
-        .get_mut(comp_name)
-        .context(format!("Competition '{comp_name}' not found"))?;
+        if !problem_exists(problems_dir, problem_name)? {
+            eprintln!("The problem '{problem_name}' does not exist");
+            return Ok(());
+        }
+        if comp_data.finished {
+            eprintln!("Cannot remove problems from an archived competition");
+            return Ok(());
+        }
 
-    if !problem_exists(problems_dir, problem_name)? {
-        eprintln!("The problem '{problem_name}' does not exist");
-        return Ok(());
-    }
-    if comp_data.finished {
-        eprintln!("Cannot remove problems from an archived competition");
-        return Ok(());
-    }
-
-    comp_data.problems.retain(|p| *p != problem_name);
+        comp_data.remove_problem(problem_name);
 
-    let comp_file = File::options()
-        .write(true)
-        .truncate(true)
-        .open(comp_file_path)?;
-    to_writer_pretty(&comp_file, &data)?;
-    eprintln!("Removed problem '{problem_name}' from the competition '{comp_name}'");
+        eprintln!("Removed problem '{problem_name}' from the competition '{comp_name}'");
 
-    Ok(())
+        Ok(())
+    })
 }
@@ -0,0 +1,42 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::config::Settings;
+use crate::problem::render::{render_statement, write_document};
+use crate::problem::sync_mappings::get_problem;
+use crate::util::get_project_root;
+
+use super::read_competitions;
+
+/// Render the statements of every problem in the competition into a single
+/// booklet, with one problem per page in competition order under its letter.
+pub fn render(
+    settings: &Settings,
+    problems_dir: &Path,
+    comp_name: &str,
+    html_path: &Path,
+    pdf: bool,
+) -> Result<()> {
+    let competitions = read_competitions(problems_dir)?;
+    let comp_data = competitions
+        .get(comp_name)
+        .context(format!("Competition '{comp_name}' not found"))?;
+
+    let project_root = get_project_root()?;
+    let sections = comp_data
+        .problems
+        .iter()
+        .map(|problem| {
+            let problem_path = project_root.join(get_problem(problems_dir, &problem.name)?);
+            render_statement(&problem_path, Some(&problem.letter))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    eprintln!(
+        "Rendering {} problem(s) in competition '{comp_name}'",
+        sections.len()
+    );
+    let title = comp_data.title.as_deref().unwrap_or(comp_name);
+    write_document(settings, title, &sections, html_path, pdf)
+}
@@ -0,0 +1,173 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use crate::problem::archive::{execute_all, undo_all, ProblemMove};
+use crate::problem::problem_location_from_path;
+use crate::problem::sync_mappings::{get_problem, sync_mappings};
+use crate::util::get_project_root;
+
+use super::{update_competitions, COMPETITIONS_FILE};
+
+/// Reopen a finished competition so that its problems can be changed again,
+/// moving its archived problems back to the `new` problems folder.
+pub fn reopen(problems_dir: &Path, comp_name: &str) -> Result<()> {
+    let comp_file_path = problems_dir.join(COMPETITIONS_FILE);
+    if !fs::exists(&comp_file_path)? {
+        bail!("Competitions file does not exist");
+    }
+
+    // The moves that were made, so they can be undone if a later step fails,
+    // including writing the competitions file
+    let mut moved = Vec::new();
+    let result = update_competitions(problems_dir, |data| {
+        let comp_data = data
+            .get(comp_name)
+            .context(format!("Competition '{comp_name}' not found"))?;
+
+        if !comp_data.finished {
+            eprintln!("The competition '{comp_name}' is not finished");
+            return Ok(());
+        }
+
+        // Check every move before making any, so that a name clash doesn't leave
+        // some of the problems unarchived
+        sync_mappings(problems_dir)?;
+        let project_root = get_project_root()?;
+        let mut moves = Vec::new();
+        let mut clashes = Vec::new();
+        for problem in comp_data.problem_names() {
+            let problem_path = project_root.join(get_problem(problems_dir, problem)?);
+            if problem_location_from_path(problems_dir, &problem_path)?.status != "archive" {
+                eprintln!(" - Problem {problem} is not archived, skipping");
+                continue;
+            }
+
+            match ProblemMove::plan(problems_dir, problem, "new") {
+                Ok(problem_move) => moves.push(problem_move),
+                Err(err) => clashes.push(format!("{err:#}")),
+            }
+
+            for (other_name, other_data) in data.iter() {
+                if other_name != comp_name
+                    && other_data.finished
+                    && other_data.contains_problem(problem)
+                {
+                    eprintln!("Warning: Problem '{problem}' was also used in the finished competition '{other_name}'");
+                }
+            }
+        }
+        if !clashes.is_empty() {
+            bail!(
+                "Cannot reopen the competition '{comp_name}':\n{}",
+                clashes.join("\n")
+            );
+        }
+
+        eprintln!("Unarchiving problems...");
+        execute_all(&moves)?;
+        moved = moves;
+        for problem_move in &moved {
+            eprintln!(" - Unarchived problem {}", problem_move.problem_name);
+        }
+        sync_mappings(problems_dir)?;
+        eprintln!("Unarchived problems: {}", moved.len());
+
+        if let Some(comp_data) = data.get_mut(comp_name) {
+            comp_data.finished = false;
+        }
+
+        eprintln!("Reopened competition '{comp_name}'");
+
+        Ok(())
+    });
+
+    match result {
+        Err(err) if !moved.is_empty() => match undo_all(problems_dir, &moved) {
+            Ok(()) => Err(err).context(format!(
+                "Failed to reopen the competition '{comp_name}', so the unarchived problems were moved back"
+            )),
+            Err(undo_err) => Err(err).context(format!(
+                "Failed to reopen the competition '{comp_name}': {undo_err:#}"
+            )),
+        },
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::reopen;
+    use crate::comp::finish::finish;
+    use crate::comp::{read_competitions, COMPETITIONS_FILE};
+    use crate::problem::sync_mappings::{get_problem, sync_mappings};
+    use crate::problem::test_support::{create_problem_dir, with_test_project};
+
+    #[test]
+    fn reopens_finished_competition() {
+        with_test_project(|problems_dir| {
+            create_problem_dir(problems_dir, "new", "easy", "alpha");
+            create_problem_dir(problems_dir, "new", "graphs", "beta");
+            sync_mappings(problems_dir).expect("mappings should sync");
+            fs::write(
+                problems_dir.join(COMPETITIONS_FILE),
+                r#"{"spring":{"finished":false,"problems":["alpha","beta"]}}"#,
+            )
+            .expect("competitions file should be written");
+
+            finish(problems_dir, "spring").expect("competition should finish");
+            assert_eq!(
+                get_problem(problems_dir, "beta").expect("beta should exist"),
+                "problems/archive/graphs/beta"
+            );
+
+            // A new problem with the same name blocks reopening, and nothing is moved
+            create_problem_dir(problems_dir, "new", "graphs", "beta");
+            assert!(reopen(problems_dir, "spring").is_err());
+            assert!(problems_dir.join("archive/easy/alpha").is_dir());
+            assert!(
+                read_competitions(problems_dir).expect("competitions should load")["spring"]
+                    .finished
+            );
+
+            fs::remove_dir_all(problems_dir.join("new/graphs/beta"))
+                .expect("clashing problem should be removed");
+            sync_mappings(problems_dir).expect("mappings should sync");
+
+            // If the competitions file can't be written after the moves, they are undone
+            let backup_path = problems_dir.join(format!("{COMPETITIONS_FILE}.bak"));
+            fs::remove_file(&backup_path).expect("backup should be removed");
+            fs::create_dir(&backup_path).expect("folder should be created");
+            let err = reopen(problems_dir, "spring").expect_err("reopen should fail");
+            assert!(err
+                .to_string()
+                .contains("so the unarchived problems were moved back"));
+            assert_eq!(
+                get_problem(problems_dir, "beta").expect("beta should exist"),
+                "problems/archive/graphs/beta"
+            );
+            assert!(problems_dir.join("archive/easy/alpha").is_dir());
+            assert!(!problems_dir.join("new/easy").exists());
+            fs::remove_dir(&backup_path).expect("folder should be removed");
+
+            reopen(problems_dir, "spring").expect("competition should reopen");
+
+            assert!(
+                !read_competitions(problems_dir).expect("competitions should load")["spring"]
+                    .finished
+            );
+            assert_eq!(
+                get_problem(problems_dir, "alpha").expect("alpha should exist"),
+                "problems/new/easy/alpha"
+            );
+            assert_eq!(
+                get_problem(problems_dir, "beta").expect("beta should exist"),
+                "problems/new/graphs/beta"
+            );
+            assert!(!problems_dir.join("archive/easy").exists());
+        });
+    }
+}
@@ -0,0 +1,34 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use super::{update_competitions, COMPETITIONS_FILE};
+
+/// Put the problems of a competition in a new order, lettering them A, B, C,
+/// ... in that order. Every problem of the competition must be given.
+pub fn reorder(problems_dir: &Path, comp_name: &str, problem_names: &[String]) -> Result<()> {
+    let comp_file_path = problems_dir.join(COMPETITIONS_FILE);
+    if !fs::exists(&comp_file_path)? {
+        bail!("Competitions file does not exist");
+    }
+
+    update_competitions(problems_dir, |data| {
+        let comp_data = data
+            .get_mut(comp_name)
+            .context(format!("Competition '{comp_name}' not found"))?;
+
+        if comp_data.finished {
+            eprintln!("Cannot reorder problems of an archived competition");
+            return Ok(());
+        }
+
+        comp_data.reorder(problem_names)?;
+        eprintln!("New order of the problems in '{comp_name}':");
+        for problem in &comp_data.problems {
+            eprintln!("  {}. {}", problem.letter, problem.name);
+        }
+
+        Ok(())
+    })
+}
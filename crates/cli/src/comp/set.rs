@@ -0,0 +1,135 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, FixedOffset};
+
+use super::{update_competitions, COMPETITIONS_FILE};
+
+/// Changes to the details of a competition. Fields that are `None` are left
+/// as they are, and an empty string clears a field.
+#[derive(Debug, Default)]
+pub struct CompetitionDetails {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// Start time in RFC 3339 format, e.g. `2025-03-01T10:00:00+10:30`.
+    pub start: Option<String>,
+    /// End time in RFC 3339 format.
+    pub end: Option<String>,
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_owned())
+}
+
+fn parse_time(value: &str) -> Result<Option<DateTime<FixedOffset>>> {
+    let Some(value) = non_empty(value) else {
+        return Ok(None);
+    };
+
+    let time = DateTime::parse_from_rfc3339(&value).context(format!(
+        "Invalid time '{value}'. Use the RFC 3339 format, e.g. 2025-03-01T10:00:00+10:30"
+    ))?;
+    Ok(Some(time))
+}
+
+/// Set the title, description, start or end time of a competition.
+pub fn set(problems_dir: &Path, comp_name: &str, details: &CompetitionDetails) -> Result<()> {
+    let comp_file_path = problems_dir.join(COMPETITIONS_FILE);
+    if !fs::exists(&comp_file_path)? {
+        bail!("Competitions file does not exist");
+    }
+
+    update_competitions(problems_dir, |data| {
+        let comp_data = data
+            .get_mut(comp_name)
+            .context(format!("Competition '{comp_name}' not found"))?;
+
+        if let Some(title) = &details.title {
+            comp_data.title = non_empty(title);
+        }
+        if let Some(description) = &details.description {
+            comp_data.description = non_empty(description);
+        }
+        if let Some(start) = &details.start {
+            comp_data.start = parse_time(start)?;
+        }
+        if let Some(end) = &details.end {
+            comp_data.end = parse_time(end)?;
+        }
+        if let (Some(start), Some(end)) = (comp_data.start, comp_data.end) {
+            if end <= start {
+                bail!(
+                    "The competition must end after it starts, but it starts at {} and ends at {}",
+                    start.to_rfc3339(),
+                    end.to_rfc3339()
+                );
+            }
+        }
+
+        eprintln!("Updated the details of competition '{comp_name}'");
+
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{set, CompetitionDetails};
+    use crate::comp::{read_competitions, COMPETITIONS_FILE};
+    use crate::problem::test_support::with_test_project;
+
+    #[test]
+    fn sets_and_clears_competition_details() {
+        with_test_project(|problems_dir| {
+            fs::write(
+                problems_dir.join(COMPETITIONS_FILE),
+                r#"{"spring":{"finished":false,"problems":[]}}"#,
+            )
+            .expect("competitions file should be written");
+
+            let details = CompetitionDetails {
+                title: Some("Spring Contest".to_owned()),
+                start: Some("2025-03-01T10:00:00+10:30".to_owned()),
+                end: Some("2025-03-01T15:00:00+10:30".to_owned()),
+                ..CompetitionDetails::default()
+            };
+            set(problems_dir, "spring", &details).expect("details should be set");
+
+            let competitions = read_competitions(problems_dir).expect("competitions should load");
+            let spring = &competitions["spring"];
+            assert_eq!(spring.title.as_deref(), Some("Spring Contest"));
+            assert_eq!(
+                spring.start.map(|start| start.to_rfc3339()).as_deref(),
+                Some("2025-03-01T10:00:00+10:30")
+            );
+
+            let details = CompetitionDetails {
+                title: Some(String::new()),
+                end: Some("2025-03-01T09:00:00+10:30".to_owned()),
+                ..CompetitionDetails::default()
+            };
+            assert!(set(problems_dir, "spring", &details).is_err());
+
+            let details = CompetitionDetails {
+                title: Some(String::new()),
+                ..CompetitionDetails::default()
+            };
+            set(problems_dir, "spring", &details).expect("title should be cleared");
+            let competitions = read_competitions(problems_dir).expect("competitions should load");
+            assert_eq!(competitions["spring"].title, None);
+            assert!(set(
+                problems_dir,
+                "spring",
+                &CompetitionDetails {
+                    start: Some("tomorrow".to_owned()),
+                    ..CompetitionDetails::default()
+                }
+            )
+            .is_err());
+        });
+    }
+}
@@ -1,43 +1,55 @@
-use std::fs::{self, File};
+use std::fs;
 use std::path::Path;
 
 use anyhow::{bail, Context, Result};
-use serde_json::from_reader;
 
 use crate::config::Settings;
-use crate::problem::run::RunnableFile;
+use crate::problem::comparator::Comparator;
+use crate::problem::report::ProblemReport;
+use crate::problem::run::{RunLimits, RunnableFile};
 use crate::problem::test::test as problem_test;
 
-use super::{Competitions, COMPETITIONS_FILE};
+use super::{read_competitions, COMPETITIONS_FILE};
 
+/// Run tests on every problem in the competition, returning a report per problem.
+///
+/// Problems are tested one after another, with up to `jobs` test cases of each
+/// problem run at the same time.
 pub fn test(
     settings: &Settings,
     problems_dir: &Path,
     comp_name: &str,
     solution_file: RunnableFile,
-) -> Result<()> {
+    limits: RunLimits,
+    comparator: Option<Comparator>,
+    jobs: usize,
+) -> Result<Vec<ProblemReport>> {
     let comp_file_path = problems_dir.join(COMPETITIONS_FILE);
     if !fs::exists(&comp_file_path)? {
         bail!("Competitions file does not exist");
     }
 
-    let comp_file = File::open(&comp_file_path)?;
-    let data: Competitions = from_reader(&comp_file)?;
+    let data = read_competitions(problems_dir)?;
 
     let comp_data = data
         .get(comp_name)
         .context(format!("Competition '{comp_name}' not found"))?;
 
     eprintln!("Testing all problems in competition '{comp_name}'");
-    for problem_name in &comp_data.problems {
+    let mut reports = Vec::new();
+    for problem_name in comp_data.problem_names() {
         eprintln!("\nRunning for problem '{problem_name}'...");
-        problem_test(
+        let report = problem_test(
             settings,
             problems_dir,
-            problem_name.as_str(),
+            problem_name,
             &solution_file,
+            limits,
+            comparator,
+            jobs,
         )?;
+        reports.push(report);
     }
 
-    Ok(())
+    Ok(reports)
 }
@@ -20,6 +20,12 @@ pub struct Settings {
     pub version: String,
     pub problems_dir: String,
     pub problem: Problem,
+    #[serde(default)]
+    pub publish: Publish,
+    #[serde(default)]
+    pub sync: SyncRemote,
+    #[serde(default)]
+    pub render: Render,
 }
 /// Settings specific to problem configuration.
 #[allow(unused)]
@@ -37,6 +43,35 @@ pub struct LangSolution {
     pub run_command: Option<Vec<String>>,
 }
 
+/// Settings for publishing problems to an online judge.
+#[allow(unused)]
+#[derive(Debug, Default, Deserialize)]
+pub struct Publish {
+    /// URL that problem archives are uploaded to.
+    pub endpoint: Option<String>,
+    /// Name of the environment variable holding the token sent with uploads.
+    pub token_env: Option<String>,
+}
+
+/// Settings for syncing problems with a remote problem store.
+#[allow(unused)]
+#[derive(Debug, Default, Deserialize)]
+pub struct SyncRemote {
+    /// URL of the remote problem store.
+    pub endpoint: Option<String>,
+    /// Name of the environment variable holding the token sent with requests.
+    pub token_env: Option<String>,
+}
+
+/// Settings for rendering problem statements.
+#[allow(unused)]
+#[derive(Debug, Default, Deserialize)]
+pub struct Render {
+    /// Command that turns a rendered HTML file into a PDF, with `@html_file`
+    /// and `@pdf_file` replaced by the file paths.
+    pub pdf_command: Option<Vec<String>>,
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Settings {
@@ -47,6 +82,9 @@ impl Default for Settings {
                 default_generator_lang: "py".to_owned(),
                 solution: HashMap::new(),
             },
+            publish: Publish::default(),
+            sync: SyncRemote::default(),
+            render: Render::default(),
         }
     }
 }
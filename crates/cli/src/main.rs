@@ -11,6 +11,7 @@ mod errors;
 mod paths;
 mod problem;
 mod publish;
+mod store;
 mod suggest;
 mod sync;
 mod util;
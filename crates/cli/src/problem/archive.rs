@@ -1,64 +1,182 @@
-//! Archive a problem (e.g. problems that have been used in a competition).
+//! Archive a problem (e.g. problems that have been used in a competition), or
+//! move an archived problem back to the `new` problems folder.
 
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 
+use crate::comp::read_competitions;
 use crate::paths::resolve_stored_path;
 use crate::problem::{problem_location_from_path, remove_dir_if_empty};
 use crate::util::get_project_root;
 
 use super::sync_mappings::{get_problem, sync_mappings};
 
-/// Archive problems by moving them from the `new` to the `archive`
-/// problems folder.
-pub fn archive(problems_dir: &Path, problem_name: &str) -> Result<()> {
-    sync_mappings(problems_dir)?;
+/// A move of a problem between the `new` and `archive` problems folders,
+/// keeping its category. The move is checked when it is planned, so that
+/// several moves can be checked before any of them are made.
+#[derive(Debug)]
+pub(crate) struct ProblemMove {
+    pub problem_name: String,
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+impl ProblemMove {
+    /// Plan to move a problem to the given status folder (`new` or `archive`),
+    /// checking that it isn't already there and that no problem with the same
+    /// name is in the way. The problem mappings must be in sync.
+    pub fn plan(problems_dir: &Path, problem_name: &str, status: &str) -> Result<Self> {
+        let action = match status {
+            "archive" => "archive",
+            _ => "unarchive",
+        };
+
+        let problem_path = match get_problem(problems_dir, problem_name) {
+            Ok(val) => val,
+            Err(_) => {
+                bail!("Failed to {action} the problem '{problem_name}'. Does the problem exist?");
+            }
+        };
+
+        let project_root = get_project_root()?;
+        let current_problem_path = resolve_stored_path(&project_root, &problem_path);
+        let location = problem_location_from_path(problems_dir, &current_problem_path)?;
+
+        if location.status == status {
+            match status {
+                "archive" => bail!("Problem '{problem_name}' is already archived"),
+                _ => bail!("Problem '{problem_name}' is not archived"),
+            }
+        }
 
-    let problem_path = match get_problem(problems_dir, problem_name) {
-        Ok(val) => val,
-        Err(_) => {
-            bail!("Failed to archive the problem '{problem_name}'. Does the problem exist?");
+        let updated_problem_path = problems_dir
+            .join(status)
+            .join(&location.category)
+            .join(&location.problem_name);
+
+        if fs::exists(&updated_problem_path)? {
+            bail!(
+                "Cannot {action}: a problem named '{problem_name}' already exists in {status}/{}",
+                location.category
+            );
         }
-    };
 
-    let project_root = get_project_root()?;
-    let current_problem_path = resolve_stored_path(&project_root, &problem_path);
-    let location = problem_location_from_path(problems_dir, &current_problem_path)?;
+        Ok(Self {
+            problem_name: problem_name.to_owned(),
+            from: current_problem_path,
+            to: updated_problem_path,
+        })
+    }
+
+    /// Move the problem, removing its old category folder if it is now empty.
+    /// The problem mappings must be synced afterwards.
+    pub fn execute(&self) -> Result<()> {
+        move_dir(&self.from, &self.to)
+    }
 
-    if location.status == "archive" {
-        bail!("Problem '{problem_name}' is already archived");
+    /// Move the problem back to where it was before the move was made.
+    pub fn undo(&self) -> Result<()> {
+        move_dir(&self.to, &self.from)
     }
+}
+
+/// Undo moves in reverse order, returning a description of every move that
+/// could not be undone.
+fn undo_moves(moves: &[ProblemMove]) -> Vec<String> {
+    moves
+        .iter()
+        .rev()
+        .filter_map(|problem_move| problem_move.undo().err())
+        .map(|err| format!("{err:#}"))
+        .collect()
+}
 
-    let updated_problem_path = problems_dir
-        .join("archive")
-        .join(&location.category)
-        .join(&location.problem_name);
+/// Make all of the moves, or none of them: if a move fails, the moves that
+/// were already made are undone. The problem mappings must be synced afterwards.
+pub(crate) fn execute_all(moves: &[ProblemMove]) -> Result<()> {
+    for (i, problem_move) in moves.iter().enumerate() {
+        if let Err(err) = problem_move.execute() {
+            let undo_errors = undo_moves(&moves[..i]);
+            if !undo_errors.is_empty() {
+                return Err(err).context(format!(
+                    "Failed to move problem '{}', and some problems could not be moved back:\n{}",
+                    problem_move.problem_name,
+                    undo_errors.join("\n")
+                ));
+            }
+            return Err(err).context(format!(
+                "Failed to move problem '{}', so no problems were moved",
+                problem_move.problem_name
+            ));
+        }
+    }
 
-    if fs::exists(&updated_problem_path)? {
+    Ok(())
+}
+
+/// Undo moves made by [`execute_all`] because a later step failed, and sync
+/// the problem mappings again.
+pub(crate) fn undo_all(problems_dir: &Path, moves: &[ProblemMove]) -> Result<()> {
+    let undo_errors = undo_moves(moves);
+    if !undo_errors.is_empty() {
         bail!(
-            "Cannot archive: a problem named '{problem_name}' already exists in category '{}'",
-            location.category
+            "Some problems could not be moved back:\n{}",
+            undo_errors.join("\n")
         );
     }
+    sync_mappings(problems_dir)
+}
 
-    if let Some(parent) = updated_problem_path.parent() {
+fn move_dir(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
         fs::create_dir_all(parent)?;
     }
 
-    fs::rename(&current_problem_path, &updated_problem_path)?;
-    remove_dir_if_empty(&problems_dir.join("new").join(&location.category))?;
+    fs::rename(from, to).context(format!(
+        "Failed to move {} to {}",
+        from.display(),
+        to.display()
+    ))?;
+    if let Some(parent) = from.parent() {
+        remove_dir_if_empty(parent)?;
+    }
+
+    Ok(())
+}
+
+/// Archive problems by moving them from the `new` to the `archive`
+/// problems folder.
+pub fn archive(problems_dir: &Path, problem_name: &str) -> Result<()> {
+    sync_mappings(problems_dir)?;
+    ProblemMove::plan(problems_dir, problem_name, "archive")?.execute()?;
     sync_mappings(problems_dir)?;
 
     Ok(())
 }
 
+/// Unarchive a problem by moving it from the `archive` back to the `new`
+/// problems folder, e.g. to use it in another competition.
+pub fn unarchive(problems_dir: &Path, problem_name: &str) -> Result<()> {
+    sync_mappings(problems_dir)?;
+    ProblemMove::plan(problems_dir, problem_name, "new")?.execute()?;
+    sync_mappings(problems_dir)?;
+
+    for (comp_name, comp_data) in read_competitions(problems_dir)? {
+        if comp_data.finished && comp_data.contains_problem(problem_name) {
+            eprintln!("Warning: Problem '{problem_name}' was used in the finished competition '{comp_name}'");
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
 
-    use super::archive;
+    use super::{archive, execute_all, unarchive, ProblemMove};
     use crate::problem::sync_mappings::{get_problem, sync_mappings};
     use crate::problem::test_support::{create_problem_dir, with_test_project};
 
@@ -99,4 +217,67 @@ mod tests {
             assert!(!fs::exists(problems_dir.join("new").join("easy")).unwrap());
         });
     }
+
+    #[test]
+    fn unarchives_problem_unless_name_clashes() {
+        with_test_project(|problems_dir| {
+            create_problem_dir(problems_dir, "new", "easy", "two-sum");
+            sync_mappings(problems_dir).expect("mappings should sync");
+
+            assert!(unarchive(problems_dir, "two-sum").is_err());
+            archive(problems_dir, "two-sum").expect("archive should succeed");
+            assert!(archive(problems_dir, "two-sum").is_err());
+
+            unarchive(problems_dir, "two-sum").expect("unarchive should succeed");
+            assert_eq!(
+                get_problem(problems_dir, "two-sum").expect("mapping should be updated"),
+                "problems/new/easy/two-sum"
+            );
+            assert!(!fs::exists(problems_dir.join("archive").join("easy")).unwrap());
+
+            archive(problems_dir, "two-sum").expect("archive should succeed");
+            create_problem_dir(problems_dir, "new", "easy", "two-sum");
+            let err = unarchive(problems_dir, "two-sum").expect_err("name clash should fail");
+            assert_eq!(
+                err.to_string(),
+                "Cannot unarchive: a problem named 'two-sum' already exists in new/easy"
+            );
+            assert!(problems_dir
+                .join("archive")
+                .join("easy")
+                .join("two-sum")
+                .is_dir());
+        });
+    }
+
+    #[test]
+    fn undoes_moves_when_one_fails() {
+        with_test_project(|problems_dir| {
+            create_problem_dir(problems_dir, "new", "easy", "alpha");
+            create_problem_dir(problems_dir, "new", "graphs", "beta");
+            sync_mappings(problems_dir).expect("mappings should sync");
+
+            let moves = vec![
+                ProblemMove::plan(problems_dir, "alpha", "archive").expect("alpha should move"),
+                ProblemMove::plan(problems_dir, "beta", "archive").expect("beta should move"),
+            ];
+            // A file where the category folder should be makes the second move fail
+            fs::create_dir_all(problems_dir.join("archive")).expect("archive should be created");
+            fs::write(problems_dir.join("archive").join("graphs"), "")
+                .expect("file should be written");
+
+            let err = execute_all(&moves).expect_err("second move should fail");
+            assert_eq!(
+                err.to_string(),
+                "Failed to move problem 'beta', so no problems were moved"
+            );
+            assert!(problems_dir.join("new").join("easy").join("alpha").is_dir());
+            assert!(problems_dir
+                .join("new")
+                .join("graphs")
+                .join("beta")
+                .is_dir());
+            assert!(!fs::exists(problems_dir.join("archive").join("easy")).unwrap());
+        });
+    }
 }
@@ -1,29 +1,77 @@
 //! Validate the folder structure and files for a problem.
-//!
-//! TODO: Add linting and formatting checks
 
 use std::collections::HashMap;
+use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use regex::Regex;
+use uuid::Uuid;
 
+use crate::config::Settings;
+use crate::problem::checker::{OutputChecker, PYTHON_CHECKER_FILE};
+use crate::problem::lint::lint;
+use crate::problem::metadata::{ProblemMetadata, PROBLEM_METADATA_FILE};
+use crate::problem::run::{compile_file, RunCommand, RunnableCategory, RunnableFile};
+use crate::problem::solve::{SolveStamp, SOLVE_STAMP_FILE};
+use crate::problem::statement::check_samples;
 use crate::problem::sync_mappings::get_problem;
+use crate::problem::validate::{validate_tests, Validator};
+use crate::problem::verdict::Verdict;
 use crate::problem::PROBLEM_NAME_REGEX_PATTERN;
-use crate::util::{get_files_in_directory, get_project_root, is_file_empty};
+use crate::util::{
+    get_files_in_directory_recursive, get_input_files_in_directory, get_project_root, is_file_empty,
+};
 
 static REQUIRED_FILES: &[&str] = &["problem.md", "solutions", "tests"];
 
+/// Folders in a problem folder that hold source files, one per language.
+static SOURCE_DIRS: &[&str] = &["solutions", "generators"];
+
+/// Headers that checkers and validators include, e.g. from a Polygon import.
+pub(crate) static SUPPORT_HEADERS: &[&str] = &["testlib.h"];
+
+/// Build artefacts that are left behind in the problem folder when a run is
+/// interrupted.
+static TOP_LEVEL_ARTEFACTS: &[&str] = &["checker.out", "validator.out", "__pycache__"];
+
+/// What [`check`] does besides the default checks.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CheckOptions {
+    /// Remove build artefacts.
+    pub fix: bool,
+    /// Run the reference solution on every test to find stale outputs.
+    pub deep: bool,
+}
+
+/// The problems found by [`check`].
+#[derive(Debug, Default, PartialEq)]
+pub struct CheckSummary {
+    pub invalid_tests: usize,
+    /// Missing reference solutions and generators that don't compile.
+    pub invalid_files: usize,
+    pub warnings: usize,
+    /// Lint issues and examples that don't match the sample tests, which are
+    /// also counted as warnings and invalid tests.
+    pub statement_issues: usize,
+}
+
+impl CheckSummary {
+    /// The number of problems that must be fixed.
+    pub fn errors(&self) -> usize {
+        self.invalid_tests + self.invalid_files
+    }
+}
+
 struct TestData {
     input_exists: bool,
     output_exists: bool,
 }
 
 /// Verify that there are no missing folders or files. If the file/folder
-/// doesn't exist, it will return the one that is missing.
-///
-/// NOTE: This only checks the top-level so far.
+/// doesn't exist, it will return the one that is missing. The contents of the
+/// folders are checked by [`check_folder_contents`].
 fn valid_folder_structure(problem_dir: &PathBuf) -> Result<(&str, bool)> {
     for file in REQUIRED_FILES {
         if !get_project_root()?
@@ -38,8 +86,229 @@ fn valid_folder_structure(problem_dir: &PathBuf) -> Result<(&str, bool)> {
     Ok(("", true))
 }
 
-/// Check that no files or tests are missing, and that the problem name is valid
-pub fn check(problems_dir: PathBuf, problem_name: &str) -> Result<()> {
+/// Check if a file is a build artefact, given its path relative to the problem folder.
+pub(crate) fn is_artefact(relative_path: &str) -> bool {
+    if TOP_LEVEL_ARTEFACTS.contains(&relative_path) {
+        return true;
+    }
+    match relative_path.split_once('/') {
+        Some((dir, file)) if SOURCE_DIRS.contains(&dir) => {
+            file.ends_with(".out") || file == "__pycache__"
+        }
+        _ => false,
+    }
+}
+
+/// Check if a top-level file or folder of a problem is one that we know about.
+fn is_known_top_level_file(settings: &Settings, name: &str) -> bool {
+    if name.starts_with('.')
+        || REQUIRED_FILES.contains(&name)
+        || SOURCE_DIRS.contains(&name)
+        || name == PROBLEM_METADATA_FILE
+        || name == PYTHON_CHECKER_FILE
+        || SUPPORT_HEADERS.contains(&name)
+    {
+        return true;
+    }
+
+    match name.split_once('.') {
+        Some(("checker" | "validator", lang)) => settings.problem.solution.contains_key(lang),
+        _ => false,
+    }
+}
+
+/// Get the names of the entries in a folder, or none if it doesn't exist.
+fn dir_entries(path: &Path) -> Result<Vec<String>> {
+    if !path.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut names = Vec::new();
+    for entry in fs::read_dir(path).context(format!("Failed to read {}", path.display()))? {
+        if let Some(name) = entry?.file_name().to_str() {
+            names.push(name.to_owned());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Check the files in the problem folder and its `solutions/` and `generators/`
+/// folders: that there is a reference solution in every configured language,
+/// that the generators compile, and that there are no build artefacts or
+/// unknown files. If `fix` is set, build artefacts are removed.
+fn check_folder_contents(
+    settings: &Settings,
+    problem_path: &Path,
+    fix: bool,
+    summary: &mut CheckSummary,
+) -> Result<()> {
+    let mut files = Vec::new();
+    for name in dir_entries(problem_path)? {
+        if SOURCE_DIRS.contains(&name.as_str()) {
+            for file in dir_entries(&problem_path.join(&name))? {
+                files.push(format!("{name}/{file}"));
+            }
+        }
+        files.push(name);
+    }
+
+    let mut solution_langs = Vec::new();
+    for file in &files {
+        if is_artefact(file) {
+            if fix {
+                let path = problem_path.join(file);
+                if path.is_dir() {
+                    fs::remove_dir_all(&path)
+                } else {
+                    fs::remove_file(&path)
+                }
+                .context(format!("Failed to remove {}", path.display()))?;
+                eprintln!("Removed build artefact `{file}`");
+            } else {
+                eprintln!("Warning: `{file}` is a build artefact. Run with `--fix` to remove it");
+                summary.warnings += 1;
+            }
+            continue;
+        }
+
+        let known = match file.split_once('/') {
+            Some((dir, name)) => {
+                let lang = Path::new(name).extension().and_then(|s| s.to_str());
+                match lang {
+                    Some(lang) if settings.problem.solution.contains_key(lang) => {
+                        if dir == "solutions" {
+                            solution_langs.push(lang.to_owned());
+                        }
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            None => is_known_top_level_file(settings, file),
+        };
+        if !known {
+            eprintln!("Warning: Unknown file `{file}`");
+            summary.warnings += 1;
+        }
+    }
+
+    // One reference solution is needed to generate outputs, and one in every
+    // configured language lets tests be run in any of them
+    let mut configured_langs: Vec<_> = settings.problem.solution.keys().collect();
+    configured_langs.sort();
+    if solution_langs.is_empty() {
+        eprintln!("There is no reference solution in `solutions/`");
+        summary.invalid_files += 1;
+    } else {
+        for lang in configured_langs {
+            if !solution_langs.contains(lang) {
+                eprintln!(
+                    "Warning: There is no reference solution in `{lang}` (`solutions/*.{lang}`)"
+                );
+                summary.warnings += 1;
+            }
+        }
+    }
+
+    for file in files.iter().filter(|file| file.starts_with("generators/")) {
+        let path = problem_path.join(file);
+        let Some(lang) = path.extension().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if !path.is_file() || !settings.problem.solution.contains_key(lang) {
+            continue;
+        }
+
+        let bin_file = env::temp_dir().join(format!("aucpl-generator-{}", Uuid::new_v4()));
+        let result = compile_file(settings, &path, lang, &bin_file);
+        if bin_file.exists() {
+            fs::remove_file(&bin_file).context("Failed to remove binary file")?;
+        }
+        match result? {
+            Some(output) => {
+                eprintln!(
+                    "Generator `{file}` failed to compile:\n{}",
+                    output.trim_end()
+                );
+                summary.invalid_files += 1;
+            }
+            None => eprintln!("Generator `{file}` compiles"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Run the reference solution on every test and count the outputs that it
+/// doesn't pass, judged by the problem's checker or comparator.
+fn check_outputs(
+    settings: &Settings,
+    problem_path: &Path,
+    solution_file: &RunnableFile,
+) -> Result<usize> {
+    let run_command = RunCommand::new(
+        settings,
+        problem_path,
+        solution_file,
+        problem_path.join("solutions/solution.out"),
+        problem_path.join(format!("{solution_file}")),
+    )?;
+    let checker = OutputChecker::for_problem(
+        settings,
+        problem_path,
+        ProblemMetadata::load_or_default(problem_path)?.checker,
+    )?;
+    eprintln!("Using {}", checker.description());
+
+    let mut stale_outputs = 0;
+    for test_file in get_input_files_in_directory(problem_path.join("tests"))? {
+        let test_name = test_file.trim_end_matches(".in");
+        let input_path = problem_path.join(format!("tests/{test_file}"));
+        let output_path = problem_path.join(format!("tests/{test_name}.out"));
+        if !output_path.exists() {
+            continue;
+        }
+
+        let result = run_command.get_result(Some(&input_path))?;
+        if let Some(verdict) = Verdict::from_run_result(&result) {
+            eprintln!("The reference solution failed on test '{test_name}': {verdict}");
+            stale_outputs += 1;
+            continue;
+        }
+
+        let check = checker.check(&input_path, &output_path, &result.output)?;
+        if check.verdict.is_accepted() {
+            eprintln!("Output of test '{test_name}' is up to date");
+        } else {
+            eprintln!(
+                "`tests/{test_name}.out` doesn't match the output of `{solution_file}`: {}",
+                check.verdict
+            );
+            if let Some(message) = check.message {
+                eprintln!("      Checker: {message}");
+            }
+            stale_outputs += 1;
+        }
+    }
+
+    run_command.cleanup()?;
+    checker.cleanup()?;
+
+    Ok(stale_outputs)
+}
+
+/// Check that no files or tests are missing, that the problem name is valid,
+/// that the solutions and generators are valid, that the examples in the
+/// statement match the sample tests, and that the test inputs pass the
+/// validator if there is one. Statement lint issues, build artefacts, unknown
+/// files and outputs that may be stale are reported as warnings.
+pub fn check(
+    settings: &Settings,
+    problems_dir: PathBuf,
+    problem_name: &str,
+    options: CheckOptions,
+) -> Result<CheckSummary> {
     eprintln!("Begin check...");
 
     let problem_path = get_problem(&problems_dir, problem_name)?;
@@ -60,12 +329,35 @@ pub fn check(problems_dir: PathBuf, problem_name: &str) -> Result<()> {
     }
     eprintln!("Folder structure for '{problem_name}' is valid");
 
+    let mut summary = CheckSummary::default();
+    check_folder_contents(settings, &path, options.fix, &mut summary)?;
+
+    let lint_issues = lint(&path)?;
+    for issue in &lint_issues {
+        eprintln!("Warning: {issue}");
+    }
+    summary.warnings += lint_issues.len();
+    summary.statement_issues += lint_issues.len();
+    if lint_issues.is_empty() {
+        eprintln!("Problem statement passed all lint rules");
+    }
+
+    match ProblemMetadata::load(&path)? {
+        Some(metadata) => eprintln!("Metadata is valid (title: {})", metadata.title),
+        None => {
+            eprintln!(
+                "Warning: `{PROBLEM_METADATA_FILE}` is missing, so default metadata will be used"
+            );
+            summary.warnings += 1;
+        }
+    }
+
     // Check that test files are valid, i.e.:
     // - A .in file must have a corresponding .out file
     // - The files are non-empty
 
     let tests_path = path.join("tests");
-    let test_files = get_files_in_directory(&tests_path)?;
+    let test_files = get_files_in_directory_recursive(&tests_path)?;
     let mut tests_data: HashMap<String, TestData> = HashMap::new();
 
     if test_files.is_empty() {
@@ -77,13 +369,17 @@ pub fn check(problems_dir: PathBuf, problem_name: &str) -> Result<()> {
 
         match is_file_empty(test_path) {
             Ok(false) => {}
-            Ok(true) => eprintln!("Warning: `{}` is an empty file", &file),
-            Err(err) => bail!("Failed to check if `{}` was empty: {err}", &file),
+            Ok(true) => {
+                eprintln!("Warning: `{}` is an empty file", file);
+                summary.warnings += 1;
+            }
+            Err(err) => bail!("Failed to check if `{}` was empty: {err}", file),
         }
 
         let file_parts: Vec<_> = file.split(".").collect();
         if file_parts.iter().len() != 2 {
-            eprintln!("The test file name `{file}` is invalid. It must be in the format `<test_name>.<in|out>`");
+            eprintln!("Warning: The test file name `{file}` is invalid. It must be in the format `<test_name>.<in|out>`");
+            summary.warnings += 1;
             continue;
         }
         let test_name = file_parts[0];
@@ -97,14 +393,13 @@ pub fn check(problems_dir: PathBuf, problem_name: &str) -> Result<()> {
             "in" => test_entry.input_exists = true,
             "out" => test_entry.output_exists = true,
             _ => {
-                eprintln!("The file extension `.{test_suffix}` is invalid. Only `.in` and `.out` are valid. This file will be skipped...");
+                eprintln!("Warning: The file extension `.{test_suffix}` is invalid. Only `.in` and `.out` are valid. This file will be skipped...");
+                summary.warnings += 1;
                 continue;
             }
         }
     }
 
-    let mut invalid_tests = 0;
-
     for (name, data) in &tests_data {
         if data.input_exists && data.output_exists {
             eprintln!("Test case '{name}' is valid");
@@ -120,15 +415,202 @@ pub fn check(problems_dir: PathBuf, problem_name: &str) -> Result<()> {
                 format!("`{name}.out` doesn't exist")
             };
             eprintln!("Test case '{name}' is invalid! Reason: {in_exists}, {out_exists}");
-            invalid_tests += 1;
+            summary.invalid_tests += 1;
         }
     }
 
     if tests_data.iter().len() < 2 {
         eprintln!("Warning: You have fewer than two test cases! Maybe add a few more?");
+        summary.warnings += 1;
     }
 
-    eprintln!("Check completed. Found {invalid_tests} invalid tests");
+    let sample_mismatches = check_samples(&path)?;
+    for mismatch in &sample_mismatches {
+        eprintln!("Sample test mismatch: {mismatch}");
+    }
+    if !sample_mismatches.is_empty() {
+        eprintln!("Run `aucpl problem sync-samples` to update the examples from the sample tests");
+        summary.invalid_tests += sample_mismatches.len();
+        summary.statement_issues += sample_mismatches.len();
+    }
 
-    Ok(())
+    let stamp = SolveStamp::load(&path)?;
+    match &stamp {
+        Some(stamp) => {
+            let reasons = stamp.stale_reasons(&path)?;
+            for reason in &reasons {
+                eprintln!("Warning: Test outputs may be stale: {reason}");
+            }
+            if reasons.is_empty() {
+                eprintln!("Test outputs are up to date with `{}`", stamp.solution);
+            } else if !options.deep {
+                eprintln!("Run `aucpl problem solve` to regenerate the outputs, or `aucpl problem check --deep` to compare them");
+            }
+            summary.warnings += reasons.len();
+        }
+        None => eprintln!(
+            "No record of generating the outputs ({SOLVE_STAMP_FILE}), so stale outputs are only found with `--deep`"
+        ),
+    }
+
+    if options.deep {
+        let solution_file = match stamp
+            .as_ref()
+            .and_then(|stamp| stamp.solution.strip_prefix("solutions/"))
+        {
+            Some(name) => RunnableFile::new(
+                settings,
+                RunnableCategory::Solution,
+                Some(&name.to_owned()),
+                None,
+            )?,
+            None => RunnableFile::new(settings, RunnableCategory::Solution, None, None)?,
+        };
+        eprintln!("Comparing test outputs with the output of `{solution_file}`...");
+        summary.invalid_tests += check_outputs(settings, &path, &solution_file)?;
+    }
+
+    match Validator::for_problem(settings, &path)? {
+        Some(validator) => {
+            eprintln!("Validating test inputs...");
+            let invalid_inputs = validate_tests(&validator, &path);
+            validator.cleanup()?;
+            summary.invalid_tests += invalid_inputs?;
+        }
+        None => eprintln!("No validator found, skipping input validation"),
+    }
+
+    eprintln!(
+        "Check completed. Found {} invalid tests, {} invalid files and {} warnings",
+        summary.invalid_tests, summary.invalid_files, summary.warnings
+    );
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+
+    use super::{check, CheckOptions, CheckSummary};
+    use crate::config::{get_settings, SETTINGS_FILE_NAME};
+    use crate::problem::solve::SolveStamp;
+    use crate::problem::sync_mappings::sync_mappings;
+    use crate::problem::test_support::{create_problem_dir, with_test_project};
+
+    #[test]
+    fn checks_solutions_generators_and_artefacts() {
+        with_test_project(|problems_dir| {
+            // A language whose "compiler" only checks the syntax of shell scripts
+            let mut settings_file = OpenOptions::new()
+                .append(true)
+                .open(SETTINGS_FILE_NAME)
+                .expect("settings file should open");
+            settings_file
+                .write_all(b"\n[problem.solution.sh]\ncompile_command = [\"sh\", \"-n\", \"@in_file\"]\nrun_command = [\"sh\", \"@script_file\"]\n")
+                .expect("settings should be written");
+            let settings = get_settings().expect("settings should load");
+
+            create_problem_dir(problems_dir, "new", "easy", "two-sum");
+            sync_mappings(problems_dir).expect("mappings should sync");
+            let problem_path = problems_dir.join("new/easy/two-sum");
+            fs::create_dir(problem_path.join("generators")).expect("folder should be created");
+            fs::write(problem_path.join("solutions/solution.py"), "print(3)\n")
+                .expect("solution should be written");
+            fs::write(problem_path.join("solutions/solution.out"), "")
+                .expect("solution should be written");
+            fs::write(problem_path.join("generators/good.sh"), "echo 1 2\n")
+                .expect("generator should be written");
+            fs::write(problem_path.join("generators/bad.sh"), "if then\n")
+                .expect("generator should be written");
+            fs::write(problem_path.join("notes.txt"), "").expect("file should be written");
+            fs::write(problem_path.join("testlib.h"), "").expect("header should be written");
+            for test in ["a", "b"] {
+                fs::write(problem_path.join(format!("tests/{test}.in")), "1 2\n")
+                    .expect("test should be written");
+                fs::write(problem_path.join(format!("tests/{test}.out")), "3\n")
+                    .expect("test should be written");
+            }
+
+            let summary = check(
+                &settings,
+                problems_dir.to_path_buf(),
+                "two-sum",
+                CheckOptions::default(),
+            )
+            .expect("check should run");
+            assert_eq!(summary.invalid_tests, 0);
+            assert_eq!(summary.invalid_files, 1, "bad.sh should not compile");
+            // The lint issues, the missing problem.toml, the build artefact,
+            // notes.txt and the missing C++ and shell solutions, but not testlib.h
+            assert_eq!(summary.warnings, summary.statement_issues + 5);
+            assert!(problem_path.join("solutions/solution.out").exists());
+
+            let options = CheckOptions {
+                fix: true,
+                ..CheckOptions::default()
+            };
+            let fixed = check(&settings, problems_dir.to_path_buf(), "two-sum", options)
+                .expect("check should run");
+            assert!(!problem_path.join("solutions/solution.out").exists());
+            // The artefact warning is gone, but the unknown file is still reported
+            assert_eq!(
+                fixed,
+                CheckSummary {
+                    warnings: summary.warnings - 1,
+                    ..summary
+                }
+            );
+
+            // Without any reference solution, the missing languages aren't warned about
+            fs::remove_file(problem_path.join("solutions/solution.py"))
+                .expect("solution should be removed");
+            let unsolved = check(
+                &settings,
+                problems_dir.to_path_buf(),
+                "two-sum",
+                CheckOptions::default(),
+            )
+            .expect("check should run");
+            assert_eq!(unsolved.invalid_files, 2);
+            assert_eq!(unsolved.warnings, fixed.warnings - 2);
+        });
+    }
+
+    #[test]
+    fn deep_check_judges_outputs_with_problem_comparator() {
+        with_test_project(|problems_dir| {
+            let settings = get_settings().expect("settings should load");
+            create_problem_dir(problems_dir, "new", "easy", "third");
+            sync_mappings(problems_dir).expect("mappings should sync");
+            let problem_path = problems_dir.join("new/easy/third");
+            fs::write(
+                problem_path.join("problem.toml"),
+                "title = \"Third\"\nchecker = \"float:1e-3\"\n",
+            )
+            .expect("checker should be written");
+            fs::write(problem_path.join("solutions/solution.py"), "print(1 / 3)\n")
+                .expect("solution should be written");
+            for (test, output) in [("a", "0.3333\n"), ("b", "0.5\n")] {
+                fs::write(problem_path.join(format!("tests/{test}.in")), "\n")
+                    .expect("test should be written");
+                fs::write(problem_path.join(format!("tests/{test}.out")), output)
+                    .expect("test should be written");
+            }
+            SolveStamp::create(&problem_path, "solutions/solution.py")
+                .expect("stamp should be created")
+                .write(&problem_path)
+                .expect("stamp should be written");
+
+            let options = CheckOptions {
+                deep: true,
+                ..CheckOptions::default()
+            };
+            let summary = check(&settings, problems_dir.to_path_buf(), "third", options)
+                .expect("check should run");
+            // `a` is within the tolerance of the float comparator, but `b` isn't
+            assert_eq!(summary.invalid_tests, 1);
+        });
+    }
 }
@@ -0,0 +1,402 @@
+//! Judge the output of a solution, either with a custom checker or a built-in
+//! comparator.
+//!
+//! Two kinds of custom checker are supported:
+//! - `checker.py`, which defines a `check(process_output, judge_output, judge_input)`
+//!   function returning a bool.
+//! - `checker.<lang>` for any other language in the settings (e.g. a C++ checker
+//!   written with testlib). It is run as `checker <input> <output> <answer>`, and
+//!   its exit code is the verdict: 0 is accepted, 1 is a wrong answer, 2 is a
+//!   presentation error and 3 is a failure of the checker itself. Anything it
+//!   writes to stderr is reported as feedback.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use uuid::Uuid;
+
+use crate::config::Settings;
+use crate::problem::comparator::Comparator;
+use crate::problem::metadata::{CheckerType, PROBLEM_METADATA_FILE};
+use crate::problem::run::{
+    find_file_langs, get_python_executable, RunCommand, RunnableCategory, RunnableFile,
+};
+use crate::problem::verdict::Verdict;
+
+pub const PYTHON_CHECKER_FILE: &str = "checker.py";
+
+const PYTHON_CHECKER_SCRIPT: &str = r#"
+import importlib.util
+import sys
+
+checker_path = sys.argv[1]
+process_output = sys.argv[2]
+judge_output = sys.argv[3]
+
+judge_input = sys.stdin.read()
+
+spec = importlib.util.spec_from_file_location("aucpl_checker", checker_path)
+if spec is None or spec.loader is None:
+    print("Could not load checker.py", file=sys.stderr)
+    sys.exit(2)
+
+module = importlib.util.module_from_spec(spec)
+spec.loader.exec_module(module)
+
+if not hasattr(module, "check"):
+    print("checker.py must define a `check` function", file=sys.stderr)
+    sys.exit(2)
+
+result = module.check(process_output, judge_output, judge_input=judge_input)
+
+print("true" if bool(result) else "false")
+"#;
+
+/// Exit codes of a native checker, following the testlib convention.
+const EXIT_OK: u32 = 0;
+const EXIT_WRONG_ANSWER: u32 = 1;
+const EXIT_PRESENTATION_ERROR: u32 = 2;
+const EXIT_FAIL: u32 = 3;
+
+/// How the output of a solution is judged for a problem.
+pub enum OutputChecker {
+    /// `checker.py`, run through the embedded Python harness.
+    Python {
+        checker_path: PathBuf,
+        python_cmd: String,
+    },
+    /// A testlib-compatible checker program.
+    Native {
+        checker_path: PathBuf,
+        run_command: RunCommand,
+    },
+    /// A built-in comparator, used when the problem has no custom checker.
+    Builtin(Comparator),
+}
+
+/// The verdict of a checker, and any feedback it gave.
+pub struct CheckResult {
+    pub verdict: Verdict,
+    pub message: Option<String>,
+}
+
+/// Find the native checker source file in the problem folder, if any.
+fn find_native_checker(settings: &Settings, problem_path: &Path) -> Result<Option<RunnableFile>> {
+    let mut checkers = find_file_langs(settings, problem_path, "checker")?;
+    checkers.retain(|lang| lang != "py");
+
+    match checkers.as_slice() {
+        [] => Ok(None),
+        [lang] => Ok(Some(RunnableFile::new(
+            settings,
+            RunnableCategory::Checker,
+            None,
+            Some(lang),
+        )?)),
+        _ => bail!(
+            "Found multiple checkers: {}. Only one checker is allowed per problem",
+            checkers
+                .iter()
+                .map(|lang| format!("checker.{lang}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+impl OutputChecker {
+    /// Get the checker for a problem, compiling it if needed. If the problem has
+    /// no custom checker, the comparator of the given checker type is used.
+    pub fn for_problem(
+        settings: &Settings,
+        problem_path: &Path,
+        checker_type: CheckerType,
+    ) -> Result<Self> {
+        let python_checker_path = problem_path.join(PYTHON_CHECKER_FILE);
+        let native_checker = find_native_checker(settings, problem_path)?;
+
+        match (python_checker_path.exists(), native_checker) {
+            (true, Some(native)) => bail!(
+                "Found both {PYTHON_CHECKER_FILE} and {native}. Only one checker is allowed per problem"
+            ),
+            (true, None) => Ok(OutputChecker::Python {
+                checker_path: python_checker_path,
+                python_cmd: get_python_executable(settings),
+            }),
+            (false, Some(native)) => {
+                let checker_path = problem_path.join(format!("{native}"));
+                let run_command = RunCommand::new(
+                    settings,
+                    problem_path,
+                    &native,
+                    problem_path.join("checker.out"),
+                    checker_path.clone(),
+                )
+                .context("Failed to prepare checker")?;
+
+                Ok(OutputChecker::Native {
+                    checker_path,
+                    run_command,
+                })
+            }
+            (false, None) => match checker_type {
+                CheckerType::Builtin(comparator) => Ok(OutputChecker::Builtin(comparator)),
+                CheckerType::Custom => bail!(
+                    "{PROBLEM_METADATA_FILE} requires a custom checker, but there is no {PYTHON_CHECKER_FILE} or checker.<lang> file"
+                ),
+            },
+        }
+    }
+
+    /// Judge the output of a solution for the test case with the given input.
+    pub fn check(
+        &self,
+        input_file_path: &PathBuf,
+        answer_file_path: &Path,
+        output: &str,
+    ) -> Result<CheckResult> {
+        match self {
+            OutputChecker::Python {
+                checker_path,
+                python_cmd,
+            } => {
+                let expected = fs::read(answer_file_path)?;
+                let passed = run_python_checker(
+                    checker_path,
+                    python_cmd,
+                    output,
+                    &expected,
+                    input_file_path,
+                )?;
+                Ok(CheckResult {
+                    verdict: accepted_if(passed),
+                    message: None,
+                })
+            }
+            OutputChecker::Native { run_command, .. } => {
+                run_native_checker(run_command, input_file_path, answer_file_path, output)
+            }
+            OutputChecker::Builtin(comparator) => {
+                let expected = fs::read(answer_file_path)?;
+                Ok(CheckResult {
+                    verdict: accepted_if(comparator.compare(&expected, output.as_bytes())),
+                    message: None,
+                })
+            }
+        }
+    }
+
+    /// Describe the checker, e.g. for progress output.
+    pub fn description(&self) -> String {
+        match self {
+            OutputChecker::Python { checker_path, .. }
+            | OutputChecker::Native { checker_path, .. } => {
+                format!("custom checker at: {}", checker_path.display())
+            }
+            OutputChecker::Builtin(comparator) => format!("the {comparator} comparator"),
+        }
+    }
+
+    /// Cleans up the compiled checker binary if it exists.
+    pub fn cleanup(&self) -> Result<()> {
+        match self {
+            OutputChecker::Native { run_command, .. } => run_command.cleanup(),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn accepted_if(passed: bool) -> Verdict {
+    if passed {
+        Verdict::Accepted
+    } else {
+        Verdict::WrongAnswer
+    }
+}
+
+fn run_python_checker(
+    checker_path: &Path,
+    python_cmd: &str,
+    process_output: &str,
+    judge_output: &[u8],
+    input_file_path: &PathBuf,
+) -> Result<bool> {
+    let judge_output = String::from_utf8_lossy(judge_output).into_owned();
+
+    let checker_run = RunCommand::from_command(
+        PathBuf::new(),
+        checker_path.to_path_buf(),
+        vec![
+            python_cmd.to_owned(),
+            "-c".to_string(),
+            PYTHON_CHECKER_SCRIPT.to_string(),
+            "@script_file".to_string(),
+            process_output.to_string(),
+            judge_output,
+        ],
+    )
+    .context("Failed to prepare checker command")?;
+    let checker_result = checker_run
+        .get_result(Some(input_file_path))
+        .context("Failed to run checker.py")?
+        .output;
+
+    let passed = match checker_result.trim().to_ascii_lowercase().as_str() {
+        "true" => true,
+        "false" => false,
+        other => {
+            bail!(
+                "checker.py must return a bool-compatible result, got: {}",
+                other
+            )
+        }
+    };
+
+    Ok(passed)
+}
+
+fn run_native_checker(
+    run_command: &RunCommand,
+    input_file_path: &PathBuf,
+    answer_file_path: &Path,
+    output: &str,
+) -> Result<CheckResult> {
+    // The checker reads the solution output from a file, so write it somewhere
+    // unique as several test cases may be checked at the same time
+    let output_file_path = env::temp_dir().join(format!("aucpl-output-{}.txt", Uuid::new_v4()));
+    fs::write(&output_file_path, output).context("Failed to write solution output")?;
+
+    let result = run_command.get_result_with_args(
+        Some(input_file_path),
+        &[input_file_path, &output_file_path, answer_file_path],
+    );
+    fs::remove_file(&output_file_path).context("Failed to remove solution output")?;
+    let result = result.context("Failed to run checker")?;
+
+    let message = Some(result.stderr.trim().to_owned()).filter(|m| !m.is_empty());
+    let verdict = match result.exit_status.code() {
+        Some(EXIT_OK) => Verdict::Accepted,
+        Some(EXIT_WRONG_ANSWER) => Verdict::WrongAnswer,
+        Some(EXIT_PRESENTATION_ERROR) => Verdict::PresentationError,
+        Some(EXIT_FAIL) => bail!(
+            "The checker failed: {}",
+            message.as_deref().unwrap_or("no message given")
+        ),
+        _ => bail!(
+            "The checker exited unexpectedly ({:?}): {}",
+            result.exit_status,
+            message.as_deref().unwrap_or("no message given")
+        ),
+    };
+
+    Ok(CheckResult { verdict, message })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::OutputChecker;
+    use crate::config::get_settings;
+    use crate::problem::comparator::Comparator;
+    use crate::problem::metadata::CheckerType;
+    use crate::problem::test_support::{create_problem_dir, with_test_project};
+
+    #[test]
+    fn chooses_checker_from_problem_files() {
+        with_test_project(|problems_dir| {
+            create_problem_dir(problems_dir, "new", "easy", "two-sum");
+            let problem_path = problems_dir.join("new/easy/two-sum");
+            let settings = get_settings().expect("settings should load");
+
+            let tokens = CheckerType::Builtin(Comparator::Tokens);
+            let checker = OutputChecker::for_problem(&settings, &problem_path, tokens)
+                .expect("checker should be found");
+            assert!(matches!(
+                checker,
+                OutputChecker::Builtin(Comparator::Tokens)
+            ));
+            assert!(
+                OutputChecker::for_problem(&settings, &problem_path, CheckerType::Custom).is_err()
+            );
+
+            fs::write(
+                problem_path.join("checker.py"),
+                "def check(*args, **kwargs): ...\n",
+            )
+            .expect("checker should be written");
+            let checker = OutputChecker::for_problem(&settings, &problem_path, tokens)
+                .expect("checker should be found");
+            assert!(matches!(checker, OutputChecker::Python { .. }));
+
+            fs::write(problem_path.join("checker.cpp"), "int main() {}\n")
+                .expect("checker should be written");
+            let err = OutputChecker::for_problem(&settings, &problem_path, tokens)
+                .err()
+                .expect("multiple checkers should be rejected");
+            assert!(err.to_string().contains("Only one checker is allowed"));
+        });
+    }
+
+    #[cfg(unix)]
+    mod native {
+        use std::fs;
+        use std::path::{Path, PathBuf};
+
+        use tempfile::TempDir;
+
+        use super::super::{run_native_checker, RunCommand};
+        use crate::problem::verdict::Verdict;
+
+        /// A checker that compares the output and answer files, exiting with
+        /// the code given in the input file.
+        fn run_checker(
+            dir: &Path,
+            input: &str,
+            output: &str,
+        ) -> anyhow::Result<(Verdict, Option<String>)> {
+            let input_path = dir.join("test.in");
+            let answer_path = dir.join("test.out");
+            fs::write(&input_path, input).expect("input should be written");
+            fs::write(&answer_path, "42\n").expect("answer should be written");
+
+            let checker = RunCommand::from_command(
+                PathBuf::new(),
+                PathBuf::new(),
+                vec![
+                    "sh".to_owned(),
+                    "-c".to_owned(),
+                    r#"if cmp -s "$1" "$2"; then echo "ok" >&2; exit 0; fi; echo "expected 42" >&2; exit "$(cat "$0")""#
+                        .to_owned(),
+                ],
+            )?;
+
+            let result = run_native_checker(&checker, &input_path, &answer_path, output)?;
+            Ok((result.verdict, result.message))
+        }
+
+        #[test]
+        fn interprets_testlib_exit_codes_and_messages() {
+            let dir = TempDir::new().expect("tempdir should be created");
+
+            let (verdict, message) =
+                run_checker(dir.path(), "1", "42\n").expect("checker should run");
+            assert_eq!(verdict, Verdict::Accepted);
+            assert_eq!(message.as_deref(), Some("ok"));
+
+            let (verdict, message) =
+                run_checker(dir.path(), "1", "41\n").expect("checker should run");
+            assert_eq!(verdict, Verdict::WrongAnswer);
+            assert_eq!(message.as_deref(), Some("expected 42"));
+
+            let (verdict, _) = run_checker(dir.path(), "2", "41\n").expect("checker should run");
+            assert_eq!(verdict, Verdict::PresentationError);
+
+            let err =
+                run_checker(dir.path(), "3", "41\n").expect_err("checker failure is an error");
+            assert!(err.to_string().contains("The checker failed: expected 42"));
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Built-in comparators for checking a solution's output against the expected output.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Error, Result};
+
+const DEFAULT_FLOAT_EPSILON: f64 = 1e-6;
+
+/// A built-in way of comparing outputs, used when a problem has no custom checker.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Comparator {
+    /// The outputs must be byte-for-byte identical.
+    #[default]
+    Exact,
+    /// The outputs must contain the same whitespace-separated tokens.
+    Tokens,
+    /// The outputs must have the same lines, ignoring trailing whitespace,
+    /// `\r\n` line endings, and trailing blank lines.
+    Lines,
+    /// Like [`Comparator::Tokens`], but tokens are compared case-insensitively.
+    CaseInsensitive,
+    /// Like [`Comparator::Tokens`], but numeric tokens are equal if they are
+    /// within an absolute or relative epsilon of each other.
+    Float { abs_epsilon: f64, rel_epsilon: f64 },
+}
+
+/// Names accepted by [`Comparator::from_str`].
+pub const COMPARATOR_NAMES: &[&str] = &["exact", "tokens", "lines", "case-insensitive", "float"];
+
+impl Comparator {
+    /// Check whether the actual output is accepted given the expected output.
+    pub fn compare(&self, expected: &[u8], actual: &[u8]) -> bool {
+        if let Comparator::Exact = self {
+            return expected == actual;
+        }
+
+        let expected = String::from_utf8_lossy(expected);
+        let actual = String::from_utf8_lossy(actual);
+
+        match self {
+            Comparator::Exact => unreachable!(),
+            Comparator::Tokens => expected.split_whitespace().eq(actual.split_whitespace()),
+            Comparator::Lines => significant_lines(&expected).eq(significant_lines(&actual)),
+            Comparator::CaseInsensitive => tokens_match(&expected, &actual, |e, a| {
+                e.to_lowercase() == a.to_lowercase()
+            }),
+            Comparator::Float {
+                abs_epsilon,
+                rel_epsilon,
+            } => tokens_match(&expected, &actual, |e, a| {
+                match (e.parse::<f64>(), a.parse::<f64>()) {
+                    (Ok(e), Ok(a)) => floats_match(e, a, *abs_epsilon, *rel_epsilon),
+                    _ => e == a,
+                }
+            }),
+        }
+    }
+}
+
+/// Lines with trailing whitespace removed, excluding trailing blank lines.
+fn significant_lines(s: &str) -> impl Iterator<Item = &str> {
+    let lines: Vec<&str> = s.lines().map(str::trim_end).collect();
+    let len = lines
+        .iter()
+        .rposition(|line| !line.is_empty())
+        .map_or(0, |i| i + 1);
+    lines.into_iter().take(len)
+}
+
+fn tokens_match(expected: &str, actual: &str, matches: impl Fn(&str, &str) -> bool) -> bool {
+    let mut expected = expected.split_whitespace();
+    let mut actual = actual.split_whitespace();
+    loop {
+        match (expected.next(), actual.next()) {
+            (Some(e), Some(a)) if matches(e, a) => continue,
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+fn floats_match(expected: f64, actual: f64, abs_epsilon: f64, rel_epsilon: f64) -> bool {
+    if expected == actual {
+        return true;
+    }
+    if !expected.is_finite() || !actual.is_finite() {
+        return false;
+    }
+
+    let diff = (expected - actual).abs();
+    diff <= abs_epsilon || diff <= rel_epsilon * expected.abs()
+}
+
+impl FromStr for Comparator {
+    type Err = Error;
+
+    /// Parse a comparator from its name. The float comparator optionally takes
+    /// the absolute and relative epsilon, e.g. `float:1e-9` or `float:1e-6:1e-9`.
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, params) = match s.split_once(':') {
+            Some((name, params)) => (name, Some(params)),
+            None => (s, None),
+        };
+
+        let comparator = match name {
+            "exact" => Comparator::Exact,
+            "tokens" => Comparator::Tokens,
+            "lines" => Comparator::Lines,
+            "case-insensitive" => Comparator::CaseInsensitive,
+            "float" => {
+                let epsilons = params
+                    .map(|params| {
+                        params
+                            .split(':')
+                            .map(|p| {
+                                p.parse::<f64>()
+                                    .ok()
+                                    .filter(|eps| *eps >= 0.0)
+                                    .context(format!("Invalid epsilon '{p}' for float comparator"))
+                            })
+                            .collect::<Result<Vec<_>>>()
+                    })
+                    .transpose()?
+                    .unwrap_or_default();
+
+                match epsilons.as_slice() {
+                    [] => Comparator::Float {
+                        abs_epsilon: DEFAULT_FLOAT_EPSILON,
+                        rel_epsilon: DEFAULT_FLOAT_EPSILON,
+                    },
+                    [eps] => Comparator::Float {
+                        abs_epsilon: *eps,
+                        rel_epsilon: *eps,
+                    },
+                    [abs_epsilon, rel_epsilon] => Comparator::Float {
+                        abs_epsilon: *abs_epsilon,
+                        rel_epsilon: *rel_epsilon,
+                    },
+                    _ => bail!(
+                        "The float comparator takes at most two epsilons, e.g. 'float:1e-6:1e-9'"
+                    ),
+                }
+            }
+            _ => bail!(
+                "Unknown comparator '{name}'. Valid comparators are: {}",
+                COMPARATOR_NAMES.join(", ")
+            ),
+        };
+
+        if params.is_some() && !matches!(comparator, Comparator::Float { .. }) {
+            bail!("The '{name}' comparator does not take any parameters");
+        }
+
+        Ok(comparator)
+    }
+}
+
+impl fmt::Display for Comparator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Comparator::Exact => write!(f, "exact"),
+            Comparator::Tokens => write!(f, "tokens"),
+            Comparator::Lines => write!(f, "lines"),
+            Comparator::CaseInsensitive => write!(f, "case-insensitive"),
+            Comparator::Float {
+                abs_epsilon,
+                rel_epsilon,
+            } => write!(f, "float:{abs_epsilon:e}:{rel_epsilon:e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Comparator;
+
+    fn compare(spec: &str, expected: &str, actual: &str) -> bool {
+        spec.parse::<Comparator>()
+            .expect("comparator should parse")
+            .compare(expected.as_bytes(), actual.as_bytes())
+    }
+
+    #[test]
+    fn exact_comparator_requires_identical_bytes() {
+        assert!(compare("exact", "1 2\n", "1 2\n"));
+        assert!(!compare("exact", "1 2\n", "1 2"));
+        assert!(!compare("exact", "1 2\n", "1 2\r\n"));
+    }
+
+    #[test]
+    fn tokens_comparator_ignores_whitespace() {
+        assert!(compare("tokens", "1 2\n3\n", "1\n2   3"));
+        assert!(!compare("tokens", "1 2 3", "1 2"));
+        assert!(!compare("tokens", "12", "1 2"));
+    }
+
+    #[test]
+    fn lines_comparator_ignores_trailing_spaces_and_line_endings() {
+        assert!(compare("lines", "1 2\n3\n", "1 2  \r\n3"));
+        assert!(compare("lines", "a\n", "a\n\n\n"));
+        assert!(!compare("lines", "1 2\n3\n", "1 2 3\n"));
+        assert!(!compare("lines", "1  2\n", "1 2\n"));
+    }
+
+    #[test]
+    fn case_insensitive_comparator_ignores_case() {
+        assert!(compare("case-insensitive", "YES\nno\n", "yes NO"));
+        assert!(!compare("case-insensitive", "YES", "YESS"));
+    }
+
+    #[test]
+    fn float_comparator_uses_absolute_and_relative_epsilon() {
+        assert!(compare("float", "0.333333", "0.3333334"));
+        assert!(!compare("float", "0.3333", "0.3334"));
+        assert!(compare("float:1e-3", "0.3333", "0.3334"));
+        assert!(compare("float:0:1e-6", "1000000000", "1000000001"));
+        assert!(compare("float", "answer 1.0", "answer 1"));
+        assert!(!compare("float", "answer 1.0", "Answer 1.0"));
+        assert!(!compare("float", "nan", "1.0"));
+    }
+
+    #[test]
+    fn rejects_invalid_comparator_specs() {
+        assert!("regex".parse::<Comparator>().is_err());
+        assert!("tokens:1e-6".parse::<Comparator>().is_err());
+        assert!("float:abc".parse::<Comparator>().is_err());
+        assert!("float:-1".parse::<Comparator>().is_err());
+        assert!("float:1:2:3".parse::<Comparator>().is_err());
+    }
+}
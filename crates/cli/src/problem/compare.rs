@@ -3,10 +3,13 @@ use std::time::Duration;
 
 use anyhow::{bail, Context, Result};
 
-use super::run::{RunCommand, RunnableFile};
+use super::comparator::Comparator;
+use super::metadata::ProblemMetadata;
+use super::run::{RunCommand, RunLimits, RunnableFile};
 use super::sync_mappings::get_problem;
+use super::verdict::Verdict;
 use crate::config::Settings;
-use crate::problem::run::RunResult;
+use crate::problem::run::{print_stderr, RunResult};
 use crate::util::{get_input_files_in_directory, get_project_root};
 
 /// Arguments for the compare command.
@@ -14,6 +17,10 @@ pub struct CompareArgs<'a> {
     pub problems_dir: &'a Path,
     pub problem_name: String,
     pub solution_files: Vec<RunnableFile>,
+    /// Overrides the limits from the problem metadata.
+    pub limits: RunLimits,
+    /// Overrides the comparator from the problem metadata.
+    pub comparator: Option<Comparator>,
 }
 
 /// Compare two solutions.
@@ -23,18 +30,27 @@ pub fn compare(settings: &Settings, compare_args: &CompareArgs) -> Result<()> {
         problems_dir,
         problem_name,
         solution_files,
+        limits,
+        comparator,
     } = compare_args;
     let problem_path = project_root.join(get_problem(problems_dir, problem_name)?);
 
+    let metadata = ProblemMetadata::load_or_default(&problem_path)?;
+    let limits = limits.or(metadata.run_limits()?);
+    let comparator = comparator.unwrap_or(metadata.checker.comparator());
+
     let mut run_commands: Vec<RunCommand> = Vec::new();
     for (i, file) in solution_files.iter().enumerate() {
-        run_commands.push(RunCommand::new(
-            settings,
-            &problem_path,
-            file,
-            problem_path.join(format!("solutions/solution_{i}.out")),
-            problem_path.join(format!("{file}")),
-        )?);
+        run_commands.push(
+            RunCommand::new(
+                settings,
+                &problem_path,
+                file,
+                problem_path.join(format!("solutions/solution_{i}.out")),
+                problem_path.join(format!("{file}")),
+            )?
+            .with_limits(limits),
+        );
     }
 
     // If there aren't at least two solutions, we can't compare so return an error
@@ -63,20 +79,32 @@ pub fn compare(settings: &Settings, compare_args: &CompareArgs) -> Result<()> {
 
         let mut passed = true;
         let mut avg_duration = Duration::new(0, 0);
+        if let Some((i, verdict)) = results
+            .iter()
+            .enumerate()
+            .find_map(|(i, result)| Verdict::from_run_result(result).map(|v| (i, v)))
+        {
+            eprintln!("  ! Test case failed: {test_file}, solution {i}: {verdict}");
+            print_stderr(&results[i].stderr);
+            passed = false;
+        }
+
         // We verify earlier that there is at least two solutions so indexing 0 is no issue
         let result_1 = &results[0];
-        for (i, result) in results.iter().enumerate().skip(1) {
-            // TODO: compare 1st, 2nd and nth result for a "best of three" (if applicable)?
-            if result_1.output.as_bytes() != result.output.as_bytes() {
-                eprintln!(
-                        "  ! Test case failed: {test_file}, solution 0 took {:.5}s, solution {i} took {:.5}s",
-                        result_1.elapsed_time.as_secs_f64(),
-                        result.elapsed_time.as_secs_f64()
-                    );
-                passed = false;
-                break;
+        if passed {
+            for (i, result) in results.iter().enumerate().skip(1) {
+                // TODO: compare 1st, 2nd and nth result for a "best of three" (if applicable)?
+                if !comparator.compare(result_1.output.as_bytes(), result.output.as_bytes()) {
+                    eprintln!(
+                            "  ! Test case failed: {test_file}, solution 0 took {:.5}s, solution {i} took {:.5}s",
+                            result_1.elapsed_time.as_secs_f64(),
+                            result.elapsed_time.as_secs_f64()
+                        );
+                    passed = false;
+                    break;
+                }
+                avg_duration += result.elapsed_time;
             }
-            avg_duration += result.elapsed_time;
         }
 
         if passed {
@@ -8,6 +8,7 @@ use anyhow::{bail, Result};
 use regex::Regex;
 
 use crate::problem::category::validate_category;
+use crate::problem::metadata::{metadata_template, title_from_name, PROBLEM_METADATA_FILE};
 use crate::problem::sync_mappings::{get_problem, sync_mappings};
 use crate::problem::PROBLEM_NAME_REGEX_PATTERN;
 
@@ -30,7 +31,9 @@ pub fn create(problems_dir: &Path, problem_name: &str, category: &str) -> Result
     fs::create_dir(path.join("solutions"))?;
     fs::create_dir(path.join("tests"))?;
 
-    let problem_statement_template = r#"# Problem Title
+    let title = title_from_name(problem_name);
+    let problem_statement_template = format!(
+        r#"# {title}
 
 Problem description.
 
@@ -38,15 +41,19 @@ Problem description.
 
 ## Output
 
+## Constraints
+
 ## Example
 
 ### Input
 
 ### Output
-"#;
+"#
+    );
 
     let mut problem_file = File::create(path.join("problem.md"))?;
     problem_file.write_all(problem_statement_template.as_bytes())?;
+    fs::write(path.join(PROBLEM_METADATA_FILE), metadata_template(&title))?;
 
     sync_mappings(problems_dir)?;
 
@@ -60,6 +67,7 @@ mod tests {
     use std::fs;
 
     use super::create;
+    use crate::problem::metadata::ProblemMetadata;
     use crate::problem::sync_mappings::get_problem;
     use crate::problem::test_support::with_test_project;
 
@@ -73,6 +81,10 @@ mod tests {
             assert!(problem_dir.join("solutions").is_dir());
             assert!(problem_dir.join("tests").is_dir());
             assert!(problem_dir.join("problem.md").is_file());
+            let metadata = ProblemMetadata::load(&problem_dir)
+                .expect("metadata should load")
+                .expect("metadata should be created");
+            assert_eq!(metadata.title, "Two Sum");
             assert_eq!(
                 get_problem(problems_dir, "two-sum").expect("mapping should exist"),
                 "problems/new/easy/two-sum"
@@ -0,0 +1,277 @@
+//! Difficulty ratings of problems.
+//!
+//! Ratings follow the Codeforces scale: multiples of 100 from 800 to 3500. A
+//! problem's rating is stored as `difficulty` in its `problem.toml`.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Error, Result};
+use toml_edit::{value, DocumentMut};
+
+use crate::problem::metadata::{metadata_template, title_from_name, PROBLEM_METADATA_FILE};
+use crate::problem::sync_mappings::get_problem;
+use crate::util::get_project_root;
+
+pub const MIN_RATING: u32 = 800;
+pub const MAX_RATING: u32 = 3500;
+pub const RATING_STEP: u32 = 100;
+
+/// The easiest problem of a competition should be at most this hard, so that
+/// every contestant can solve something.
+const MAX_EASIEST_RATING: u32 = 1200;
+/// The largest allowed jump in difficulty between consecutive problems.
+const MAX_RATING_GAP: u32 = 500;
+
+/// Check that a rating is on the rating scale.
+pub fn validate_rating(rating: u32) -> Result<()> {
+    if !(MIN_RATING..=MAX_RATING).contains(&rating) || !rating.is_multiple_of(RATING_STEP) {
+        bail!(
+            "Invalid rating {rating}. Ratings must be multiples of {RATING_STEP} from {MIN_RATING} to {MAX_RATING}"
+        );
+    }
+    Ok(())
+}
+
+/// An inclusive range of ratings, e.g. `800..1200`. Either end may be left out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DifficultyRange {
+    pub min: Option<u32>,
+    pub max: Option<u32>,
+}
+
+impl DifficultyRange {
+    pub fn contains(&self, rating: u32) -> bool {
+        self.min.is_none_or(|min| rating >= min) && self.max.is_none_or(|max| rating <= max)
+    }
+}
+
+impl FromStr for DifficultyRange {
+    type Err = Error;
+
+    /// Parse a range such as `800..1200`, `1600..`, `..1200` or `1000`.
+    fn from_str(s: &str) -> Result<Self> {
+        let parse_bound = |bound: &str| -> Result<Option<u32>> {
+            let bound = bound.trim();
+            if bound.is_empty() {
+                return Ok(None);
+            }
+            bound
+                .parse()
+                .map(Some)
+                .context(format!("Invalid rating '{bound}' in difficulty range"))
+        };
+
+        let range = match s.split_once("..") {
+            Some((min, max)) => DifficultyRange {
+                min: parse_bound(min)?,
+                max: parse_bound(max)?,
+            },
+            None => {
+                let rating = parse_bound(s)?.context("The difficulty range is empty")?;
+                DifficultyRange {
+                    min: Some(rating),
+                    max: Some(rating),
+                }
+            }
+        };
+
+        if let (Some(min), Some(max)) = (range.min, range.max) {
+            if min > max {
+                bail!("Invalid difficulty range '{s}': {min} is greater than {max}");
+            }
+        }
+
+        Ok(range)
+    }
+}
+
+impl fmt::Display for DifficultyRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) if min == max => write!(f, "{min}"),
+            (min, max) => write!(
+                f,
+                "{}..{}",
+                min.map(|r| r.to_string()).unwrap_or_default(),
+                max.map(|r| r.to_string()).unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// Set the difficulty rating in the metadata file of the problem at the given
+/// path, keeping the rest of the file (including comments) as is. The metadata
+/// file is created if it does not exist.
+pub fn set_difficulty(problem_path: &Path, problem_name: &str, rating: u32) -> Result<()> {
+    let path = problem_path.join(PROBLEM_METADATA_FILE);
+    let contents = if path.exists() {
+        fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))?
+    } else {
+        metadata_template(&title_from_name(problem_name))
+    };
+
+    let mut document: DocumentMut = contents
+        .parse()
+        .context(format!("Failed to parse {}", path.display()))?;
+    document["difficulty"] = value(i64::from(rating));
+
+    fs::write(&path, document.to_string())
+        .context(format!("Failed to write {}", path.display()))?;
+
+    Ok(())
+}
+
+/// Rate the difficulty of a problem.
+pub fn rate(problems_dir: &Path, problem_name: &str, rating: u32) -> Result<()> {
+    validate_rating(rating)?;
+
+    let problem_path = get_project_root()?.join(get_problem(problems_dir, problem_name)?);
+    set_difficulty(&problem_path, problem_name, rating)?;
+
+    eprintln!("Rated problem '{problem_name}' with difficulty {rating}");
+
+    Ok(())
+}
+
+/// Find issues with the spread of difficulties in a problem set, e.g. for a
+/// competition. Returns a description of each issue found.
+pub fn spread_issues(ratings: &[(String, Option<u32>)]) -> Vec<String> {
+    let mut issues = Vec::new();
+
+    let unrated: Vec<&str> = ratings
+        .iter()
+        .filter(|(_, rating)| rating.is_none())
+        .map(|(name, _)| name.as_str())
+        .collect();
+    if !unrated.is_empty() {
+        issues.push(format!("Unrated problems: {}", unrated.join(", ")));
+    }
+
+    let mut rated: Vec<(&str, u32)> = ratings
+        .iter()
+        .filter_map(|(name, rating)| rating.map(|r| (name.as_str(), r)))
+        .collect();
+    rated.sort_by_key(|(_, rating)| *rating);
+
+    if let Some((name, rating)) = rated.first() {
+        if *rating > MAX_EASIEST_RATING {
+            issues.push(format!(
+                "The easiest problem '{name}' is rated {rating}, but there should be a problem rated at most {MAX_EASIEST_RATING}"
+            ));
+        }
+    }
+
+    for pair in rated.windows(2) {
+        let ((easier, easier_rating), (harder, harder_rating)) = (pair[0], pair[1]);
+        if harder_rating - easier_rating > MAX_RATING_GAP {
+            issues.push(format!(
+                "Gap of {} between '{easier}' ({easier_rating}) and '{harder}' ({harder_rating}) is larger than {MAX_RATING_GAP}",
+                harder_rating - easier_rating
+            ));
+        }
+    }
+
+    // With a handful of problems, most of them should not have the same rating
+    if rated.len() >= 4 {
+        for (_, rating) in &rated {
+            let count = rated.iter().filter(|(_, r)| r == rating).count();
+            if count * 2 > rated.len() {
+                issues.push(format!(
+                    "{count} out of {} problems are rated {rating}",
+                    rated.len()
+                ));
+                break;
+            }
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{rate, spread_issues, validate_rating, DifficultyRange};
+    use crate::problem::metadata::{ProblemMetadata, PROBLEM_METADATA_FILE};
+    use crate::problem::sync_mappings::sync_mappings;
+    use crate::problem::test_support::{create_problem_dir, with_test_project};
+
+    #[test]
+    fn parses_difficulty_ranges() {
+        let range: DifficultyRange = "800..1200".parse().expect("range should parse");
+        assert!(range.contains(800) && range.contains(1200));
+        assert!(!range.contains(1300));
+
+        let range: DifficultyRange = "1600..".parse().expect("range should parse");
+        assert!(range.contains(3500) && !range.contains(1500));
+
+        let range: DifficultyRange = "1000".parse().expect("range should parse");
+        assert!(range.contains(1000) && !range.contains(1100));
+        assert_eq!(range.to_string(), "1000");
+
+        assert!("1200..800".parse::<DifficultyRange>().is_err());
+        assert!("easy".parse::<DifficultyRange>().is_err());
+    }
+
+    #[test]
+    fn validates_ratings() {
+        assert!(validate_rating(800).is_ok());
+        assert!(validate_rating(3500).is_ok());
+        assert!(validate_rating(700).is_err());
+        assert!(validate_rating(1250).is_err());
+    }
+
+    #[test]
+    fn rate_updates_metadata_and_keeps_comments() {
+        with_test_project(|problems_dir| {
+            create_problem_dir(problems_dir, "new", "easy", "two-sum");
+            sync_mappings(problems_dir).expect("mappings should sync");
+            let problem_path = problems_dir.join("new/easy/two-sum");
+            fs::write(
+                problem_path.join(PROBLEM_METADATA_FILE),
+                "# The title\ntitle = \"Two Sum\"\n",
+            )
+            .expect("metadata should be written");
+
+            rate(problems_dir, "two-sum", 1200).expect("problem should be rated");
+
+            let contents = fs::read_to_string(problem_path.join(PROBLEM_METADATA_FILE))
+                .expect("metadata should be readable");
+            assert!(contents.starts_with("# The title\n"));
+            let metadata = ProblemMetadata::load(&problem_path)
+                .expect("metadata should load")
+                .expect("metadata should exist");
+            assert_eq!(metadata.difficulty, Some(1200));
+
+            assert!(rate(problems_dir, "two-sum", 1250).is_err());
+        });
+    }
+
+    #[test]
+    fn reports_poor_difficulty_spread() {
+        let ratings = |ratings: &[Option<u32>]| -> Vec<(String, Option<u32>)> {
+            ratings
+                .iter()
+                .enumerate()
+                .map(|(i, rating)| (format!("p{i}"), *rating))
+                .collect()
+        };
+
+        assert!(
+            spread_issues(&ratings(&[Some(800), Some(1200), Some(1600), Some(2000)])).is_empty()
+        );
+
+        let issues = spread_issues(&ratings(&[Some(1400), None, Some(2200)]));
+        assert_eq!(issues.len(), 3, "unexpected issues: {issues:?}");
+        assert_eq!(issues[0], "Unrated problems: p1");
+        assert!(issues[1].contains("easiest problem 'p0' is rated 1400"));
+        assert!(issues[2].contains("Gap of 800"));
+
+        let issues = spread_issues(&ratings(&[Some(800), Some(1000), Some(1000), Some(1000)]));
+        assert_eq!(issues, vec!["3 out of 4 problems are rated 1000"]);
+    }
+}
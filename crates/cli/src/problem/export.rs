@@ -0,0 +1,800 @@
+//! Export problems to the package formats of other online judges.
+//!
+//! - `kattis`: the Kattis problem package format, with `problem.yaml`, the tests
+//!   in `data/secret`, the solutions in `submissions/accepted`, and any custom
+//!   checker in `output_validators`. Kattis derives the time limit from the
+//!   solutions, so only the memory limit is exported.
+//! - `domjudge`: the Kattis format with a `domjudge-problem.ini` holding the time
+//!   limit.
+//! - `polygon`: a Polygon package with `problem.xml`, the tests numbered in
+//!   `tests`, and the checker in `files`.
+//! - `cms`: the CMS "Italian" task format, with `task.yaml` and the tests in
+//!   `input` and `output`.
+//!
+//! `checker.py` is turned into a standalone program that speaks the judge's
+//! checker protocol. Native checkers are copied as is, since they use the
+//! testlib protocol, which only Polygon supports directly.
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::config::Settings;
+use crate::problem::checker::PYTHON_CHECKER_FILE;
+use crate::problem::comparator::Comparator;
+use crate::problem::metadata::{
+    title_from_name, CheckerType, ProblemMetadata, DEFAULT_MEMORY_LIMIT_MB, DEFAULT_TIME_LIMIT_SECS,
+};
+use crate::problem::run::find_file_langs;
+use crate::problem::sync_mappings::get_problem;
+use crate::util::{get_files_in_directory, get_input_files_in_directory, get_project_root};
+
+/// Names accepted by [`ExportFormat::from_name`].
+pub const EXPORT_FORMAT_NAMES: &[&str] = &["kattis", "domjudge", "polygon", "cms"];
+
+/// Package format of an online judge.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExportFormat {
+    Kattis,
+    Domjudge,
+    Polygon,
+    Cms,
+}
+
+impl ExportFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "kattis" => Some(ExportFormat::Kattis),
+            "domjudge" => Some(ExportFormat::Domjudge),
+            "polygon" => Some(ExportFormat::Polygon),
+            "cms" => Some(ExportFormat::Cms),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportFormat::Kattis => "kattis",
+            ExportFormat::Domjudge => "domjudge",
+            ExportFormat::Polygon => "polygon",
+            ExportFormat::Cms => "cms",
+        }
+    }
+}
+
+/// A custom checker of a problem.
+enum CheckerSource {
+    /// The contents of `checker.py`.
+    Python(String),
+    /// A testlib-compatible checker.
+    Native { lang: String, source: Vec<u8> },
+}
+
+/// A problem read from its folder.
+struct ProblemSource {
+    name: String,
+    title: String,
+    metadata: ProblemMetadata,
+    statement: Vec<u8>,
+    /// Test names with their input and output.
+    tests: Vec<(String, Vec<u8>, Vec<u8>)>,
+    /// Solution file names with their contents.
+    solutions: Vec<(String, Vec<u8>)>,
+    checker: Option<CheckerSource>,
+}
+
+impl ProblemSource {
+    fn read(settings: &Settings, problem_path: &Path, problem_name: &str) -> Result<Self> {
+        let read =
+            |path: &Path| fs::read(path).context(format!("Failed to read {}", path.display()));
+
+        let (title, metadata) = match ProblemMetadata::load(problem_path)? {
+            Some(metadata) => (metadata.title.clone(), metadata),
+            None => (title_from_name(problem_name), ProblemMetadata::default()),
+        };
+
+        let tests_dir = problem_path.join("tests");
+        let mut tests = Vec::new();
+        for input_file in get_input_files_in_directory(&tests_dir)? {
+            let test_name = input_file.trim_end_matches(".in").to_owned();
+            let output_path = tests_dir.join(format!("{test_name}.out"));
+            if !output_path.exists() {
+                bail!(
+                    "Test '{test_name}' has no output file. Run `aucpl problem solve` to generate it"
+                );
+            }
+            tests.push((
+                test_name,
+                read(&tests_dir.join(&input_file))?,
+                read(&output_path)?,
+            ));
+        }
+        if tests.is_empty() {
+            bail!("Problem '{problem_name}' has no tests to export");
+        }
+
+        let solutions_dir = problem_path.join("solutions");
+        let mut solutions = Vec::new();
+        if solutions_dir.exists() {
+            let mut files = get_files_in_directory(&solutions_dir)?;
+            files.sort();
+            for file in files {
+                let is_source = Path::new(&file)
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| settings.problem.solution.contains_key(ext));
+                if is_source {
+                    let contents = read(&solutions_dir.join(&file))?;
+                    solutions.push((file, contents));
+                }
+            }
+        }
+
+        let python_checker_path = problem_path.join(PYTHON_CHECKER_FILE);
+        let mut native_checkers = find_file_langs(settings, problem_path, "checker")?;
+        native_checkers.retain(|lang| lang != "py");
+        let checker = match (python_checker_path.exists(), native_checkers.as_slice()) {
+            (false, []) => None,
+            (true, []) => Some(CheckerSource::Python(
+                String::from_utf8(read(&python_checker_path)?)
+                    .context(format!("{PYTHON_CHECKER_FILE} is not valid UTF-8"))?,
+            )),
+            (false, [lang]) => Some(CheckerSource::Native {
+                lang: lang.to_owned(),
+                source: read(&problem_path.join(format!("checker.{lang}")))?,
+            }),
+            _ => bail!("Found multiple checkers. Only one checker is allowed per problem"),
+        };
+        if checker.is_none() && metadata.checker == CheckerType::Custom {
+            bail!("Problem '{problem_name}' requires a custom checker, but none was found");
+        }
+
+        Ok(ProblemSource {
+            name: problem_name.to_owned(),
+            title,
+            statement: read(&problem_path.join("problem.md"))?,
+            metadata,
+            tests,
+            solutions,
+            checker,
+        })
+    }
+
+    fn time_limit(&self) -> f64 {
+        self.metadata.limits.time.unwrap_or(DEFAULT_TIME_LIMIT_SECS)
+    }
+
+    fn memory_limit(&self) -> u64 {
+        self.metadata
+            .limits
+            .memory
+            .unwrap_or(DEFAULT_MEMORY_LIMIT_MB)
+    }
+}
+
+/// A file in an exported package.
+struct PackageFile {
+    path: String,
+    contents: Vec<u8>,
+    executable: bool,
+}
+
+impl PackageFile {
+    fn new(path: impl Into<String>, contents: impl Into<Vec<u8>>) -> Self {
+        PackageFile {
+            path: path.into(),
+            contents: contents.into(),
+            executable: false,
+        }
+    }
+
+    fn executable(path: impl Into<String>, contents: impl Into<Vec<u8>>) -> Self {
+        PackageFile {
+            executable: true,
+            ..PackageFile::new(path, contents)
+        }
+    }
+}
+
+/// Code appended to `checker.py` to run it as a Kattis output validator, which
+/// is run as `validator <input> <answer> <feedback_dir> < output`.
+pub(crate) const KATTIS_CHECKER_WRAPPER: &str = r#"
+
+if __name__ == "__main__":
+    import sys
+
+    with open(sys.argv[1]) as f:
+        judge_input = f.read()
+    with open(sys.argv[2]) as f:
+        judge_output = f.read()
+    process_output = sys.stdin.read()
+
+    sys.exit(42 if check(process_output, judge_output, judge_input=judge_input) else 43)
+"#;
+
+/// Code appended to `checker.py` to run it as a CMS checker, which is run as
+/// `checker <input> <answer> <output>` and prints the score to stdout.
+const CMS_CHECKER_WRAPPER: &str = r#"
+
+if __name__ == "__main__":
+    import sys
+
+    with open(sys.argv[1]) as f:
+        judge_input = f.read()
+    with open(sys.argv[2]) as f:
+        judge_output = f.read()
+    with open(sys.argv[3]) as f:
+        process_output = f.read()
+
+    if check(process_output, judge_output, judge_input=judge_input):
+        print("1.0")
+        print("Output is correct", file=sys.stderr)
+    else:
+        print("0.0")
+        print("Output isn't correct", file=sys.stderr)
+"#;
+
+pub(crate) const PYTHON_SHEBANG: &str = "#!/usr/bin/env python3\n";
+
+/// Turn `checker.py` into a standalone Python program.
+fn python_checker_program(checker: &str, wrapper: &str) -> String {
+    format!("{PYTHON_SHEBANG}{}{wrapper}", checker.trim_end())
+}
+
+/// Quote a string for YAML. JSON strings are valid YAML.
+fn yaml_string(s: &str) -> String {
+    serde_json::to_string(s).expect("strings should serialize")
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Flags for the Kattis default output validator that match a comparator.
+fn kattis_validator_flags(comparator: Comparator) -> String {
+    match comparator {
+        Comparator::Exact => "case_sensitive space_change_sensitive".to_owned(),
+        Comparator::Tokens => "case_sensitive".to_owned(),
+        Comparator::Lines => {
+            eprintln!(
+                "Warning: the Kattis default validator ignores line breaks, so the lines comparator is exported as tokens"
+            );
+            "case_sensitive".to_owned()
+        }
+        Comparator::CaseInsensitive => String::new(),
+        Comparator::Float {
+            abs_epsilon,
+            rel_epsilon,
+        } => format!(
+            "float_absolute_tolerance {abs_epsilon:e} float_relative_tolerance {rel_epsilon:e}"
+        ),
+    }
+}
+
+fn kattis_package(problem: &ProblemSource, format: ExportFormat) -> Vec<PackageFile> {
+    let metadata = &problem.metadata;
+    let mut files = Vec::new();
+
+    let mut yaml = format!("name: {}\n", yaml_string(&problem.title));
+    if !metadata.authors.is_empty() {
+        yaml.push_str(&format!(
+            "author: {}\n",
+            yaml_string(&metadata.authors.join(", "))
+        ));
+    }
+    if let Some(source) = &metadata.source {
+        yaml.push_str(&format!("source: {}\n", yaml_string(source)));
+    }
+    if !metadata.tags.is_empty() {
+        yaml.push_str(&format!(
+            "keywords: {}\n",
+            yaml_string(&metadata.tags.join(" "))
+        ));
+    }
+    match &problem.checker {
+        Some(_) => yaml.push_str("validation: custom\n"),
+        None => {
+            let flags = kattis_validator_flags(metadata.checker.comparator());
+            if !flags.is_empty() {
+                yaml.push_str(&format!("validator_flags: {flags}\n"));
+            }
+        }
+    }
+    yaml.push_str(&format!("limits:\n  memory: {}\n", problem.memory_limit()));
+    files.push(PackageFile::new("problem.yaml", yaml));
+
+    if format == ExportFormat::Domjudge {
+        files.push(PackageFile::new(
+            "domjudge-problem.ini",
+            format!("timelimit = '{}'\n", problem.time_limit()),
+        ));
+    }
+
+    files.push(PackageFile::new(
+        "problem_statement/problem.en.md",
+        problem.statement.clone(),
+    ));
+
+    // Sample tests are shown to contestants, the rest are kept secret
+    for (test_name, input, output) in &problem.tests {
+        let group = if test_name.starts_with("sample") {
+            "sample"
+        } else {
+            "secret"
+        };
+        files.push(PackageFile::new(
+            format!("data/{group}/{test_name}.in"),
+            input.clone(),
+        ));
+        files.push(PackageFile::new(
+            format!("data/{group}/{test_name}.ans"),
+            output.clone(),
+        ));
+    }
+
+    for (file, contents) in &problem.solutions {
+        files.push(PackageFile::new(
+            format!("submissions/accepted/{file}"),
+            contents.clone(),
+        ));
+    }
+
+    match &problem.checker {
+        Some(CheckerSource::Python(checker)) => files.push(PackageFile::executable(
+            "output_validators/checker/checker.py",
+            python_checker_program(checker, KATTIS_CHECKER_WRAPPER),
+        )),
+        Some(CheckerSource::Native { lang, source }) => {
+            eprintln!(
+                "Warning: checker.{lang} is exported as is, but {} output validators must exit with 42 (accepted) or 43 (wrong answer) instead of the testlib codes",
+                format.name()
+            );
+            files.push(PackageFile::new(
+                format!("output_validators/checker/checker.{lang}"),
+                source.clone(),
+            ));
+        }
+        None => {}
+    }
+
+    files
+}
+
+/// Polygon's name for the compiler of a language.
+fn polygon_source_type(lang: &str) -> &str {
+    match lang {
+        "cpp" => "cpp.g++17",
+        "c" => "c.gcc",
+        "py" => "python.3",
+        "java" => "java11",
+        "rs" => "rust",
+        _ => lang,
+    }
+}
+
+/// Get the testlib standard checker that is closest to a comparator.
+///
+/// Only the tokens comparator has an exact match. `fcmp` compares lines, so it
+/// doesn't tell apart outputs that only differ in their line endings or final
+/// newline, unlike the exact comparator. `lcmp` compares the tokens of each
+/// line, so it ignores whitespace inside lines, unlike the lines comparator.
+/// A warning is printed when one of these is used.
+fn polygon_std_checker(comparator: Comparator) -> Result<&'static str> {
+    match comparator {
+        Comparator::Exact => {
+            eprintln!(
+                "Warning: Polygon has no standard checker for byte-for-byte comparison, so std::fcmp.cpp is used. It compares whole lines and ignores line endings"
+            );
+            Ok("std::fcmp.cpp")
+        }
+        Comparator::Tokens => Ok("std::wcmp.cpp"),
+        Comparator::Lines => {
+            eprintln!(
+                "Warning: Polygon has no standard checker that only ignores trailing whitespace, so std::lcmp.cpp is used. It also ignores whitespace inside lines"
+            );
+            Ok("std::lcmp.cpp")
+        }
+        Comparator::CaseInsensitive => bail!(
+            "Polygon has no standard checker for case-insensitive comparison. Add a testlib checker to the problem to export it"
+        ),
+        Comparator::Float {
+            abs_epsilon,
+            rel_epsilon,
+        } => {
+            let epsilon = abs_epsilon.max(rel_epsilon);
+            let (checker, checker_epsilon) = if epsilon >= 1e-4 {
+                ("std::rcmp4.cpp", 1e-4)
+            } else if epsilon >= 1e-6 {
+                ("std::rcmp6.cpp", 1e-6)
+            } else {
+                ("std::rcmp9.cpp", 1e-9)
+            };
+            if epsilon != checker_epsilon {
+                eprintln!(
+                    "Warning: Polygon has no standard checker with an epsilon of {epsilon:e}, so {checker} is used"
+                );
+            }
+            Ok(checker)
+        }
+    }
+}
+
+fn polygon_package(problem: &ProblemSource) -> Result<Vec<PackageFile>> {
+    let mut files = Vec::new();
+
+    let width = problem.tests.len().to_string().len().max(2);
+    for (i, (_, input, output)) in problem.tests.iter().enumerate() {
+        let number = format!("{:0width$}", i + 1);
+        files.push(PackageFile::new(format!("tests/{number}"), input.clone()));
+        files.push(PackageFile::new(
+            format!("tests/{number}.a"),
+            output.clone(),
+        ));
+    }
+
+    files.push(PackageFile::new(
+        "statements/english/problem.md",
+        problem.statement.clone(),
+    ));
+
+    let checker = match &problem.checker {
+        Some(CheckerSource::Python(_)) => bail!(
+            "Polygon only supports testlib checkers, so {PYTHON_CHECKER_FILE} cannot be exported. Rewrite it as a testlib checker (e.g. checker.cpp)"
+        ),
+        Some(CheckerSource::Native { lang, source }) => {
+            let path = format!("files/check.{lang}");
+            files.push(PackageFile::new(&path, source.clone()));
+            format!(
+                "        <checker type=\"testlib\">\n            <source path=\"{path}\" type=\"{}\"/>\n        </checker>\n",
+                polygon_source_type(lang)
+            )
+        }
+        None => format!(
+            "        <checker name=\"{}\" type=\"testlib\"/>\n",
+            polygon_std_checker(problem.metadata.checker.comparator())?
+        ),
+    };
+
+    let mut solutions = String::new();
+    for (i, (file, contents)) in problem.solutions.iter().enumerate() {
+        let path = format!("solutions/{file}");
+        let lang = Path::new(file)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default();
+        let tag = if i == 0 { "main" } else { "accepted" };
+        solutions.push_str(&format!(
+            "            <solution tag=\"{tag}\">\n                <source path=\"{path}\" type=\"{}\"/>\n            </solution>\n",
+            polygon_source_type(lang)
+        ));
+        files.push(PackageFile::new(path, contents.clone()));
+    }
+
+    let tags: String = problem
+        .metadata
+        .tags
+        .iter()
+        .map(|tag| format!("        <tag value=\"{}\"/>\n", xml_escape(tag)))
+        .collect();
+
+    let xml = format!(
+        r#"<?xml version="1.0" encoding="utf-8" standalone="no"?>
+<problem short-name="{name}" revision="1">
+    <names>
+        <name language="english" value="{title}"/>
+    </names>
+    <statements>
+        <statement charset="UTF-8" language="english" path="statements/english/problem.md" type="text/markdown"/>
+    </statements>
+    <judging input-file="" output-file="">
+        <testset name="tests">
+            <time-limit>{time_limit}</time-limit>
+            <memory-limit>{memory_limit}</memory-limit>
+            <test-count>{test_count}</test-count>
+            <input-path-pattern>tests/%0{width}d</input-path-pattern>
+            <answer-path-pattern>tests/%0{width}d.a</answer-path-pattern>
+            <tests>
+{tests}            </tests>
+        </testset>
+    </judging>
+    <assets>
+{checker}        <solutions>
+{solutions}        </solutions>
+    </assets>
+    <tags>
+{tags}    </tags>
+</problem>
+"#,
+        name = xml_escape(&problem.name),
+        title = xml_escape(&problem.title),
+        time_limit = (problem.time_limit() * 1000.0).round() as u64,
+        memory_limit = problem.memory_limit() * 1024 * 1024,
+        test_count = problem.tests.len(),
+        tests = "                <test method=\"manual\"/>\n".repeat(problem.tests.len()),
+    );
+    files.push(PackageFile::new("problem.xml", xml));
+
+    Ok(files)
+}
+
+fn cms_package(problem: &ProblemSource) -> Vec<PackageFile> {
+    let mut files = Vec::new();
+
+    let yaml = format!(
+        "name: {}\ntitle: {}\ntime_limit: {:?}\nmemory_limit: {}\nn_input: {}\ninfile: \"\"\noutfile: \"\"\npublic_testcases: \"\"\ntoken_mode: disabled\n",
+        yaml_string(&problem.name),
+        yaml_string(&problem.title),
+        problem.time_limit(),
+        problem.memory_limit(),
+        problem.tests.len(),
+    );
+    files.push(PackageFile::new("task.yaml", yaml));
+
+    files.push(PackageFile::new(
+        "statement/statement.md",
+        problem.statement.clone(),
+    ));
+
+    for (i, (_, input, output)) in problem.tests.iter().enumerate() {
+        files.push(PackageFile::new(
+            format!("input/input{i}.txt"),
+            input.clone(),
+        ));
+        files.push(PackageFile::new(
+            format!("output/output{i}.txt"),
+            output.clone(),
+        ));
+    }
+
+    for (file, contents) in &problem.solutions {
+        files.push(PackageFile::new(format!("sol/{file}"), contents.clone()));
+    }
+
+    match &problem.checker {
+        Some(CheckerSource::Python(checker)) => files.push(PackageFile::executable(
+            "check/checker",
+            python_checker_program(checker, CMS_CHECKER_WRAPPER),
+        )),
+        Some(CheckerSource::Native { lang, source }) => {
+            eprintln!(
+                "Warning: checker.{lang} is exported as is, but CMS needs it compiled to check/checker and printing the score to stdout"
+            );
+            files.push(PackageFile::new(
+                format!("check/checker.{lang}"),
+                source.clone(),
+            ));
+        }
+        None => match problem.metadata.checker.comparator() {
+            Comparator::Exact | Comparator::Tokens | Comparator::Lines => {}
+            comparator => eprintln!(
+                "Warning: CMS has no built-in {comparator} comparator, so outputs will be compared ignoring whitespace"
+            ),
+        },
+    }
+
+    files
+}
+
+/// Write the package files to a zip archive.
+fn write_package(files: &[PackageFile], output: &Path) -> Result<()> {
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent).context(format!("Failed to create {}", parent.display()))?;
+    }
+    let file = File::create(output).context(format!("Failed to create {}", output.display()))?;
+    let mut zip = ZipWriter::new(file);
+
+    for file in files {
+        let options = match file.executable {
+            true => SimpleFileOptions::default().unix_permissions(0o755),
+            false => SimpleFileOptions::default(),
+        };
+        zip.start_file(&file.path, options)?;
+        zip.write_all(&file.contents)?;
+    }
+
+    zip.finish()
+        .context(format!("Failed to write {}", output.display()))?;
+
+    Ok(())
+}
+
+/// Export a problem to a package for the given judge, written as a zip archive.
+pub fn export(
+    settings: &Settings,
+    problems_dir: &Path,
+    problem_name: &str,
+    format: ExportFormat,
+    output: &Path,
+) -> Result<()> {
+    let problem_path = get_project_root()?.join(get_problem(problems_dir, problem_name)?);
+    let problem = ProblemSource::read(settings, &problem_path, problem_name)?;
+
+    let files = match format {
+        ExportFormat::Kattis | ExportFormat::Domjudge => kattis_package(&problem, format),
+        ExportFormat::Polygon => polygon_package(&problem)?,
+        ExportFormat::Cms => cms_package(&problem),
+    };
+    write_package(&files, output)?;
+
+    eprintln!(
+        "Exported '{problem_name}' as a {} package to {}",
+        format.name(),
+        output.display()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, File};
+
+    use zip::ZipArchive;
+
+    use super::{export, ExportFormat};
+    use crate::config::get_settings;
+    use crate::problem::sync_mappings::sync_mappings;
+    use crate::problem::test_support::{create_problem_dir, with_test_project};
+
+    /// Export the `two-sum` problem and return the sorted file names and the archive.
+    fn export_files(
+        problems_dir: &std::path::Path,
+        format: ExportFormat,
+    ) -> (Vec<String>, ZipArchive<File>) {
+        let settings = get_settings().expect("settings should load");
+        let output = problems_dir.join(format!("../dist/two-sum-{}.zip", format.name()));
+        export(&settings, problems_dir, "two-sum", format, &output).expect("export should work");
+
+        let archive = ZipArchive::new(File::open(&output).expect("archive should open"))
+            .expect("archive should be valid");
+        let mut names: Vec<String> = archive.file_names().map(|s| s.to_owned()).collect();
+        names.sort();
+        (names, archive)
+    }
+
+    fn read_file(archive: &mut ZipArchive<File>, name: &str) -> String {
+        std::io::read_to_string(archive.by_name(name).expect("file should be exported"))
+            .expect("file should be readable")
+    }
+
+    fn setup_problem(problems_dir: &std::path::Path, metadata: &str) {
+        create_problem_dir(problems_dir, "new", "easy", "two-sum");
+        let problem_path = problems_dir.join("new/easy/two-sum");
+        fs::write(problem_path.join("problem.toml"), metadata).expect("metadata should be written");
+        fs::write(problem_path.join("tests/a.in"), "1 2\n").expect("test should be written");
+        fs::write(problem_path.join("tests/a.out"), "3\n").expect("test should be written");
+        fs::write(
+            problem_path.join("solutions/solution.cpp"),
+            "int main() {}\n",
+        )
+        .expect("solution should be written");
+        sync_mappings(problems_dir).expect("mappings should sync");
+    }
+
+    #[test]
+    fn exports_kattis_package_with_python_checker() {
+        with_test_project(|problems_dir| {
+            setup_problem(
+                problems_dir,
+                "title = \"Two Sum\"\nauthors = [\"Alice\"]\n[limits]\nmemory = 512\n",
+            );
+            fs::write(
+                problems_dir.join("new/easy/two-sum/checker.py"),
+                "def check(process_output, judge_output, **kwargs):\n    return True\n",
+            )
+            .expect("checker should be written");
+            fs::write(
+                problems_dir.join("new/easy/two-sum/tests/sample-1.in"),
+                "2 2\n",
+            )
+            .expect("sample should be written");
+            fs::write(
+                problems_dir.join("new/easy/two-sum/tests/sample-1.out"),
+                "4\n",
+            )
+            .expect("sample should be written");
+
+            let (names, mut archive) = export_files(problems_dir, ExportFormat::Kattis);
+            assert_eq!(
+                names,
+                vec![
+                    "data/sample/sample-1.ans",
+                    "data/sample/sample-1.in",
+                    "data/secret/a.ans",
+                    "data/secret/a.in",
+                    "output_validators/checker/checker.py",
+                    "problem.yaml",
+                    "problem_statement/problem.en.md",
+                    "submissions/accepted/solution.cpp",
+                ]
+            );
+            assert_eq!(
+                read_file(&mut archive, "problem.yaml"),
+                "name: \"Two Sum\"\nauthor: \"Alice\"\nvalidation: custom\nlimits:\n  memory: 512\n"
+            );
+            assert_eq!(read_file(&mut archive, "data/sample/sample-1.ans"), "4\n");
+            let checker = read_file(&mut archive, "output_validators/checker/checker.py");
+            assert!(checker.starts_with("#!/usr/bin/env python3\ndef check("));
+            assert!(checker.contains("sys.exit(42 if check("));
+        });
+    }
+
+    #[test]
+    fn exports_polygon_package_with_standard_checker() {
+        with_test_project(|problems_dir| {
+            setup_problem(
+                problems_dir,
+                "title = \"Two & Sum\"\nchecker = \"tokens\"\ntags = [\"math\"]\n[limits]\ntime = 2.5\n",
+            );
+
+            let (names, mut archive) = export_files(problems_dir, ExportFormat::Polygon);
+            assert_eq!(
+                names,
+                vec![
+                    "problem.xml",
+                    "solutions/solution.cpp",
+                    "statements/english/problem.md",
+                    "tests/01",
+                    "tests/01.a",
+                ]
+            );
+            let xml = read_file(&mut archive, "problem.xml");
+            assert!(xml.contains("<name language=\"english\" value=\"Two &amp; Sum\"/>"));
+            assert!(xml.contains("<time-limit>2500</time-limit>"));
+            assert!(xml.contains("<memory-limit>268435456</memory-limit>"));
+            assert!(xml.contains("<checker name=\"std::wcmp.cpp\" type=\"testlib\"/>"));
+            assert!(xml.contains("<source path=\"solutions/solution.cpp\" type=\"cpp.g++17\"/>"));
+            assert!(xml.contains("<tag value=\"math\"/>"));
+        });
+    }
+
+    #[test]
+    fn exports_cms_and_domjudge_limits() {
+        with_test_project(|problems_dir| {
+            setup_problem(problems_dir, "title = \"Two Sum\"\n");
+
+            let (names, mut archive) = export_files(problems_dir, ExportFormat::Cms);
+            assert!(names.contains(&"input/input0.txt".to_owned()));
+            assert!(names.contains(&"output/output0.txt".to_owned()));
+            let task = read_file(&mut archive, "task.yaml");
+            assert!(task.contains("time_limit: 1.0\nmemory_limit: 256\nn_input: 1\n"));
+
+            let (_, mut archive) = export_files(problems_dir, ExportFormat::Domjudge);
+            assert_eq!(
+                read_file(&mut archive, "domjudge-problem.ini"),
+                "timelimit = '1'\n"
+            );
+            assert!(read_file(&mut archive, "problem.yaml")
+                .contains("validator_flags: case_sensitive space_change_sensitive\n"));
+        });
+    }
+
+    #[test]
+    fn rejects_python_checker_for_polygon() {
+        with_test_project(|problems_dir| {
+            setup_problem(problems_dir, "title = \"Two Sum\"\n");
+            fs::write(problems_dir.join("new/easy/two-sum/checker.py"), "")
+                .expect("checker should be written");
+            let settings = get_settings().expect("settings should load");
+
+            let err = export(
+                &settings,
+                problems_dir,
+                "two-sum",
+                ExportFormat::Polygon,
+                &problems_dir.join("../dist/two-sum.zip"),
+            )
+            .expect_err("export should fail");
+            assert!(err
+                .to_string()
+                .contains("Polygon only supports testlib checkers"));
+        });
+    }
+}
@@ -5,9 +5,13 @@ use std::time::Duration;
 use anyhow::{bail, Context, Result};
 use uuid::Uuid;
 
-use super::generate;
-use super::run::{RunCommand, RunResult, RunnableFile};
+use super::comparator::Comparator;
+use super::generate::{generate_input, GeneratedInput};
+use super::metadata::ProblemMetadata;
+use super::run::{print_stderr, RunCommand, RunLimits, RunResult, RunnableFile};
 use super::sync_mappings::get_problem;
+use super::validate::Validator;
+use super::verdict::Verdict;
 use crate::{config::Settings, util::get_project_root};
 
 pub struct FuzzArgs<'a> {
@@ -15,6 +19,10 @@ pub struct FuzzArgs<'a> {
     pub problem_name: String,
     pub solution_files: Vec<RunnableFile>,
     pub generator: RunnableFile,
+    /// Overrides the limits from the problem metadata.
+    pub limits: RunLimits,
+    /// Overrides the comparator from the problem metadata.
+    pub comparator: Option<Comparator>,
 }
 
 /// Generate new test cases until the solutions produce different results.
@@ -25,18 +33,27 @@ pub fn fuzz(settings: &Settings, fuzz_args: &FuzzArgs) -> Result<()> {
         problem_name,
         solution_files,
         generator,
+        limits,
+        comparator,
     } = fuzz_args;
     let problem_path = project_root.join(get_problem(problems_dir, problem_name)?);
 
+    let metadata = ProblemMetadata::load_or_default(&problem_path)?;
+    let limits = limits.or(metadata.run_limits()?);
+    let comparator = comparator.unwrap_or(metadata.checker.comparator());
+
     let mut run_commands: Vec<RunCommand> = Vec::new();
     for (i, file) in solution_files.iter().enumerate() {
-        run_commands.push(RunCommand::new(
-            settings,
-            &problem_path,
-            file,
-            problem_path.join(format!("solutions/solution_{i}.out")),
-            problem_path.join(format!("{file}")),
-        )?);
+        run_commands.push(
+            RunCommand::new(
+                settings,
+                &problem_path,
+                file,
+                problem_path.join(format!("solutions/solution_{i}.out")),
+                problem_path.join(format!("{file}")),
+            )?
+            .with_limits(limits),
+        );
     }
 
     // If there aren't at least two solutions, we can't compare so return an error
@@ -44,19 +61,32 @@ pub fn fuzz(settings: &Settings, fuzz_args: &FuzzArgs) -> Result<()> {
         bail!("At least two solutions are required for fuzzing.");
     }
 
+    // Prepare the generator and validator once rather than for every test case
+    let generator = RunCommand::new(
+        settings,
+        &problem_path,
+        generator,
+        problem_path.join("generators/generator.out"),
+        problem_path.join(format!("{generator}")),
+    )
+    .context("Failed to get generator command")?;
+    let validator = Validator::for_problem(settings, &problem_path)?;
+
     let mut total_tests = 0;
     let mut total_times: Vec<Duration> = vec![Duration::new(0, 0); run_commands.len()];
 
     // TODO: Set an optional limit?
     loop {
-        total_tests += 1;
-
         let test_name = format!("generated_{}", Uuid::new_v4());
+        let input_file_path = problem_path.join(format!("tests/{test_name}.in"));
 
-        generate::generate(settings, problems_dir, problem_name, generator, &test_name)
+        let generated = generate_input(&generator, validator.as_ref(), &input_file_path)
             .context("Failed to generate test case")?;
-
-        let input_file_path = problem_path.join(format!("tests/{test_name}.in"));
+        if let GeneratedInput::Rejected(reason) = generated {
+            eprintln!("  ! Skipping a generated test case the validator rejected: {reason}");
+            continue;
+        }
+        total_tests += 1;
 
         let mut results: Vec<RunResult> = Vec::new();
         for (i, run_cmd) in run_commands.iter().enumerate() {
@@ -68,20 +98,34 @@ pub fn fuzz(settings: &Settings, fuzz_args: &FuzzArgs) -> Result<()> {
 
         let mut passed = true;
         let mut avg_duration = Duration::new(0, 0);
+        if let Some((i, verdict)) = results
+            .iter()
+            .enumerate()
+            .find_map(|(i, result)| Verdict::from_run_result(result).map(|v| (i, v)))
+        {
+            eprintln!(
+                "  ! Test case {total_tests} (tests/{test_name}.in) failed, solution {i}: {verdict}"
+            );
+            print_stderr(&results[i].stderr);
+            passed = false;
+        }
+
         // We verify earlier that there is at least two solutions so indexing 0 is no issue
         let result_1 = &results[0];
-        for (i, result) in results.iter().enumerate().skip(1) {
-            // TODO: compare 1st, 2nd and nth result for a "best of three" (if applicable)?
-            if result_1.output.as_bytes() != result.output.as_bytes() {
-                eprintln!(
-                        "  ! Test case {total_tests} (tests/generated.in) failed, solution 0 took {:.5}s, solution {i} took {:.5}s",
-                        result_1.elapsed_time.as_secs_f64(),
-                        result.elapsed_time.as_secs_f64()
-                    );
-                passed = false;
-                break;
+        if passed {
+            for (i, result) in results.iter().enumerate().skip(1) {
+                // TODO: compare 1st, 2nd and nth result for a "best of three" (if applicable)?
+                if !comparator.compare(result_1.output.as_bytes(), result.output.as_bytes()) {
+                    eprintln!(
+                            "  ! Test case {total_tests} (tests/{test_name}.in) failed, solution 0 took {:.5}s, solution {i} took {:.5}s",
+                            result_1.elapsed_time.as_secs_f64(),
+                            result.elapsed_time.as_secs_f64()
+                        );
+                    passed = false;
+                    break;
+                }
+                avg_duration += result.elapsed_time;
             }
-            avg_duration += result.elapsed_time;
         }
 
         if passed {
@@ -119,6 +163,10 @@ pub fn fuzz(settings: &Settings, fuzz_args: &FuzzArgs) -> Result<()> {
     for run_command in run_commands {
         run_command.cleanup()?;
     }
+    generator.cleanup()?;
+    if let Some(validator) = validator {
+        validator.cleanup()?;
+    }
 
     Ok(())
 }
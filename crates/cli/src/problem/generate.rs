@@ -1,15 +1,47 @@
 use std::fs::File;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Context, Result};
 
 use super::run::{RunCommand, RunnableFile};
 use super::sync_mappings::get_problem;
+use super::validate::{validate_generated_input, Validator};
 use crate::config::Settings;
 use crate::util::get_project_root;
 
-/// Generate a test case input with a generator file.
+/// The outcome of generating a test case input.
+#[derive(Debug, Eq, PartialEq)]
+pub enum GeneratedInput {
+    Valid,
+    /// The validator rejected the input, so it was deleted again.
+    Rejected(String),
+}
+
+/// Run a prepared generator, writing its output to `test_path`. If a
+/// validator is given, the input is deleted again if it is invalid.
+pub fn generate_input(
+    generator: &RunCommand,
+    validator: Option<&Validator>,
+    test_path: &PathBuf,
+) -> Result<GeneratedInput> {
+    let result = generator
+        .get_result(None)
+        .context("Failed to get generator output")?;
+    let mut test_file = File::create(test_path).context("Failed to create test file")?;
+    test_file.write_all(result.output.as_bytes())?;
+
+    if let Some(validator) = validator {
+        if let Some(reason) = validate_generated_input(validator, test_path)? {
+            return Ok(GeneratedInput::Rejected(reason));
+        }
+    }
+
+    Ok(GeneratedInput::Valid)
+}
+
+/// Generate a test case input with a generator file. If the problem has a
+/// validator, the input is deleted again if it is invalid.
 pub fn generate(
     settings: &Settings,
     problems_dir: &Path,
@@ -34,14 +66,23 @@ pub fn generate(
         problem_path.join(format!("{generator}")),
     )
     .context("Failed to get generator command")?;
+    let validator = match Validator::for_problem(settings, &problem_path) {
+        Ok(validator) => validator,
+        Err(err) => {
+            run_command.cleanup()?;
+            return Err(err);
+        }
+    };
 
-    let result = run_command
-        .get_result(None)
-        .context("Failed to get generator output")?;
-    let mut test_file = File::create(test_path).context("Failed to create test file")?;
-    test_file.write_all(result.output.as_bytes())?;
-
+    let generated = generate_input(&run_command, validator.as_ref(), &test_path);
     run_command.cleanup()?;
+    if let Some(validator) = &validator {
+        validator.cleanup()?;
+    }
+
+    if let GeneratedInput::Rejected(reason) = generated? {
+        bail!("The generated test input is invalid and was deleted: {reason}");
+    }
 
     Ok(())
 }
@@ -0,0 +1,951 @@
+//! Import problems from the package formats of other online judges.
+//!
+//! Kattis and DOMjudge packages are recognised by their `problem.yaml`, and
+//! Polygon packages by their `problem.xml`. A package can be a zip archive or a
+//! folder, and may have all of its files in a single top-level folder.
+//!
+//! Statements written in LaTeX are converted to Markdown on a best-effort
+//! basis, so imported statements should be reviewed. Kattis output validators
+//! use a different protocol to our checkers, so only the ones exported by
+//! `aucpl problem export` are imported.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde_yaml::Value;
+use toml_edit::{value, Array, DocumentMut};
+use walkdir::WalkDir;
+use zip::ZipArchive;
+
+use crate::paths::to_unix_path;
+use crate::problem::checker::PYTHON_CHECKER_FILE;
+use crate::problem::comparator::Comparator;
+use crate::problem::create::create;
+use crate::problem::export::{KATTIS_CHECKER_WRAPPER, PYTHON_SHEBANG};
+use crate::problem::metadata::{title_from_name, CheckerType, PROBLEM_METADATA_FILE};
+use crate::problem::statement::{examples_markdown, Example};
+use crate::problem::sync_mappings::{get_problem, sync_mappings};
+use crate::util::get_project_root;
+
+/// Files of a package, keyed by their path in the package.
+type PackageFiles = BTreeMap<String, Vec<u8>>;
+
+/// A problem read from a package, ready to be written to a problem folder.
+#[derive(Debug, Default)]
+struct ImportedProblem {
+    /// Short name given by the package, if any.
+    name: Option<String>,
+    title: Option<String>,
+    authors: Vec<String>,
+    source: Option<String>,
+    tags: Vec<String>,
+    checker: Option<CheckerType>,
+    time_limit: Option<f64>,
+    memory_limit: Option<u64>,
+    statement: Option<String>,
+    /// Test names with their input and, if the package has it, output.
+    tests: Vec<(String, Vec<u8>, Option<Vec<u8>>)>,
+    /// Names of the tests that are examples in the statement.
+    samples: Vec<String>,
+    /// Solution file names with their contents, the main solution first.
+    solutions: Vec<(String, Vec<u8>)>,
+    /// Other files to copy to the problem folder, e.g. a testlib checker.
+    extra_files: Vec<(String, Vec<u8>)>,
+}
+
+/// Read the files of a zip archive or folder. Returns the files and the name of
+/// the archive or folder.
+fn read_package(path: &Path) -> Result<(PackageFiles, String)> {
+    let mut files = PackageFiles::new();
+
+    if path.is_dir() {
+        for entry in WalkDir::new(path) {
+            let entry = entry?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let relative_path = to_unix_path(entry.path().strip_prefix(path)?);
+            let contents = fs::read(entry.path())
+                .context(format!("Failed to read {}", entry.path().display()))?;
+            files.insert(relative_path, contents);
+        }
+    } else {
+        let file = File::open(path).context(format!("Failed to open {}", path.display()))?;
+        let mut archive = ZipArchive::new(file)
+            .context(format!("{} is not a zip archive or folder", path.display()))?;
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            if !entry.is_file() {
+                continue;
+            }
+            let Some(entry_path) = entry.enclosed_name() else {
+                bail!("The archive contains an unsafe path '{}'", entry.name());
+            };
+            let relative_path = to_unix_path(&entry_path);
+            let mut contents = Vec::new();
+            std::io::copy(&mut entry, &mut contents)?;
+            files.insert(relative_path, contents);
+        }
+    }
+
+    let mut name = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .context("The package path has no valid name")?
+        .to_owned();
+
+    // Packages are often zipped with all the files in one folder
+    let is_root = |files: &PackageFiles| {
+        files.contains_key("problem.yaml") || files.contains_key("problem.xml")
+    };
+    if !is_root(&files) {
+        let top_level: Vec<&str> = files
+            .keys()
+            .filter_map(|path| path.split_once('/').map(|(dir, _)| dir))
+            .collect();
+        if let Some(dir) = top_level.first().map(|dir| dir.to_string()) {
+            if files.len() == top_level.len() && top_level.iter().all(|d| *d == dir) {
+                let prefix = format!("{dir}/");
+                files = files
+                    .into_iter()
+                    .map(|(path, contents)| (path[prefix.len()..].to_owned(), contents))
+                    .collect();
+                name = dir;
+            }
+        }
+    }
+
+    Ok((files, name))
+}
+
+/// Get the file stem and extension of a path in a package.
+fn split_file_name(path: &str) -> (&str, &str) {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    file_name.rsplit_once('.').unwrap_or((file_name, ""))
+}
+
+fn utf8(path: &str, contents: &[u8]) -> Result<String> {
+    String::from_utf8(contents.to_vec()).context(format!("{path} is not valid UTF-8"))
+}
+
+fn latex_command_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"\\(textbf|emph|textit|texttt|section\*?|subsection\*?)\{([^{}]*)\}")
+            .expect("regex should be valid")
+    })
+}
+
+/// Convert a LaTeX statement to Markdown. Only the common commands are
+/// converted, and math is kept as is.
+fn latex_to_markdown(latex: &str) -> String {
+    let mut lines = Vec::new();
+    for line in latex.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('%')
+            || trimmed.starts_with("\\problemname")
+            || trimmed.starts_with("\\illustration")
+            || trimmed.starts_with("\\begin{")
+            || trimmed.starts_with("\\end{")
+        {
+            continue;
+        }
+
+        let line = latex_command_regex().replace_all(line, |caps: &regex::Captures| {
+            let text = &caps[2];
+            match &caps[1] {
+                "textbf" => format!("**{text}**"),
+                "emph" | "textit" => format!("*{text}*"),
+                "texttt" => format!("`{text}`"),
+                "section" | "section*" => format!("\n## {text}\n"),
+                _ => format!("\n### {text}\n"),
+            }
+        });
+        let line = match line.trim_start().strip_prefix("\\item") {
+            Some(item) => format!("- {}", item.trim()),
+            None => line.trim_end_matches("\\\\").to_owned(),
+        };
+        lines.push(line);
+    }
+
+    let markdown = lines.join("\n");
+    let blank_lines = Regex::new(r"\n{3,}").expect("regex should be valid");
+    blank_lines
+        .replace_all(markdown.trim(), "\n\n")
+        .into_owned()
+}
+
+/// Get a string from a YAML value that is either a string or a map of
+/// languages to strings, preferring English.
+fn yaml_localised_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.to_owned()),
+        Value::Mapping(map) => map
+            .get("en")
+            .or_else(|| map.values().next())
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_owned()),
+        _ => None,
+    }
+}
+
+/// Get the comparator that matches the Kattis default output validator flags.
+fn comparator_from_validator_flags(flags: &str) -> Comparator {
+    let flags: Vec<&str> = flags.split_whitespace().collect();
+    let flag_value = |name: &str| {
+        flags
+            .iter()
+            .position(|flag| *flag == name)
+            .and_then(|i| flags.get(i + 1))
+            .and_then(|value| value.parse::<f64>().ok())
+    };
+
+    let tolerance = flag_value("float_tolerance");
+    let abs_epsilon = flag_value("float_absolute_tolerance").or(tolerance);
+    let rel_epsilon = flag_value("float_relative_tolerance").or(tolerance);
+    if abs_epsilon.is_some() || rel_epsilon.is_some() {
+        return Comparator::Float {
+            abs_epsilon: abs_epsilon.unwrap_or_default(),
+            rel_epsilon: rel_epsilon.unwrap_or_default(),
+        };
+    }
+
+    match (
+        flags.contains(&"case_sensitive"),
+        flags.contains(&"space_change_sensitive"),
+    ) {
+        (true, true) => Comparator::Exact,
+        (true, false) => Comparator::Tokens,
+        (false, _) => Comparator::CaseInsensitive,
+    }
+}
+
+/// Add the tests in a folder of a Kattis package, named after their path in
+/// the folder with the given prefix. The prefix is left out if the name already
+/// starts with it, e.g. for samples that were exported as `sample-1`.
+fn add_kattis_tests(
+    files: &PackageFiles,
+    dir: &str,
+    prefix: &str,
+    problem: &mut ImportedProblem,
+) -> Vec<String> {
+    let mut test_names = Vec::new();
+    let dir_prefix = format!("{dir}/");
+    for (path, input) in files.range(dir_prefix.clone()..) {
+        let Some(relative_path) = path.strip_prefix(&dir_prefix) else {
+            break;
+        };
+        let Some(stem) = relative_path.strip_suffix(".in") else {
+            continue;
+        };
+        let name = stem.replace('/', "-");
+        let test_name = if name.starts_with(prefix.trim_end_matches('-')) {
+            name
+        } else {
+            format!("{prefix}{name}")
+        };
+        let output = files.get(&format!("{dir}/{stem}.ans")).cloned();
+        problem
+            .tests
+            .push((test_name.clone(), input.clone(), output));
+        test_names.push(test_name);
+    }
+    test_names
+}
+
+fn read_kattis(files: &PackageFiles) -> Result<ImportedProblem> {
+    let yaml_contents = utf8("problem.yaml", &files["problem.yaml"])?;
+    let yaml: Value =
+        serde_yaml::from_str(&yaml_contents).context("Failed to parse problem.yaml")?;
+
+    let mut problem = ImportedProblem {
+        title: yaml.get("name").and_then(yaml_localised_string),
+        source: yaml.get("source").and_then(|source| match source {
+            Value::Mapping(map) => map.get("name").and_then(|v| v.as_str()).map(String::from),
+            _ => source.as_str().map(String::from),
+        }),
+        ..Default::default()
+    };
+
+    if let Some(authors) = yaml.get("author").and_then(|v| v.as_str()) {
+        problem.authors = authors
+            .split([',', '&'])
+            .flat_map(|s| s.split(" and "))
+            .map(|s| s.trim().to_owned())
+            .filter(|s| !s.is_empty())
+            .collect();
+    }
+    match yaml.get("keywords") {
+        Some(Value::String(keywords)) => {
+            problem.tags = keywords.split_whitespace().map(String::from).collect()
+        }
+        Some(Value::Sequence(keywords)) => {
+            problem.tags = keywords
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(String::from)
+                .collect()
+        }
+        _ => {}
+    }
+
+    let validation = yaml
+        .get("validation")
+        .and_then(|v| v.as_str())
+        .unwrap_or("default");
+    if validation.starts_with("custom") {
+        // Validators exported by this tool wrap a `checker.py` that can be restored
+        let exported_checker = files
+            .range("output_validators/".to_owned()..)
+            .take_while(|(path, _)| path.starts_with("output_validators/"))
+            .find_map(|(path, contents)| {
+                let contents = String::from_utf8(contents.clone()).ok()?;
+                let checker = contents
+                    .strip_prefix(PYTHON_SHEBANG)?
+                    .strip_suffix(KATTIS_CHECKER_WRAPPER)?;
+                path.ends_with(".py").then(|| format!("{checker}\n"))
+            });
+        match exported_checker {
+            Some(checker) => problem
+                .extra_files
+                .push((PYTHON_CHECKER_FILE.to_owned(), checker.into_bytes())),
+            None => eprintln!(
+                "Warning: the package has a custom output validator, which cannot be imported. Add a checker to the problem by hand"
+            ),
+        }
+        problem.checker = Some(CheckerType::Custom);
+    } else {
+        let flags = yaml
+            .get("validator_flags")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        problem.checker = Some(CheckerType::Builtin(comparator_from_validator_flags(flags)));
+    }
+
+    let limits = yaml.get("limits");
+    problem.memory_limit = limits
+        .and_then(|l| l.get("memory"))
+        .and_then(|v| v.as_u64());
+    problem.time_limit = limits
+        .and_then(|l| l.get("time_limit"))
+        .and_then(|v| v.as_f64());
+    if let Some(ini) = files.get("domjudge-problem.ini") {
+        let ini = utf8("domjudge-problem.ini", ini)?;
+        for line in ini.lines() {
+            if let Some((key, value)) = line.split_once('=') {
+                if key.trim() == "timelimit" {
+                    problem.time_limit = value.trim().trim_matches(['\'', '"']).parse().ok();
+                }
+            }
+        }
+    } else if let Some(time_limit) = files.get(".timelimit") {
+        problem.time_limit = utf8(".timelimit", time_limit)?.trim().parse().ok();
+    }
+
+    for dir in ["problem_statement", "statement"] {
+        let markdown = ["problem.en.md", "problem.md"]
+            .iter()
+            .find_map(|name| files.get_key_value(&format!("{dir}/{name}")));
+        let latex = ["problem.en.tex", "problem.tex"]
+            .iter()
+            .find_map(|name| files.get_key_value(&format!("{dir}/{name}")));
+        if let Some((path, contents)) = markdown {
+            problem.statement = Some(utf8(path, contents)?);
+        } else if let Some((path, contents)) = latex {
+            problem.statement = Some(latex_to_markdown(&utf8(path, contents)?));
+        } else {
+            continue;
+        }
+        break;
+    }
+
+    problem.samples = add_kattis_tests(files, "data/sample", "sample-", &mut problem);
+    add_kattis_tests(files, "data/secret", "", &mut problem);
+
+    for (path, contents) in files.range("submissions/accepted/".to_owned()..) {
+        if !path.starts_with("submissions/accepted/") {
+            break;
+        }
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        problem
+            .solutions
+            .push((file_name.to_owned(), contents.clone()));
+    }
+
+    Ok(problem)
+}
+
+/// Get the testlib standard checker's comparator.
+fn comparator_from_std_checker(name: &str) -> Option<Comparator> {
+    let float = |epsilon: f64| Comparator::Float {
+        abs_epsilon: epsilon,
+        rel_epsilon: epsilon,
+    };
+    match name.trim_start_matches("std::").trim_end_matches(".cpp") {
+        "fcmp" => Some(Comparator::Exact),
+        "wcmp" | "ncmp" | "yesno" => Some(Comparator::Tokens),
+        "lcmp" => Some(Comparator::Lines),
+        "nyesno" => Some(Comparator::CaseInsensitive),
+        "rcmp4" => Some(float(1e-4)),
+        "rcmp6" => Some(float(1e-6)),
+        "rcmp9" | "rcmp" => Some(float(1e-9)),
+        _ => None,
+    }
+}
+
+/// Format a test number with a printf-style pattern such as `tests/%02d`.
+fn format_test_path(pattern: &str, number: usize) -> String {
+    let re = Regex::new(r"%0?(\d*)d").expect("regex should be valid");
+    re.replace(pattern, |caps: &regex::Captures| {
+        let width = caps[1].parse().unwrap_or(0);
+        format!("{number:0width$}")
+    })
+    .into_owned()
+}
+
+/// Build a statement from the LaTeX sections of a Polygon statement.
+fn polygon_statement(sections: &BTreeMap<&str, String>) -> String {
+    let mut statement = latex_to_markdown(sections.get("legend").map_or("", |s| s));
+    for (key, heading) in [("input", "Input"), ("output", "Output"), ("notes", "Note")] {
+        if let Some(section) = sections.get(key).filter(|s| !s.trim().is_empty()) {
+            statement.push_str(&format!(
+                "\n\n## {heading}\n\n{}",
+                latex_to_markdown(section)
+            ));
+        }
+    }
+    statement
+}
+
+fn read_polygon(files: &PackageFiles) -> Result<ImportedProblem> {
+    let xml = utf8("problem.xml", &files["problem.xml"])?;
+    let document = roxmltree::Document::parse(&xml).context("Failed to parse problem.xml")?;
+    let root = document.root_element();
+    let find = |name: &str| root.descendants().find(|node| node.has_tag_name(name));
+
+    let mut problem = ImportedProblem {
+        name: root.attribute("short-name").map(String::from),
+        ..Default::default()
+    };
+
+    let names: Vec<_> = root
+        .descendants()
+        .filter(|node| {
+            node.has_tag_name("name") && node.parent().is_some_and(|p| p.has_tag_name("names"))
+        })
+        .collect();
+    problem.title = names
+        .iter()
+        .find(|node| node.attribute("language") == Some("english"))
+        .or(names.first())
+        .and_then(|node| node.attribute("value"))
+        .map(String::from);
+
+    problem.tags = root
+        .descendants()
+        .filter(|node| node.has_tag_name("tag"))
+        .filter_map(|node| node.attribute("value"))
+        .map(String::from)
+        .collect();
+
+    let testset = root
+        .descendants()
+        .find(|node| node.has_tag_name("testset") && node.attribute("name") == Some("tests"))
+        .context("problem.xml has no 'tests' testset")?;
+    let child_text = |name: &str| {
+        testset
+            .children()
+            .find(|node| node.has_tag_name(name))
+            .and_then(|node| node.text())
+            .map(|text| text.trim().to_owned())
+    };
+    problem.time_limit = child_text("time-limit")
+        .and_then(|ms| ms.parse::<f64>().ok())
+        .map(|ms| ms / 1000.0);
+    problem.memory_limit = child_text("memory-limit")
+        .and_then(|bytes| bytes.parse::<u64>().ok())
+        .map(|bytes| bytes / 1024 / 1024);
+
+    let input_pattern = child_text("input-path-pattern").unwrap_or("tests/%02d".to_owned());
+    let answer_pattern = child_text("answer-path-pattern").unwrap_or("tests/%02d.a".to_owned());
+    let tests: Vec<_> = testset
+        .descendants()
+        .filter(|node| node.has_tag_name("test"))
+        .collect();
+    for (i, test) in tests.iter().enumerate() {
+        let input_path = format_test_path(&input_pattern, i + 1);
+        let Some(input) = files.get(&input_path) else {
+            bail!("Test file '{input_path}' is missing from the package");
+        };
+        let test_name = split_file_name(&input_path).0.to_owned();
+        let output = files
+            .get(&format_test_path(&answer_pattern, i + 1))
+            .cloned();
+        if test.attribute("sample") == Some("true") {
+            problem.samples.push(test_name.clone());
+        }
+        problem.tests.push((test_name, input.clone(), output));
+    }
+
+    if let Some(checker) = find("checker") {
+        let comparator = checker
+            .attribute("name")
+            .and_then(comparator_from_std_checker);
+        let source_path = checker
+            .children()
+            .find(|node| node.has_tag_name("source"))
+            .and_then(|node| node.attribute("path"));
+        match (comparator, source_path) {
+            (Some(comparator), _) => problem.checker = Some(CheckerType::Builtin(comparator)),
+            (None, Some(path)) => {
+                let source = files
+                    .get(path)
+                    .context(format!("Checker '{path}' is missing from the package"))?;
+                let (_, lang) = split_file_name(path);
+                problem
+                    .extra_files
+                    .push((format!("checker.{lang}"), source.clone()));
+                if let Some(testlib) = files.get("files/testlib.h") {
+                    problem
+                        .extra_files
+                        .push(("testlib.h".to_owned(), testlib.clone()));
+                }
+                problem.checker = Some(CheckerType::Custom);
+            }
+            (None, None) => {}
+        }
+    }
+
+    let mut solutions: Vec<(bool, String, Vec<u8>)> = Vec::new();
+    for solution in root
+        .descendants()
+        .filter(|node| node.has_tag_name("solution"))
+    {
+        let tag = solution.attribute("tag").unwrap_or_default();
+        if tag != "main" && tag != "accepted" {
+            continue;
+        }
+        let Some(path) = solution
+            .children()
+            .find(|node| node.has_tag_name("source"))
+            .and_then(|node| node.attribute("path"))
+        else {
+            continue;
+        };
+        if let Some(contents) = files.get(path) {
+            let file_name = path.rsplit('/').next().unwrap_or(path).to_owned();
+            solutions.push((tag == "main", file_name, contents.clone()));
+        }
+    }
+    solutions.sort_by_key(|(is_main, _, _)| !is_main);
+    problem.solutions = solutions
+        .into_iter()
+        .map(|(_, name, contents)| (name, contents))
+        .collect();
+
+    if let Some(statement) = files.get("statements/english/problem.md") {
+        problem.statement = Some(utf8("problem.md", statement)?);
+    } else if let Some(properties) = files.get("statements/english/problem-properties.json") {
+        let properties: serde_json::Value = serde_json::from_slice(properties)
+            .context("Failed to parse problem-properties.json")?;
+        let sections = ["legend", "input", "output", "notes"]
+            .into_iter()
+            .filter_map(|key| Some((key, properties.get(key)?.as_str()?.to_owned())))
+            .collect();
+        problem.statement = Some(polygon_statement(&sections));
+    } else {
+        let sections: BTreeMap<&str, String> = ["legend", "input", "output", "notes"]
+            .into_iter()
+            .filter_map(|key| {
+                let path = format!("statement-sections/english/{key}.tex");
+                let contents = files.get(&path)?;
+                Some(utf8(&path, contents).map(|s| (key, s)))
+            })
+            .collect::<Result<_>>()?;
+        if !sections.is_empty() {
+            problem.statement = Some(polygon_statement(&sections));
+        }
+    }
+
+    Ok(problem)
+}
+
+/// Give each language's first solution the default solution name, e.g.
+/// `solution.cpp`, so that it is used by `aucpl problem solve`.
+fn rename_solutions(solutions: &mut [(String, Vec<u8>)]) {
+    let mut names: Vec<String> = solutions.iter().map(|(name, _)| name.to_owned()).collect();
+    for (name, _) in solutions.iter_mut() {
+        let (_, lang) = split_file_name(name);
+        let default_name = format!("solution.{lang}");
+        if !names.contains(&default_name) {
+            names.push(default_name.clone());
+            *name = default_name;
+        }
+    }
+}
+
+/// Write the problem statement, adding the title and examples if it has none.
+fn statement_markdown(problem: &ImportedProblem, title: &str) -> Result<Option<String>> {
+    let Some(statement) = &problem.statement else {
+        return Ok(None);
+    };
+
+    let mut markdown = statement.trim().to_owned();
+    if !markdown.starts_with("# ") {
+        markdown = format!("# {title}\n\n{markdown}");
+    }
+    if !markdown.contains("## Example") {
+        let mut examples = Vec::new();
+        for test_name in &problem.samples {
+            let Some((_, input, output)) =
+                problem.tests.iter().find(|(name, _, _)| name == test_name)
+            else {
+                continue;
+            };
+            examples.push(Example {
+                input: utf8(test_name, input)?,
+                output: output
+                    .as_ref()
+                    .map(|output| utf8(test_name, output))
+                    .transpose()?,
+            });
+        }
+        if !examples.is_empty() {
+            markdown.push_str("\n\n");
+            markdown.push_str(&examples_markdown(&examples));
+        }
+    }
+    markdown.push('\n');
+
+    Ok(Some(markdown))
+}
+
+/// Set the metadata from the package in the new problem's metadata file,
+/// keeping the template's comments.
+fn write_metadata(problem_path: &Path, problem: &ImportedProblem) -> Result<()> {
+    let path = problem_path.join(PROBLEM_METADATA_FILE);
+    let contents =
+        fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))?;
+    let mut document: DocumentMut = contents
+        .parse()
+        .context(format!("Failed to parse {}", path.display()))?;
+
+    let array = |items: &[String]| value(items.iter().map(String::as_str).collect::<Array>());
+    if let Some(title) = &problem.title {
+        document["title"] = value(title);
+    }
+    document["tags"] = array(&problem.tags);
+    document["authors"] = array(&problem.authors);
+    if let Some(source) = &problem.source {
+        document["source"] = value(source);
+    }
+    if let Some(checker) = problem.checker {
+        document["checker"] = value(checker.to_string());
+    }
+    if let Some(time_limit) = problem.time_limit {
+        document["limits"]["time"] = value(time_limit);
+    }
+    if let Some(memory_limit) = problem.memory_limit {
+        document["limits"]["memory"] = value(memory_limit as i64);
+    }
+
+    fs::write(&path, document.to_string())
+        .context(format!("Failed to write {}", path.display()))?;
+
+    Ok(())
+}
+
+/// Write the imported files to the new problem folder.
+fn write_problem(problem_path: &Path, problem: &mut ImportedProblem, title: &str) -> Result<()> {
+    match statement_markdown(problem, title)? {
+        Some(statement) => fs::write(problem_path.join("problem.md"), statement)?,
+        None => eprintln!("Warning: the package has no statement that can be imported"),
+    }
+    write_metadata(problem_path, problem)?;
+
+    let mut missing_outputs = 0;
+    for (test_name, input, output) in &problem.tests {
+        fs::write(problem_path.join(format!("tests/{test_name}.in")), input)?;
+        match output {
+            Some(output) => fs::write(problem_path.join(format!("tests/{test_name}.out")), output)?,
+            None => missing_outputs += 1,
+        }
+    }
+    if missing_outputs > 0 {
+        eprintln!(
+            "Warning: {missing_outputs} tests have no output. Run `aucpl problem solve` to generate them"
+        );
+    }
+
+    rename_solutions(&mut problem.solutions);
+    for (file_name, contents) in &problem.solutions {
+        fs::write(problem_path.join("solutions").join(file_name), contents)?;
+    }
+    for (file_name, contents) in &problem.extra_files {
+        fs::write(problem_path.join(file_name), contents)?;
+    }
+
+    Ok(())
+}
+
+/// Import a problem from a Kattis, DOMjudge or Polygon package into a new
+/// problem in the given category. The problem is named after the package
+/// unless a name is given.
+pub fn import(
+    problems_dir: &Path,
+    package_path: &Path,
+    category: &str,
+    problem_name: Option<&str>,
+) -> Result<String> {
+    let (files, package_name) = read_package(package_path)?;
+
+    let mut problem = if files.contains_key("problem.yaml") {
+        read_kattis(&files)?
+    } else if files.contains_key("problem.xml") {
+        read_polygon(&files)?
+    } else {
+        bail!(
+            "{} is not a Kattis, DOMjudge or Polygon package. Expected a problem.yaml or problem.xml file",
+            package_path.display()
+        );
+    };
+    if problem.tests.is_empty() {
+        bail!("The package has no tests");
+    }
+
+    let problem_name = match problem_name {
+        Some(name) => name.to_owned(),
+        None => problem.name.clone().unwrap_or(package_name),
+    };
+    create(problems_dir, &problem_name, category)?;
+
+    let problem_path = get_project_root()?.join(get_problem(problems_dir, &problem_name)?);
+    let title = problem
+        .title
+        .clone()
+        .unwrap_or_else(|| title_from_name(&problem_name));
+    if let Err(err) = write_problem(&problem_path, &mut problem, &title) {
+        // Don't leave a half-imported problem behind
+        fs::remove_dir_all(&problem_path)?;
+        sync_mappings(problems_dir)?;
+        return Err(err.context(format!("Failed to import '{problem_name}'")));
+    }
+
+    eprintln!(
+        "Imported '{problem_name}' with {} tests and {} solutions",
+        problem.tests.len(),
+        problem.solutions.len()
+    );
+
+    Ok(problem_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{import, latex_to_markdown};
+    use crate::config::get_settings;
+    use crate::problem::comparator::Comparator;
+    use crate::problem::export::{export, ExportFormat};
+    use crate::problem::metadata::{CheckerType, ProblemMetadata};
+    use crate::problem::sync_mappings::sync_mappings;
+    use crate::problem::test_support::{create_problem_dir, with_test_project};
+
+    #[test]
+    fn imports_kattis_package_folder() {
+        with_test_project(|problems_dir| {
+            let package = problems_dir.join("../packages/twosum");
+            fs::create_dir_all(package.join("problem_statement"))
+                .expect("folder should be created");
+            fs::create_dir_all(package.join("data/sample")).expect("folder should be created");
+            fs::create_dir_all(package.join("data/secret/group1"))
+                .expect("folder should be created");
+            fs::create_dir_all(package.join("submissions/accepted"))
+                .expect("folder should be created");
+            fs::write(
+                package.join("problem.yaml"),
+                "name: Two Sum\nauthor: Alice and Bob\nvalidator_flags: case_sensitive\nlimits:\n  memory: 512\n",
+            )
+            .expect("file should be written");
+            fs::write(package.join(".timelimit"), "2\n").expect("file should be written");
+            fs::write(
+                package.join("problem_statement/problem.en.tex"),
+                "\\problemname{Two Sum}\nAdd \\textbf{two} numbers.\n\\section*{Input}\nTwo integers $a$ and $b$.\n",
+            )
+            .expect("statement should be written");
+            fs::write(package.join("data/sample/1.in"), "1 2\n").expect("test should be written");
+            fs::write(package.join("data/sample/1.ans"), "3\n").expect("test should be written");
+            fs::write(package.join("data/secret/group1/big.in"), "5 5\n")
+                .expect("test should be written");
+            fs::write(package.join("data/secret/group1/big.ans"), "10\n")
+                .expect("test should be written");
+            fs::write(package.join("submissions/accepted/alice.cpp"), "// ok\n")
+                .expect("solution should be written");
+
+            let name = import(problems_dir, &package, "easy", None).expect("import should work");
+            assert_eq!(name, "twosum");
+
+            let problem_path = problems_dir.join("new/easy/twosum");
+            assert_eq!(
+                fs::read_to_string(problem_path.join("problem.md")).expect("file should be readable"),
+                "# Two Sum\n\nAdd **two** numbers.\n\n## Input\n\nTwo integers $a$ and $b$.\n\n## Example\n\n### Input\n\n```\n1 2\n```\n\n### Output\n\n```\n3\n```\n"
+            );
+            assert_eq!(
+                fs::read_to_string(problem_path.join("tests/group1-big.out"))
+                    .expect("file should be readable"),
+                "10\n"
+            );
+            assert!(problem_path.join("tests/sample-1.in").is_file());
+            assert!(problem_path.join("solutions/solution.cpp").is_file());
+
+            let metadata = ProblemMetadata::load(&problem_path)
+                .expect("metadata should load")
+                .expect("metadata should exist");
+            assert_eq!(metadata.title, "Two Sum");
+            assert_eq!(metadata.authors, vec!["Alice", "Bob"]);
+            assert_eq!(metadata.checker, CheckerType::Builtin(Comparator::Tokens));
+            assert_eq!(metadata.limits.time, Some(2.0));
+            assert_eq!(metadata.limits.memory, Some(512));
+        });
+    }
+
+    #[test]
+    fn imports_polygon_package_zip() {
+        with_test_project(|problems_dir| {
+            let package = problems_dir.join("../polygon.zip");
+            let mut zip =
+                zip::ZipWriter::new(fs::File::create(&package).expect("package should be created"));
+            let options = zip::write::SimpleFileOptions::default();
+            let files: &[(&str, &str)] = &[
+                (
+                    "pkg/problem.xml",
+                    r#"<problem short-name="two-sum"><names><name language="english" value="Two Sum"/></names>
+<judging><testset name="tests"><time-limit>1500</time-limit><memory-limit>268435456</memory-limit>
+<input-path-pattern>tests/%02d</input-path-pattern><answer-path-pattern>tests/%02d.a</answer-path-pattern>
+<tests><test method="manual" sample="true"/><test method="manual"/></tests></testset></judging>
+<assets><checker name="std::rcmp6.cpp" type="testlib"/>
+<solutions><solution tag="accepted"><source path="solutions/slow.py"/></solution>
+<solution tag="main"><source path="solutions/main.cpp"/></solution></solutions></assets>
+<tags><tag value="math"/></tags></problem>"#,
+                ),
+                ("pkg/tests/01", "1 2\n"),
+                ("pkg/tests/01.a", "3\n"),
+                ("pkg/tests/02", "5 5\n"),
+                ("pkg/solutions/main.cpp", "// main\n"),
+                ("pkg/solutions/slow.py", "# slow\n"),
+                (
+                    "pkg/statements/english/problem.md",
+                    "# Two Sum\n\nAdd them.\n",
+                ),
+            ];
+            for (path, contents) in files {
+                zip.start_file(*path, options)
+                    .expect("package file should be written");
+                std::io::Write::write_all(&mut zip, contents.as_bytes())
+                    .expect("package file should be written");
+            }
+            zip.finish().expect("package should be written");
+
+            let name = import(problems_dir, &package, "math", None).expect("import should work");
+            assert_eq!(name, "two-sum");
+
+            let problem_path = problems_dir.join("new/math/two-sum");
+            assert!(problem_path.join("tests/01.out").is_file());
+            assert!(!problem_path.join("tests/02.out").exists());
+            assert_eq!(
+                fs::read_to_string(problem_path.join("solutions/solution.cpp"))
+                    .expect("file should be readable"),
+                "// main\n"
+            );
+            assert!(problem_path.join("solutions/solution.py").is_file());
+
+            let metadata = ProblemMetadata::load(&problem_path)
+                .expect("metadata should load")
+                .expect("metadata should exist");
+            assert_eq!(metadata.tags, vec!["math"]);
+            assert_eq!(metadata.limits.time, Some(1.5));
+            assert_eq!(metadata.limits.memory, Some(256));
+            assert_eq!(
+                metadata.checker,
+                CheckerType::Builtin(Comparator::Float {
+                    abs_epsilon: 1e-6,
+                    rel_epsilon: 1e-6
+                })
+            );
+        });
+    }
+
+    #[test]
+    fn restores_exported_python_checker() {
+        with_test_project(|problems_dir| {
+            create_problem_dir(problems_dir, "new", "easy", "two-sum");
+            let problem_path = problems_dir.join("new/easy/two-sum");
+            let checker = "def check(process_output, judge_output, **kwargs):\n    return True\n";
+            fs::write(problem_path.join("checker.py"), checker).expect("checker should be written");
+            fs::write(problem_path.join("tests/a.in"), "1 2\n").expect("test should be written");
+            fs::write(problem_path.join("tests/a.out"), "3\n").expect("test should be written");
+            fs::write(problem_path.join("tests/sample-1.in"), "2 2\n")
+                .expect("test should be written");
+            fs::write(problem_path.join("tests/sample-1.out"), "4\n")
+                .expect("test should be written");
+            sync_mappings(problems_dir).expect("mappings should sync");
+            let settings = get_settings().expect("settings should load");
+            let package = problems_dir.join("../two-sum-kattis.zip");
+            export(
+                &settings,
+                problems_dir,
+                "two-sum",
+                ExportFormat::Kattis,
+                &package,
+            )
+            .expect("export should work");
+
+            import(problems_dir, &package, "dp", Some("two-sum-copy")).expect("import should work");
+
+            let imported_path = problems_dir.join("new/dp/two-sum-copy");
+            assert_eq!(
+                fs::read_to_string(imported_path.join("checker.py"))
+                    .expect("file should be readable"),
+                checker
+            );
+            assert_eq!(
+                fs::read_to_string(imported_path.join("tests/a.out"))
+                    .expect("file should be readable"),
+                "3\n"
+            );
+            assert_eq!(
+                fs::read_to_string(imported_path.join("tests/sample-1.out"))
+                    .expect("file should be readable"),
+                "4\n"
+            );
+            assert!(!imported_path.join("tests/sample-sample-1.in").exists());
+        });
+    }
+
+    #[test]
+    fn rejects_unknown_package() {
+        with_test_project(|problems_dir| {
+            let package = problems_dir.join("../unknown");
+            fs::create_dir_all(&package).expect("folder should be created");
+            fs::write(package.join("README"), "").expect("file should be written");
+
+            assert!(import(problems_dir, &package, "easy", None).is_err());
+            assert!(!problems_dir.join("new/easy/unknown").exists());
+        });
+    }
+
+    #[test]
+    fn converts_latex_lists() {
+        assert_eq!(
+            latex_to_markdown(
+                "\\begin{itemize}\n  \\item \\emph{one}\n  \\item two\n\\end{itemize}"
+            ),
+            "- *one*\n- two"
+        );
+    }
+}
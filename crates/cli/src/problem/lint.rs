@@ -0,0 +1,275 @@
+//! Lint rules for problem statements.
+//!
+//! The statement must have non-empty `## Input`, `## Output`, `## Constraints`
+//! and `## Example` sections, must not contain text left over from
+//! the template, must only use `$...$` and `$$...$$` for math, and should not
+//! have overly long lines. Text in code blocks is not linted.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::problem::statement::{parse_examples, PROBLEM_STATEMENT_FILE};
+
+/// Lines longer than this many characters are reported, except in code blocks
+/// and tables.
+pub const MAX_LINE_LENGTH: usize = 120;
+
+/// Text from the statement template that should be replaced.
+const PLACEHOLDERS: &[&str] = &["Problem Title", "Problem description."];
+
+/// Math delimiters that the renderer doesn't support, with the ones to use instead.
+const UNSUPPORTED_MATH_DELIMITERS: &[(&str, &str)] = &[
+    ("\\(", "$...$"),
+    ("\\)", "$...$"),
+    ("\\[", "$$...$$"),
+    ("\\]", "$$...$$"),
+];
+
+/// A problem found in a statement, at a 1-based line number.
+#[derive(Debug, PartialEq)]
+pub struct LintIssue {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{PROBLEM_STATEMENT_FILE}:{}: {}",
+            self.line, self.message
+        )
+    }
+}
+
+/// A heading and whether any text follows it before the next section.
+struct Section {
+    line: usize,
+    level: usize,
+    title: String,
+    has_content: bool,
+}
+
+/// Remove inline code spans from a line, so that their contents aren't linted.
+fn strip_code_spans(line: &str) -> String {
+    let mut result = String::new();
+    let mut in_code = false;
+    for c in line.chars() {
+        if c == '`' {
+            in_code = !in_code;
+        } else if !in_code {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Count the `$` math delimiters on a line, ignoring escaped `\$`.
+fn count_dollars(line: &str) -> usize {
+    let mut count = 0;
+    let mut escaped = false;
+    for c in line.chars() {
+        match c {
+            '\\' => {
+                escaped = !escaped;
+                continue;
+            }
+            '$' if !escaped => count += 1,
+            _ => {}
+        }
+        escaped = false;
+    }
+    count
+}
+
+/// Lint a problem statement.
+pub fn lint_statement(markdown: &str) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    let mut sections: Vec<Section> = Vec::new();
+    let mut in_code_block = false;
+
+    for (i, line) in markdown.lines().enumerate() {
+        let line_number = i + 1;
+
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            if let Some(section) = sections.last_mut() {
+                section.has_content = true;
+            }
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        let level = line.chars().take_while(|&c| c == '#').count();
+        if level > 0 && line[level..].starts_with(' ') {
+            sections.push(Section {
+                line: line_number,
+                level,
+                title: line[level..].trim().to_owned(),
+                has_content: false,
+            });
+        } else if !line.trim().is_empty() {
+            if let Some(section) = sections.last_mut() {
+                section.has_content = true;
+            }
+        }
+
+        for placeholder in PLACEHOLDERS {
+            if line.contains(placeholder) {
+                issues.push(LintIssue {
+                    line: line_number,
+                    message: format!("Replace the template text \"{placeholder}\""),
+                });
+            }
+        }
+
+        let text = strip_code_spans(line);
+        for (delimiter, replacement) in UNSUPPORTED_MATH_DELIMITERS {
+            if text.contains(delimiter) {
+                issues.push(LintIssue {
+                    line: line_number,
+                    message: format!("Use {replacement} for math instead of `{delimiter}`"),
+                });
+                break;
+            }
+        }
+        if !count_dollars(&text).is_multiple_of(2) {
+            issues.push(LintIssue {
+                line: line_number,
+                message: "Math is not closed: the line has an odd number of `$` delimiters"
+                    .to_owned(),
+            });
+        }
+
+        let length = line.chars().count();
+        if length > MAX_LINE_LENGTH && !line.trim_start().starts_with('|') {
+            issues.push(LintIssue {
+                line: line_number,
+                message: format!(
+                    "Line is {length} characters long, more than {MAX_LINE_LENGTH}. Split it over multiple lines"
+                ),
+            });
+        }
+    }
+
+    // A section only counts as empty if its subsections are too
+    for i in (0..sections.len()).rev() {
+        let level = sections[i].level;
+        let has_subsection_content = sections[i + 1..]
+            .iter()
+            .take_while(|section| section.level > level)
+            .any(|section| section.has_content);
+        sections[i].has_content |= has_subsection_content;
+    }
+
+    for name in ["Input", "Output", "Constraints"] {
+        let section = sections
+            .iter()
+            .find(|section| section.level == 2 && section.title.eq_ignore_ascii_case(name));
+        match section {
+            Some(section) if !section.has_content => issues.push(LintIssue {
+                line: section.line,
+                message: format!("The `## {name}` section is empty"),
+            }),
+            Some(_) => {}
+            None => issues.push(LintIssue {
+                line: 1,
+                message: format!("Missing the `## {name}` section"),
+            }),
+        }
+    }
+
+    let example_section = sections
+        .iter()
+        .find(|section| section.level == 2 && section.title.to_lowercase().starts_with("example"));
+    match example_section {
+        Some(section) if parse_examples(markdown).is_empty() => issues.push(LintIssue {
+            line: section.line,
+            message: "The `## Example` section has no example input in a code block".to_owned(),
+        }),
+        Some(_) => {}
+        None => issues.push(LintIssue {
+            line: 1,
+            message: "Missing the `## Example` section".to_owned(),
+        }),
+    }
+
+    issues.sort_by_key(|issue| issue.line);
+    issues
+}
+
+/// Lint the statement of the problem at the given path.
+pub fn lint(problem_path: &Path) -> Result<Vec<LintIssue>> {
+    let path = problem_path.join(PROBLEM_STATEMENT_FILE);
+    let markdown =
+        fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))?;
+    Ok(lint_statement(&markdown))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lint_statement, LintIssue};
+
+    fn messages(markdown: &str) -> Vec<String> {
+        lint_statement(markdown)
+            .iter()
+            .map(LintIssue::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn accepts_complete_statement() {
+        let markdown = "# Two Sum\n\nPrint $a + b$, where the cost is \\$5.\n\n## Input\n\nTwo integers `$a` and $b$.\n\n## Output\n\nThe sum.\n\n## Constraints\n\n- $1 \\le a, b \\le 10^9$\n\n## Example\n\n### Input\n\n```\n1 2 \\(\n```\n\n### Output\n\n```\n3\n```\n";
+
+        assert_eq!(messages(markdown), Vec::<String>::new());
+    }
+
+    #[test]
+    fn reports_template_statement() {
+        let markdown = "# Problem Title\n\nProblem description.\n\n## Input\n\n## Output\n\n## Constraints\n\n## Example\n\n### Input\n\n### Output\n";
+
+        assert_eq!(
+            messages(markdown),
+            vec![
+                "problem.md:1: Replace the template text \"Problem Title\"",
+                "problem.md:3: Replace the template text \"Problem description.\"",
+                "problem.md:5: The `## Input` section is empty",
+                "problem.md:7: The `## Output` section is empty",
+                "problem.md:9: The `## Constraints` section is empty",
+                "problem.md:11: The `## Example` section has no example input in a code block",
+            ]
+        );
+    }
+
+    #[test]
+    fn requires_constraints_section() {
+        let markdown = "# A\n\nText.\n\n## Input\n\nText.\n\n### Constraints\n\n- $n \\le 10$\n\n## Output\n\nText.\n\n## Example\n\n### Input\n\n```\n1\n```\n";
+
+        assert_eq!(
+            messages(markdown),
+            vec!["problem.md:1: Missing the `## Constraints` section"]
+        );
+    }
+
+    #[test]
+    fn reports_math_delimiters_and_long_lines() {
+        let long_line = "word ".repeat(30);
+        let markdown = format!(
+            "# A\n\nGiven \\(n\\), print $n.\n\n{long_line}\n\n| {long_line} |\n\n## Input\n\nText.\n\n## Output\n\nText.\n\n## Constraints\n\nNone.\n\n## Example\n\n### Input\n\n```\n1\n```\n"
+        );
+
+        assert_eq!(
+            messages(&markdown),
+            vec![
+                "problem.md:3: Use $...$ for math instead of `\\(`",
+                "problem.md:3: Math is not closed: the line has an odd number of `$` delimiters",
+                "problem.md:5: Line is 150 characters long, more than 120. Split it over multiple lines",
+            ]
+        );
+    }
+}
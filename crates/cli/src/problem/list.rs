@@ -0,0 +1,280 @@
+//! List problems in the project.
+
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use crate::comp::read_competitions;
+use crate::problem::difficulty::DifficultyRange;
+use crate::problem::metadata::ProblemMetadata;
+use crate::problem::problem_location_from_path;
+use crate::problem::sync_mappings::{get_all_problem_names, get_problem};
+use crate::util::{get_input_files_in_directory, get_project_root};
+
+/// A problem and the details shown when listing it.
+#[derive(Debug, Serialize)]
+pub struct ProblemSummary {
+    pub name: String,
+    pub title: Option<String>,
+    pub status: String,
+    pub category: String,
+    pub difficulty: Option<u32>,
+    pub tags: Vec<String>,
+    pub tests: usize,
+    /// Competitions the problem is in.
+    pub competitions: Vec<String>,
+}
+
+/// Filters for the problems to list. Filters that are not set match every problem.
+#[derive(Debug, Default)]
+pub struct ListFilters {
+    /// Either `new` or `archive`.
+    pub status: Option<String>,
+    pub category: Option<String>,
+    /// Only problems in this competition.
+    pub comp: Option<String>,
+    /// Only problems that are not in any competition.
+    pub no_comp: bool,
+    pub tag: Option<String>,
+    pub min_tests: Option<usize>,
+    pub max_tests: Option<usize>,
+    pub difficulty: Option<DifficultyRange>,
+}
+
+impl ListFilters {
+    pub fn matches(&self, summary: &ProblemSummary) -> bool {
+        self.status.as_ref().is_none_or(|s| *s == summary.status)
+            && self
+                .category
+                .as_ref()
+                .is_none_or(|c| *c == summary.category)
+            && self
+                .comp
+                .as_ref()
+                .is_none_or(|c| summary.competitions.contains(c))
+            && (!self.no_comp || summary.competitions.is_empty())
+            && self.tag.as_ref().is_none_or(|t| summary.tags.contains(t))
+            && self.min_tests.is_none_or(|min| summary.tests >= min)
+            && self.max_tests.is_none_or(|max| summary.tests <= max)
+            && self.difficulty.is_none_or(|range| {
+                summary
+                    .difficulty
+                    .is_some_and(|rating| range.contains(rating))
+            })
+    }
+}
+
+/// Output format of the problem list.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ListFormat {
+    Table,
+    Json,
+}
+
+impl ListFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "table" => Some(ListFormat::Table),
+            "json" => Some(ListFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Get a summary of every problem, sorted by name.
+pub fn get_problem_summaries(problems_dir: &Path) -> Result<Vec<ProblemSummary>> {
+    let project_root = get_project_root()?;
+    let competitions = read_competitions(problems_dir)?;
+
+    let mut names = get_all_problem_names(problems_dir)?;
+    names.sort();
+
+    let mut summaries = Vec::new();
+    for name in names {
+        let problem_path = project_root.join(get_problem(problems_dir, &name)?);
+        let location = problem_location_from_path(problems_dir, &problem_path)?;
+        let metadata = ProblemMetadata::load(&problem_path)?;
+        let tests_path = problem_path.join("tests");
+        let tests = if tests_path.is_dir() {
+            get_input_files_in_directory(tests_path)?.len()
+        } else {
+            0
+        };
+        let problem_competitions = competitions
+            .iter()
+            .filter(|(_, comp)| comp.contains_problem(&name))
+            .map(|(comp_name, _)| comp_name.to_owned())
+            .collect();
+
+        let (title, difficulty, tags) = match metadata {
+            Some(metadata) => (Some(metadata.title), metadata.difficulty, metadata.tags),
+            None => (None, None, Vec::new()),
+        };
+
+        summaries.push(ProblemSummary {
+            name,
+            title,
+            status: location.status,
+            category: location.category,
+            difficulty,
+            tags,
+            tests,
+            competitions: problem_competitions,
+        });
+    }
+
+    Ok(summaries)
+}
+
+/// Format the problems as a table with aligned columns.
+fn format_table(summaries: &[ProblemSummary]) -> String {
+    let or_dash = |s: String| if s.is_empty() { "-".to_owned() } else { s };
+
+    let header = [
+        "NAME",
+        "STATUS",
+        "CATEGORY",
+        "DIFFICULTY",
+        "TESTS",
+        "COMPETITIONS",
+        "TAGS",
+        "TITLE",
+    ]
+    .map(str::to_owned);
+    let rows: Vec<[String; 8]> = summaries
+        .iter()
+        .map(|summary| {
+            [
+                summary.name.clone(),
+                summary.status.clone(),
+                summary.category.clone(),
+                or_dash(
+                    summary
+                        .difficulty
+                        .map(|r| r.to_string())
+                        .unwrap_or_default(),
+                ),
+                summary.tests.to_string(),
+                or_dash(summary.competitions.join(",")),
+                or_dash(summary.tags.join(",")),
+                or_dash(summary.title.clone().unwrap_or_default()),
+            ]
+        })
+        .collect();
+
+    let mut widths = header.clone().map(|cell| cell.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut table = String::new();
+    for row in std::iter::once(&header).chain(&rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect();
+        table.push_str(line.join("  ").trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+/// List the problems that match the filters.
+pub fn list(problems_dir: &Path, filters: &ListFilters, format: ListFormat) -> Result<()> {
+    if let Some(status) = &filters.status {
+        if status != "new" && status != "archive" {
+            bail!("Invalid status '{status}'. It must be either 'new' or 'archive'");
+        }
+    }
+    if let Some(comp) = &filters.comp {
+        if !read_competitions(problems_dir)?.contains_key(comp) {
+            bail!("Competition '{comp}' not found");
+        }
+    }
+
+    let summaries: Vec<ProblemSummary> = get_problem_summaries(problems_dir)?
+        .into_iter()
+        .filter(|summary| filters.matches(summary))
+        .collect();
+
+    match format {
+        ListFormat::Table => print!("{}", format_table(&summaries)),
+        ListFormat::Json => println!("{}", serde_json::to_string_pretty(&summaries)?),
+    }
+    eprintln!("Total problems: {}", summaries.len());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{format_table, get_problem_summaries, ListFilters};
+    use crate::comp::{add, create};
+    use crate::problem::metadata::PROBLEM_METADATA_FILE;
+    use crate::problem::sync_mappings::sync_mappings;
+    use crate::problem::test_support::{create_problem_dir, with_test_project};
+
+    #[test]
+    fn summarises_problems_with_their_location_and_metadata() {
+        with_test_project(|problems_dir| {
+            create_problem_dir(problems_dir, "new", "graphs", "shortest-path");
+            create_problem_dir(problems_dir, "archive", "easy", "two-sum");
+            let two_sum_path = problems_dir.join("archive/easy/two-sum");
+            fs::write(
+                two_sum_path.join(PROBLEM_METADATA_FILE),
+                "title = \"Two Sum\"\ndifficulty = 800\ntags = [\"arrays\"]\n",
+            )
+            .expect("metadata should be written");
+            fs::write(two_sum_path.join("tests/a.in"), "1\n").expect("test should be written");
+            fs::write(two_sum_path.join("tests/a.out"), "1\n").expect("test should be written");
+            sync_mappings(problems_dir).expect("mappings should sync");
+            create::create(problems_dir, "weekly-1").expect("competition should be created");
+            add::add(problems_dir, "weekly-1", "shortest-path").expect("problem should be added");
+
+            let summaries = get_problem_summaries(problems_dir).expect("problems should be listed");
+
+            assert_eq!(summaries.len(), 2);
+            assert_eq!(summaries[0].name, "shortest-path");
+            assert_eq!(summaries[0].status, "new");
+            assert_eq!(summaries[0].title, None);
+            assert_eq!(summaries[0].competitions, vec!["weekly-1"]);
+            assert_eq!(summaries[1].name, "two-sum");
+            assert_eq!(summaries[1].category, "easy");
+            assert_eq!(summaries[1].difficulty, Some(800));
+            assert_eq!(summaries[1].tests, 1);
+
+            let filters = ListFilters {
+                tag: Some("arrays".to_owned()),
+                ..Default::default()
+            };
+            assert!(!filters.matches(&summaries[0]));
+            assert!(filters.matches(&summaries[1]));
+
+            let filters = ListFilters {
+                no_comp: true,
+                min_tests: Some(1),
+                difficulty: Some("..1000".parse().expect("range should parse")),
+                ..Default::default()
+            };
+            assert!(!filters.matches(&summaries[0]));
+            assert!(filters.matches(&summaries[1]));
+
+            let table = format_table(&summaries);
+            let lines: Vec<&str> = table.lines().collect();
+            assert_eq!(
+                lines[0],
+                "NAME           STATUS   CATEGORY  DIFFICULTY  TESTS  COMPETITIONS  TAGS    TITLE"
+            );
+            assert_eq!(
+                lines[2],
+                "two-sum        archive  easy      800         1      -             arrays  Two Sum"
+            );
+        });
+    }
+}
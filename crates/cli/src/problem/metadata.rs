@@ -0,0 +1,306 @@
+//! Problem metadata, stored in a `problem.toml` file in the problem folder.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Error, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::problem::comparator::Comparator;
+use crate::problem::difficulty::validate_rating;
+use crate::problem::run::{memory_limit_from_mb, RunLimits};
+use crate::problem::subtask::{validate_subtasks, Subtask};
+
+pub const PROBLEM_METADATA_FILE: &str = "problem.toml";
+
+pub const DEFAULT_TIME_LIMIT_SECS: f64 = 1.0;
+pub const DEFAULT_MEMORY_LIMIT_MB: u64 = 256;
+
+/// Metadata of a problem.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct ProblemMetadata {
+    pub title: String,
+    /// Difficulty rating, e.g. 800.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<u32>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub authors: Vec<String>,
+    /// Where the problem came from, e.g. a past competition.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub checker: CheckerType,
+    #[serde(default)]
+    pub limits: ProblemLimits,
+    /// Groups of tests with partial scoring. Empty if the problem is not
+    /// scored by subtask.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subtasks: Vec<Subtask>,
+}
+
+/// Resource limits of a problem.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct ProblemLimits {
+    /// Time limit in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<f64>,
+    /// Memory limit in megabytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<u64>,
+}
+
+/// How the output of a solution is checked.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum CheckerType {
+    /// A built-in comparator, used unless the problem has a custom checker file.
+    Builtin(Comparator),
+    /// The problem must have a custom checker file.
+    Custom,
+}
+
+impl Default for CheckerType {
+    fn default() -> Self {
+        CheckerType::Builtin(Comparator::default())
+    }
+}
+
+impl CheckerType {
+    /// The comparator to use when outputs are compared directly, e.g. between
+    /// two solutions. Problems with a custom checker fall back to an exact match.
+    pub fn comparator(&self) -> Comparator {
+        match self {
+            CheckerType::Builtin(comparator) => *comparator,
+            CheckerType::Custom => Comparator::default(),
+        }
+    }
+}
+
+impl FromStr for CheckerType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "custom" => Ok(CheckerType::Custom),
+            _ => Ok(CheckerType::Builtin(s.parse()?)),
+        }
+    }
+}
+
+impl TryFrom<String> for CheckerType {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<CheckerType> for String {
+    fn from(checker: CheckerType) -> Self {
+        checker.to_string()
+    }
+}
+
+impl fmt::Display for CheckerType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckerType::Builtin(comparator) => write!(f, "{comparator}"),
+            CheckerType::Custom => write!(f, "custom"),
+        }
+    }
+}
+
+impl ProblemMetadata {
+    /// Load the metadata of the problem at the given path. Returns `None` if the
+    /// problem has no metadata file.
+    pub fn load(problem_path: &Path) -> Result<Option<Self>> {
+        let path = problem_path.join(PROBLEM_METADATA_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let contents =
+            fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))?;
+        let metadata: Self =
+            toml::from_str(&contents).context(format!("Failed to parse {}", path.display()))?;
+        metadata
+            .validate()
+            .context(format!("Invalid {}", path.display()))?;
+
+        Ok(Some(metadata))
+    }
+
+    /// Load the metadata of the problem at the given path, or the default
+    /// metadata if the problem has no metadata file.
+    pub fn load_or_default(problem_path: &Path) -> Result<Self> {
+        Ok(Self::load(problem_path)?.unwrap_or_default())
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.title.trim().is_empty() {
+            bail!("The problem title in {PROBLEM_METADATA_FILE} must not be empty");
+        }
+        if let Some(difficulty) = self.difficulty {
+            validate_rating(difficulty).context(format!(
+                "The difficulty in {PROBLEM_METADATA_FILE} is not on the rating scale"
+            ))?;
+        }
+        self.run_limits()?;
+        validate_subtasks(&self.subtasks)?;
+
+        Ok(())
+    }
+
+    /// Get the resource limits to run solutions with.
+    pub fn run_limits(&self) -> Result<RunLimits> {
+        let time_limit = self
+            .limits
+            .time
+            .map(Duration::try_from_secs_f64)
+            .transpose()
+            .context(format!(
+                "The time limit in {PROBLEM_METADATA_FILE} must be a non-negative number of seconds"
+            ))?;
+        let memory_limit = self
+            .limits
+            .memory
+            .map(memory_limit_from_mb)
+            .transpose()
+            .context(format!(
+                "The memory limit in {PROBLEM_METADATA_FILE} is too large"
+            ))?;
+
+        Ok(RunLimits {
+            time_limit,
+            memory_limit,
+        })
+    }
+}
+
+/// Turn a problem name into a default title, e.g. `two-sum` becomes `Two Sum`.
+pub fn title_from_name(problem_name: &str) -> String {
+    problem_name
+        .split(['-', '_'])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Contents of the metadata file for a new problem.
+pub fn metadata_template(title: &str) -> String {
+    format!(
+        r#"title = "{title}"
+
+# Difficulty rating of the problem, e.g. 800
+# difficulty = 800
+
+tags = []
+authors = []
+
+# Where the problem came from, e.g. a past competition
+# source = ""
+
+# How outputs are checked if there is no custom checker file. One of: exact,
+# tokens, lines, case-insensitive, or float[:ABS_EPS[:REL_EPS]].
+# Use "custom" to require a `checker.<lang>` file.
+checker = "exact"
+
+[limits]
+# Time limit in seconds
+time = {DEFAULT_TIME_LIMIT_SECS:?}
+# Memory limit in megabytes
+memory = {DEFAULT_MEMORY_LIMIT_MB}
+
+# Subtasks for partial scoring. A subtask earns its points only if all of its
+# tests and the subtasks it depends on pass. Its tests are the ones in
+# `tests/<name>/` unless `tests` is given, where `*` matches any characters.
+# [[subtasks]]
+# name = "subtask1"
+# points = 30
+# tests = ["subtask1/*"]
+# depends_on = []
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::Duration;
+
+    use tempfile::TempDir;
+
+    use super::{
+        metadata_template, title_from_name, CheckerType, ProblemMetadata, PROBLEM_METADATA_FILE,
+    };
+    use crate::problem::comparator::Comparator;
+
+    #[test]
+    fn template_is_valid_metadata() {
+        let dir = TempDir::new().expect("tempdir should be created");
+        fs::write(
+            dir.path().join(PROBLEM_METADATA_FILE),
+            metadata_template(&title_from_name("two-sum")),
+        )
+        .expect("metadata should be written");
+
+        let metadata = ProblemMetadata::load(dir.path())
+            .expect("metadata should load")
+            .expect("metadata should exist");
+        let limits = metadata.run_limits().expect("limits should be valid");
+
+        assert_eq!(metadata.title, "Two Sum");
+        assert_eq!(metadata.checker, CheckerType::Builtin(Comparator::Exact));
+        assert_eq!(limits.time_limit, Some(Duration::from_secs(1)));
+        assert_eq!(limits.memory_limit, Some(256 * 1024 * 1024));
+    }
+
+    #[test]
+    fn rejects_invalid_metadata() {
+        let dir = TempDir::new().expect("tempdir should be created");
+        let path = dir.path().join(PROBLEM_METADATA_FILE);
+
+        assert_eq!(
+            ProblemMetadata::load(dir.path()).expect("load should succeed"),
+            None
+        );
+
+        fs::write(&path, "title = \"A\"\nchecker = \"regex\"\n").expect("write should succeed");
+        assert!(ProblemMetadata::load(dir.path()).is_err());
+
+        fs::write(&path, "title = \"\"\n").expect("write should succeed");
+        assert!(ProblemMetadata::load(dir.path()).is_err());
+
+        fs::write(&path, "title = \"A\"\ndifficulty = 850\n").expect("write should succeed");
+        assert!(ProblemMetadata::load(dir.path()).is_err());
+
+        fs::write(&path, "title = \"A\"\n[limits]\ntime = -1.0\n").expect("write should succeed");
+        assert!(ProblemMetadata::load(dir.path()).is_err());
+
+        fs::write(
+            &path,
+            format!("title = \"A\"\n[limits]\nmemory = {}\n", i64::MAX),
+        )
+        .expect("write should succeed");
+        assert!(ProblemMetadata::load(dir.path()).is_err());
+
+        fs::write(
+            &path,
+            "title = \"A\"\n[[subtasks]]\nname = \"a\"\npoints = 10\ndepends_on = [\"b\"]\n",
+        )
+        .expect("write should succeed");
+        assert!(ProblemMetadata::load(dir.path()).is_err());
+    }
+}
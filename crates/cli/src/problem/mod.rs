@@ -9,15 +9,29 @@ use normpath::PathExt;
 pub mod archive;
 pub mod category;
 pub mod check;
+pub mod checker;
+pub mod comparator;
 pub mod compare;
 pub mod create;
+pub mod difficulty;
+pub mod export;
 pub mod fuzz;
 pub mod generate;
+pub mod import;
+pub mod lint;
+pub mod list;
+pub mod metadata;
 pub mod reclassify;
+pub mod render;
+pub mod report;
 pub mod run;
 pub mod solve;
+pub mod statement;
+pub mod subtask;
 pub mod sync_mappings;
 pub mod test;
+pub mod validate;
+pub mod verdict;
 
 #[cfg(test)]
 pub mod test_support;
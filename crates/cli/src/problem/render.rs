@@ -0,0 +1,334 @@
+//! Render problem statements to standalone HTML, and to PDF through a locally
+//! installed typesetter.
+//!
+//! Math between `$` signs is typeset to MathML with KaTeX while rendering, so
+//! the documents don't need network access or JavaScript to show it. KaTeX is
+//! behind the `math` feature and isn't available with MSVC, so otherwise the
+//! TeX source is shown as is. The examples are shown as a table of inputs and
+//! outputs.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use pulldown_cmark::{html, CowStr, Event, Options, Parser};
+use subprocess::{Exec, Redirection};
+
+use crate::config::Settings;
+use crate::problem::metadata::{
+    title_from_name, ProblemMetadata, DEFAULT_MEMORY_LIMIT_MB, DEFAULT_TIME_LIMIT_SECS,
+};
+use crate::problem::report::escape_xml;
+use crate::problem::statement::{parse_examples, split_examples, Example, PROBLEM_STATEMENT_FILE};
+use crate::problem::sync_mappings::get_problem;
+use crate::util::get_project_root;
+
+/// Arguments to print a page to PDF with Chromium or Google Chrome.
+const CHROME_ARGS: &[&str] = &[
+    "--headless",
+    "--disable-gpu",
+    "--no-pdf-header-footer",
+    "--print-to-pdf=@pdf_file",
+    "@html_file",
+];
+
+/// Typesetters that are tried in order if no PDF command is set, with the
+/// arguments to run them with.
+const TYPESETTERS: &[(&str, &[&str])] = &[
+    ("chromium", CHROME_ARGS),
+    ("chromium-browser", CHROME_ARGS),
+    ("google-chrome", CHROME_ARGS),
+    ("wkhtmltopdf", &["@html_file", "@pdf_file"]),
+    ("weasyprint", &["@html_file", "@pdf_file"]),
+];
+
+const STYLE: &str = r#"body { font-family: "Latin Modern Roman", "Times New Roman", serif; max-width: 50em; margin: 2em auto; padding: 0 1em; line-height: 1.4; }
+h1 { text-align: center; margin-bottom: 0.2em; }
+.limits { text-align: center; margin-top: 0; font-style: italic; }
+.problem-letter { text-align: center; font-size: 1.2em; font-weight: bold; margin-bottom: 0; }
+.problem + .problem { break-before: page; page-break-before: always; }
+table.examples { width: 100%; border-collapse: collapse; margin: 1em 0; }
+table.examples th, table.examples td { border: 1px solid #000; padding: 0.3em 0.6em; text-align: left; vertical-align: top; width: 50%; }
+pre { margin: 0; font-family: "DejaVu Sans Mono", monospace; white-space: pre-wrap; }
+code { font-family: "DejaVu Sans Mono", monospace; }
+math { font-family: "Latin Modern Math", "STIX Two Math", "Cambria Math", math; }
+@page { size: A4; margin: 2cm; }
+"#;
+
+/// Typeset TeX math to MathML. Invalid math is shown in red instead of failing.
+#[cfg(all(feature = "math", not(all(windows, target_env = "msvc"))))]
+fn render_math(tex: &str, display: bool) -> Result<String> {
+    use katex::{Opts, OutputType};
+
+    let opts = Opts::builder()
+        .display_mode(display)
+        .output_type(OutputType::Mathml)
+        .throw_on_error(false)
+        .build()
+        .context("Failed to set up KaTeX")?;
+    katex::render_with_opts(tex, &opts).context(format!("Failed to typeset the math `{tex}`"))
+}
+
+/// Show TeX math as its source, for builds without KaTeX.
+#[cfg(not(all(feature = "math", not(all(windows, target_env = "msvc")))))]
+fn render_math(tex: &str, display: bool) -> Result<String> {
+    Ok(match display {
+        true => format!("<pre class=\"math\">$${}$$</pre>", escape_xml(tex)),
+        false => format!("<code class=\"math\">${}$</code>", escape_xml(tex)),
+    })
+}
+
+/// Convert Markdown to HTML, with `$...$` and `$$...$$` math typeset to MathML.
+fn markdown_to_html(markdown: &str) -> Result<String> {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_MATH | Options::ENABLE_STRIKETHROUGH;
+    let events = Parser::new_ext(markdown, options)
+        .map(|event| match event {
+            Event::InlineMath(tex) => {
+                Ok(Event::InlineHtml(CowStr::from(render_math(&tex, false)?)))
+            }
+            Event::DisplayMath(tex) => {
+                Ok(Event::InlineHtml(CowStr::from(render_math(&tex, true)?)))
+            }
+            event => Ok(event),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut output = String::new();
+    html::push_html(&mut output, events.into_iter());
+    Ok(output)
+}
+
+/// Render the examples as a table with the input and output side by side.
+fn examples_table(examples: &[Example]) -> String {
+    let heading = match examples.len() {
+        1 => "Example",
+        _ => "Examples",
+    };
+    let mut table = format!(
+        "<h2>{heading}</h2>\n<table class=\"examples\">\n<tr><th>Input</th><th>Output</th></tr>\n"
+    );
+    for example in examples {
+        table.push_str(&format!(
+            "<tr><td><pre>{}</pre></td><td><pre>{}</pre></td></tr>\n",
+            escape_xml(example.input.trim_end()),
+            escape_xml(example.output.as_deref().unwrap_or_default().trim_end())
+        ));
+    }
+    table.push_str("</table>\n");
+    table
+}
+
+/// Render the statement of the problem at the given path as an HTML fragment,
+/// with the limits under the title. `letter` is shown above the title, e.g. in
+/// a competition booklet.
+pub fn render_statement(problem_path: &Path, letter: Option<&str>) -> Result<String> {
+    let statement_path = problem_path.join(PROBLEM_STATEMENT_FILE);
+    let markdown = fs::read_to_string(&statement_path)
+        .context(format!("Failed to read {}", statement_path.display()))?;
+
+    let examples = parse_examples(&markdown);
+    let mut body = match split_examples(&markdown) {
+        Some((before, after)) if !examples.is_empty() => {
+            markdown_to_html(&before)? + &examples_table(&examples) + &markdown_to_html(&after)?
+        }
+        _ => markdown_to_html(&markdown)?,
+    };
+
+    if let Some(metadata) = ProblemMetadata::load(problem_path)? {
+        let time = metadata.limits.time.unwrap_or(DEFAULT_TIME_LIMIT_SECS);
+        let memory = metadata.limits.memory.unwrap_or(DEFAULT_MEMORY_LIMIT_MB);
+        let seconds = if time == 1.0 { "second" } else { "seconds" };
+        let limits = format!(
+            "<p class=\"limits\">Time limit: {time} {seconds} | Memory limit: {memory} MB</p>\n"
+        );
+        body = match body.find("</h1>\n") {
+            Some(i) => {
+                let end = i + "</h1>\n".len();
+                format!("{}{limits}{}", &body[..end], &body[end..])
+            }
+            None => limits + &body,
+        };
+    }
+
+    let letter = letter
+        .map(|letter| {
+            format!(
+                "<p class=\"problem-letter\">Problem {}</p>\n",
+                escape_xml(letter)
+            )
+        })
+        .unwrap_or_default();
+
+    Ok(format!(
+        "<section class=\"problem\">\n{letter}{body}</section>\n"
+    ))
+}
+
+/// Wrap rendered statements in a standalone HTML document.
+pub fn html_document(title: &str, sections: &[String]) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{}</title>
+<style>
+{STYLE}</style>
+</head>
+<body>
+{}</body>
+</html>
+"#,
+        escape_xml(title),
+        sections.concat()
+    )
+}
+
+/// Find an executable on the `PATH`.
+fn find_executable(name: &str) -> Option<PathBuf> {
+    let paths = env::var_os("PATH")?;
+    env::split_paths(&paths)
+        .map(|dir| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Get the command to convert HTML to PDF with, either from the settings or
+/// the first typesetter that is installed.
+fn pdf_command(settings: &Settings) -> Option<Vec<String>> {
+    if let Some(command) = &settings.render.pdf_command {
+        return Some(command.clone());
+    }
+
+    TYPESETTERS.iter().find_map(|(name, args)| {
+        let path = find_executable(name)?;
+        let mut command = vec![path.to_string_lossy().into_owned()];
+        command.extend(args.iter().map(|arg| arg.to_string()));
+        Some(command)
+    })
+}
+
+/// Convert an HTML file to PDF with the given command.
+fn html_to_pdf(command: &[String], html_path: &Path, pdf_path: &Path) -> Result<()> {
+    let html_path = html_path.to_string_lossy();
+    let pdf_path = pdf_path.to_string_lossy();
+    let mut args = command.iter().map(|arg| {
+        arg.replace("@html_file", &html_path)
+            .replace("@pdf_file", &pdf_path)
+    });
+
+    let program = args.next().context("The PDF command is empty")?;
+    let capture = Exec::cmd(&program)
+        .args(args.collect::<Vec<_>>())
+        .stdout(Redirection::Pipe)
+        .stderr(Redirection::Merge)
+        .capture()
+        .context(format!("Failed to run `{program}`"))?;
+    if !capture.success() {
+        bail!(
+            "`{program}` failed to create the PDF:\n{}",
+            capture.stdout_str().trim()
+        );
+    }
+
+    Ok(())
+}
+
+/// Write the HTML document to `html_path`, and a PDF next to it if `pdf` is
+/// set and a typesetter is available.
+pub fn write_document(
+    settings: &Settings,
+    title: &str,
+    sections: &[String],
+    html_path: &Path,
+    pdf: bool,
+) -> Result<()> {
+    if let Some(parent) = html_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(html_path, html_document(title, sections))
+        .context(format!("Failed to write {}", html_path.display()))?;
+    eprintln!("Rendered {}", html_path.display());
+
+    if !pdf {
+        return Ok(());
+    }
+    let Some(command) = pdf_command(settings) else {
+        eprintln!("No typesetter found, so no PDF was created. Install Chromium, wkhtmltopdf or WeasyPrint, or set `pdf_command` under [render] in the settings file");
+        return Ok(());
+    };
+
+    let html_path = fs::canonicalize(html_path)?;
+    let pdf_path = html_path.with_extension("pdf");
+    html_to_pdf(&command, &html_path, &pdf_path)?;
+    eprintln!("Rendered {}", pdf_path.display());
+
+    Ok(())
+}
+
+/// Render the statement of a problem to HTML, and to PDF if `pdf` is set.
+pub fn render(
+    settings: &Settings,
+    problems_dir: &Path,
+    problem_name: &str,
+    html_path: &Path,
+    pdf: bool,
+) -> Result<()> {
+    let problem_path = get_project_root()?.join(get_problem(problems_dir, problem_name)?);
+    let title = match ProblemMetadata::load(&problem_path)? {
+        Some(metadata) => metadata.title,
+        None => title_from_name(problem_name),
+    };
+
+    let section = render_statement(&problem_path, None)?;
+    write_document(settings, &title, &[section], html_path, pdf)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::{html_document, render_statement};
+    use crate::problem::metadata::{metadata_template, PROBLEM_METADATA_FILE};
+
+    #[test]
+    fn renders_statement_with_math_limits_and_examples() {
+        let dir = TempDir::new().expect("tempdir should be created");
+        fs::write(
+            dir.path().join("problem.md"),
+            "# Two Sum\n\nGiven $a < b$, print $a + b$.\n\n## Example\n\n### Input\n\n```\n1 2\n```\n\n### Output\n\n```\n3\n```\n\n## Notes\n\n$$a + b$$\n",
+        )
+        .expect("statement should be written");
+        fs::write(
+            dir.path().join(PROBLEM_METADATA_FILE),
+            metadata_template("Two Sum"),
+        )
+        .expect("metadata should be written");
+
+        let html = render_statement(dir.path(), Some("A")).expect("statement should render");
+
+        assert!(html.contains("<p class=\"problem-letter\">Problem A</p>\n<h1>Two Sum</h1>\n<p class=\"limits\">Time limit: 1 second | Memory limit: 256 MB</p>"));
+        if cfg!(all(
+            feature = "math",
+            not(all(windows, target_env = "msvc"))
+        )) {
+            assert!(html.contains("<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><semantics><mrow><mi>a</mi><mo>&lt;</mo><mi>b</mi></mrow>"));
+            assert!(html.contains("<span class=\"katex\"><math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\">"));
+            assert!(!html.contains("$"));
+        } else {
+            assert!(html.contains("<code class=\"math\">$a &lt; b$</code>"));
+            assert!(html.contains("<pre class=\"math\">$$a + b$$</pre>"));
+        }
+        assert!(html.contains("<tr><td><pre>1 2</pre></td><td><pre>3</pre></td></tr>"));
+        assert!(html.contains("<h2>Notes</h2>"));
+        assert!(!html.contains("<h3>Input</h3>"));
+
+        let document = html_document("Two Sum & Co", &[html]);
+        assert!(document.starts_with("<!DOCTYPE html>"));
+        assert!(document.contains("<title>Two Sum &amp; Co</title>"));
+        assert!(!document.contains("<script"));
+        assert!(!document.contains("https://"));
+    }
+}
@@ -0,0 +1,335 @@
+//! Machine-readable reports for test runs.
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+
+use super::subtask::SubtaskReport;
+use super::verdict::{Verdict, VerdictSummary};
+
+/// Output format of a test report.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReportFormat {
+    /// Human-readable progress output only (no report is written).
+    Text,
+    Json,
+    Junit,
+}
+
+impl ReportFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(ReportFormat::Text),
+            "json" => Some(ReportFormat::Json),
+            "junit" => Some(ReportFormat::Junit),
+            _ => None,
+        }
+    }
+}
+
+/// The result of a single test case.
+pub struct TestCaseReport {
+    /// Name of the test, i.e. the input file name without the `.in` suffix.
+    pub name: String,
+    pub verdict: Verdict,
+    pub elapsed_time: Duration,
+    pub peak_memory: Option<u64>,
+    /// Feedback from the checker, if any.
+    pub message: Option<String>,
+    /// What the solution wrote to stderr.
+    pub stderr: String,
+}
+
+/// The results of all test cases of a problem.
+pub struct ProblemReport {
+    pub problem: String,
+    pub tests: Vec<TestCaseReport>,
+    /// Scores of the subtasks, if the problem has any.
+    pub subtasks: Vec<SubtaskReport>,
+}
+
+impl ProblemReport {
+    pub fn new(problem: &str) -> Self {
+        Self {
+            problem: problem.to_owned(),
+            tests: Vec::new(),
+            subtasks: Vec::new(),
+        }
+    }
+
+    /// Total points earned over all subtasks.
+    pub fn score(&self) -> u32 {
+        self.subtasks.iter().map(|subtask| subtask.earned).sum()
+    }
+
+    /// Total points available over all subtasks.
+    pub fn max_score(&self) -> u32 {
+        self.subtasks.iter().map(|subtask| subtask.points).sum()
+    }
+
+    pub fn summary(&self) -> VerdictSummary {
+        let mut summary = VerdictSummary::default();
+        for test in &self.tests {
+            summary.add(&test.verdict);
+        }
+        summary
+    }
+
+    pub fn failures(&self) -> usize {
+        self.tests
+            .iter()
+            .filter(|test| !test.verdict.is_accepted())
+            .count()
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.tests.iter().map(|test| test.elapsed_time).sum()
+    }
+}
+
+/// Count the failed test cases over all problem reports.
+pub fn count_failures(reports: &[ProblemReport]) -> usize {
+    reports.iter().map(ProblemReport::failures).sum()
+}
+
+/// Render the reports as a JSON document.
+pub fn to_json(reports: &[ProblemReport]) -> Value {
+    let problems: Vec<Value> = reports
+        .iter()
+        .map(|report| {
+            let tests: Vec<Value> = report
+                .tests
+                .iter()
+                .map(|test| {
+                    json!({
+                        "name": test.name,
+                        "verdict": test.verdict.code(),
+                        "description": test.verdict.to_string(),
+                        "time_seconds": test.elapsed_time.as_secs_f64(),
+                        "memory_bytes": test.peak_memory,
+                        "message": test.message,
+                    })
+                })
+                .collect();
+
+            let mut problem = json!({
+                "problem": report.problem,
+                "passed": report.tests.len() - report.failures(),
+                "total": report.tests.len(),
+                "time_seconds": report.total_time().as_secs_f64(),
+                "tests": tests,
+            });
+
+            if !report.subtasks.is_empty() {
+                let subtasks: Vec<Value> = report
+                    .subtasks
+                    .iter()
+                    .map(|subtask| {
+                        json!({
+                            "name": subtask.name,
+                            "points": subtask.points,
+                            "earned": subtask.earned,
+                            "failed_tests": subtask.failed_tests,
+                            "failed_dependencies": subtask.failed_dependencies,
+                        })
+                    })
+                    .collect();
+                problem["score"] = json!(report.score());
+                problem["max_score"] = json!(report.max_score());
+                problem["subtasks"] = json!(subtasks);
+            }
+
+            problem
+        })
+        .collect();
+
+    json!({
+        "passed": count_failures(reports) == 0,
+        "problems": problems,
+    })
+}
+
+pub(crate) fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Render the reports as a JUnit XML document, with one test suite per problem.
+pub fn to_junit(reports: &[ProblemReport]) -> String {
+    let total_tests: usize = reports.iter().map(|report| report.tests.len()).sum();
+    let total_time: Duration = reports.iter().map(ProblemReport::total_time).sum();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites tests=\"{total_tests}\" failures=\"{}\" time=\"{:.5}\">\n",
+        count_failures(reports),
+        total_time.as_secs_f64()
+    ));
+
+    for report in reports {
+        let problem = escape_xml(&report.problem);
+        xml.push_str(&format!(
+            "  <testsuite name=\"{problem}\" tests=\"{}\" failures=\"{}\" time=\"{:.5}\">\n",
+            report.tests.len(),
+            report.failures(),
+            report.total_time().as_secs_f64()
+        ));
+
+        for test in &report.tests {
+            let name = escape_xml(&test.name);
+            let time = test.elapsed_time.as_secs_f64();
+            if test.verdict.is_accepted() {
+                xml.push_str(&format!(
+                    "    <testcase name=\"{name}\" classname=\"{problem}\" time=\"{time:.5}\"/>\n"
+                ));
+                continue;
+            }
+
+            xml.push_str(&format!(
+                "    <testcase name=\"{name}\" classname=\"{problem}\" time=\"{time:.5}\">\n"
+            ));
+            xml.push_str(&format!(
+                "      <failure type=\"{}\" message=\"{}\">{}</failure>\n",
+                test.verdict.code(),
+                escape_xml(&test.verdict.to_string()),
+                escape_xml(test.message.as_deref().unwrap_or_default())
+            ));
+            xml.push_str("    </testcase>\n");
+        }
+
+        xml.push_str("  </testsuite>\n");
+    }
+
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+/// Write the reports in the given format to a file, or to stdout if no file is given.
+pub fn write_report(
+    reports: &[ProblemReport],
+    format: ReportFormat,
+    output_path: Option<&Path>,
+) -> Result<()> {
+    let contents = match format {
+        ReportFormat::Text => return Ok(()),
+        ReportFormat::Json => serde_json::to_string_pretty(&to_json(reports))? + "\n",
+        ReportFormat::Junit => to_junit(reports),
+    };
+
+    match output_path {
+        Some(path) => {
+            fs::write(path, contents)
+                .context(format!("Failed to write report to {}", path.display()))?;
+            eprintln!("Wrote test report to {}", path.display());
+        }
+        None => print!("{contents}"),
+    }
+
+    Ok(())
+}
+
+/// Write the reports, then return an error if any test case failed so that the
+/// command exits with a non-zero status.
+pub fn finish_report(
+    reports: &[ProblemReport],
+    format: ReportFormat,
+    output_path: Option<&Path>,
+) -> Result<()> {
+    write_report(reports, format, output_path)?;
+
+    let failures = count_failures(reports);
+    if failures > 0 {
+        bail!("{failures} test case(s) failed");
+    }
+
+    Ok(())
+}
+
+/// Write the report, then return an error unless the solution earned exactly
+/// the expected score, e.g. to check that a partial solution only passes the
+/// intended subtasks.
+pub fn finish_scored_report(
+    report: &ProblemReport,
+    expected_score: u32,
+    format: ReportFormat,
+    output_path: Option<&Path>,
+) -> Result<()> {
+    write_report(std::slice::from_ref(report), format, output_path)?;
+
+    if report.subtasks.is_empty() {
+        bail!(
+            "Problem '{}' has no subtasks, so it cannot be scored",
+            report.problem
+        );
+    }
+    if report.score() != expected_score {
+        bail!(
+            "The solution scored {} points, but {expected_score} points were expected",
+            report.score()
+        );
+    }
+    eprintln!("The solution scored the expected {expected_score} points");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{count_failures, to_json, to_junit, ProblemReport, TestCaseReport};
+    use crate::problem::verdict::Verdict;
+
+    fn sample_report() -> ProblemReport {
+        let mut report = ProblemReport::new("two-sum");
+        report.tests.push(TestCaseReport {
+            name: "a".to_owned(),
+            verdict: Verdict::Accepted,
+            elapsed_time: Duration::from_millis(100),
+            peak_memory: Some(1024),
+            message: None,
+            stderr: String::new(),
+        });
+        report.tests.push(TestCaseReport {
+            name: "b".to_owned(),
+            verdict: Verdict::WrongAnswer,
+            elapsed_time: Duration::from_millis(200),
+            peak_memory: None,
+            message: Some("expected <3>".to_owned()),
+            stderr: String::new(),
+        });
+        report
+    }
+
+    #[test]
+    fn json_report_contains_per_test_verdicts() {
+        let reports = vec![sample_report()];
+        let json = to_json(&reports);
+
+        assert_eq!(json["passed"], false);
+        assert_eq!(json["problems"][0]["problem"], "two-sum");
+        assert_eq!(json["problems"][0]["passed"], 1);
+        assert_eq!(json["problems"][0]["tests"][1]["verdict"], "WA");
+        assert_eq!(json["problems"][0]["tests"][1]["message"], "expected <3>");
+        assert_eq!(json["problems"][0]["tests"][0]["memory_bytes"], 1024);
+    }
+
+    #[test]
+    fn junit_report_marks_failures_and_escapes_messages() {
+        let reports = vec![sample_report()];
+        let xml = to_junit(&reports);
+
+        assert_eq!(count_failures(&reports), 1);
+        assert!(xml.contains("<testsuites tests=\"2\" failures=\"1\""));
+        assert!(xml.contains("<testcase name=\"a\" classname=\"two-sum\" time=\"0.10000\"/>"));
+        assert!(xml.contains(
+            "<failure type=\"WA\" message=\"Wrong answer\">expected &lt;3&gt;</failure>"
+        ));
+    }
+}
@@ -2,11 +2,12 @@ use std::ffi::OsStr;
 use std::fmt;
 use std::fs::{self, File};
 use std::path::{Path, PathBuf};
+use std::thread;
 use std::time::{Duration, Instant};
 
-use anyhow::{bail, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use normpath::PathExt;
-use subprocess::{Exec, Redirection};
+use subprocess::{Exec, ExitStatus, Job, Redirection};
 
 use crate::config::Settings;
 use crate::util::get_lang_from_extension;
@@ -34,11 +35,15 @@ pub fn get_python_executable(settings: &Settings) -> String {
     }
 }
 
-/// Represents the category of a runnable file, either a solution or a generator.
+/// Represents the category of a runnable file: a solution or a generator, which
+/// live in their own folders, or a checker or validator, which live in the
+/// problem folder.
 #[derive(Eq, PartialEq)]
 pub enum RunnableCategory {
     Solution,
     Generator,
+    Checker,
+    Validator,
 }
 
 impl fmt::Display for RunnableCategory {
@@ -46,11 +51,14 @@ impl fmt::Display for RunnableCategory {
         match self {
             RunnableCategory::Solution => write!(f, "solution"),
             RunnableCategory::Generator => write!(f, "generator"),
+            RunnableCategory::Checker => write!(f, "checker"),
+            RunnableCategory::Validator => write!(f, "validator"),
         }
     }
 }
 
-/// Represents a runnable file, which can be either a solution or a generator.
+/// Represents a runnable file, which can be a solution, generator, checker or
+/// validator.
 /// The file must not be a binary file, and is expected to be a script or a
 /// source code file
 pub struct RunnableFile {
@@ -94,7 +102,9 @@ impl RunnableFile {
             }
             (None, None) => {
                 let lang = match category {
-                    RunnableCategory::Solution => settings.problem.default_lang.clone(),
+                    RunnableCategory::Solution
+                    | RunnableCategory::Checker
+                    | RunnableCategory::Validator => settings.problem.default_lang.clone(),
                     RunnableCategory::Generator => settings.problem.default_generator_lang.clone(),
                 };
                 let filename = format!("{category}.{lang}");
@@ -111,8 +121,83 @@ impl RunnableFile {
 }
 
 impl fmt::Display for RunnableFile {
+    /// Formats the path of the file relative to the problem folder.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}s/{}", self.category, self.name)
+        match self.category {
+            RunnableCategory::Checker | RunnableCategory::Validator => {
+                write!(f, "{}", self.name)
+            }
+            _ => write!(f, "{}s/{}", self.category, self.name),
+        }
+    }
+}
+
+/// Get the languages of the files named `<stem>.<lang>` in the problem folder,
+/// for each language that has settings.
+pub fn find_file_langs(
+    settings: &Settings,
+    problem_path: &Path,
+    stem: &str,
+) -> Result<Vec<String>> {
+    let mut langs = Vec::new();
+    for entry in fs::read_dir(problem_path)? {
+        let path = entry?.path();
+        if path.file_stem().and_then(|s| s.to_str()) != Some(stem) {
+            continue;
+        }
+        if let Some(lang) = path.extension().and_then(|s| s.to_str()) {
+            if settings.problem.solution.contains_key(lang) {
+                langs.push(lang.to_owned());
+            }
+        }
+    }
+    langs.sort();
+
+    Ok(langs)
+}
+
+/// Compile a source file with the compile command of its language without
+/// running it, e.g. to check that it compiles. Returns the compiler output if
+/// compilation failed. Files in languages without a compile command are not
+/// compiled.
+pub fn compile_file(
+    settings: &Settings,
+    file_path: &Path,
+    lang: &str,
+    bin_file: &Path,
+) -> Result<Option<String>> {
+    let lang_settings = settings
+        .problem
+        .solution
+        .get(lang)
+        .context(format!("Could not get settings for language `{lang}`"))?;
+    let Some(compile_command) = &lang_settings.compile_command else {
+        return Ok(None);
+    };
+
+    let mut cmd_iter = compile_command.iter();
+    let mut final_cmd = Exec::cmd(
+        cmd_iter
+            .next()
+            .context("compile_command specified in the settings, but array is empty")?,
+    );
+    for c in cmd_iter {
+        final_cmd = match c.as_str() {
+            "@in_file" => final_cmd.arg(file_path),
+            "@bin_file" => final_cmd.arg(bin_file),
+            _ => final_cmd.arg(c),
+        }
+    }
+
+    let capture = final_cmd
+        .stdout(Redirection::Pipe)
+        .stderr(Redirection::Merge)
+        .capture()
+        .context(format!("Failed to compile {}", file_path.display()))?;
+    if capture.success() {
+        Ok(None)
+    } else {
+        Ok(Some(capture.stdout_str()))
     }
 }
 
@@ -124,11 +209,120 @@ pub struct RunCommand {
     bin_file: PathBuf,
     script_file: PathBuf,
     run_command: Vec<String>,
+    limits: RunLimits,
+}
+
+/// How often a running process is polled for completion and memory usage.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Resource limits applied to every run of a command. A limit of `None` means
+/// the process is allowed to run unrestricted.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RunLimits {
+    pub time_limit: Option<Duration>,
+    /// Memory limit in bytes.
+    pub memory_limit: Option<u64>,
+}
+
+impl RunLimits {
+    /// Use the limits from `fallback` for any limit that is not set.
+    pub fn or(self, fallback: RunLimits) -> RunLimits {
+        RunLimits {
+            time_limit: self.time_limit.or(fallback.time_limit),
+            memory_limit: self.memory_limit.or(fallback.memory_limit),
+        }
+    }
+}
+
+/// The resource limit that caused a process to be killed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LimitExceeded {
+    Time,
+    Memory,
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitExceeded::Time => write!(f, "time limit exceeded"),
+            LimitExceeded::Memory => write!(f, "memory limit exceeded"),
+        }
+    }
 }
 
 pub struct RunResult {
     pub output: String,
+    pub stderr: String,
     pub elapsed_time: Duration,
+    /// Peak resident set size of the process in bytes, if it could be measured.
+    pub peak_memory: Option<u64>,
+    pub exit_status: ExitStatus,
+    pub limit_exceeded: Option<LimitExceeded>,
+}
+
+/// Read the peak resident set size (in bytes) of a running process.
+#[cfg(target_os = "linux")]
+fn read_peak_memory(pid: u32) -> Option<u64> {
+    let status = fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+    status.lines().find_map(|line| {
+        let kib = line
+            .strip_prefix("VmHWM:")?
+            .trim()
+            .strip_suffix("kB")?
+            .trim()
+            .parse::<u64>()
+            .ok()?;
+        Some(kib * 1024)
+    })
+}
+
+/// Peak memory measurement is only supported on Linux.
+#[cfg(not(target_os = "linux"))]
+fn read_peak_memory(_pid: u32) -> Option<u64> {
+    None
+}
+
+/// Kill a running job. On Unix, the job is started in its own process group so
+/// that any processes spawned by it (e.g. by a shell wrapper) are killed too.
+fn kill_job(job: &Job) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use subprocess::unix::JobExt;
+
+        const SIGKILL: i32 = 9;
+        job.send_signal_group(SIGKILL)
+    }
+    #[cfg(not(unix))]
+    {
+        job.kill()
+    }
+}
+
+/// Format a memory size in bytes for display, e.g. `12.34 MB`.
+pub fn format_memory(bytes: Option<u64>) -> String {
+    match bytes {
+        Some(bytes) => format!("{:.2} MB", bytes as f64 / (1024.0 * 1024.0)),
+        None => "unknown".to_owned(),
+    }
+}
+
+/// Convert a memory limit in megabytes to bytes, failing if it is too large.
+pub fn memory_limit_from_mb(mb: u64) -> Result<u64> {
+    mb.checked_mul(1024 * 1024)
+        .context(format!("The memory limit of {mb} MB is too large"))
+}
+
+/// Print what a solution wrote to stderr under the line of its test case, so
+/// that debug output isn't lost when the output is captured.
+pub fn print_stderr(stderr: &str) {
+    if stderr.trim().is_empty() {
+        return;
+    }
+
+    eprintln!("      Stderr:");
+    for line in stderr.trim_end().lines() {
+        eprintln!("        {line}");
+    }
 }
 
 impl RunCommand {
@@ -198,6 +392,7 @@ impl RunCommand {
             bin_file,
             script_file,
             run_command,
+            limits: RunLimits::default(),
         })
     }
 
@@ -218,12 +413,33 @@ impl RunCommand {
             bin_file,
             script_file,
             run_command,
+            limits: RunLimits::default(),
         })
     }
 
-    /// Returns the result of running the command, capturing its output and elapsed time.
-    /// If `input_file_path` is provided, it will be used as the standard input for the command.
+    /// Sets the resource limits applied to every run of this command.
+    pub fn with_limits(mut self, limits: RunLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Returns the result of running the command, capturing its output, elapsed time
+    /// and peak memory usage. If `input_file_path` is provided, it will be used as the
+    /// standard input for the command.
+    ///
+    /// The process is killed if it exceeds the time or memory limit, in which case
+    /// `limit_exceeded` is set on the result.
     pub fn get_result(&self, input_file_path: Option<&PathBuf>) -> Result<RunResult> {
+        self.get_result_with_args(input_file_path, &[])
+    }
+
+    /// Same as [`RunCommand::get_result`], but appends extra arguments to the
+    /// run command.
+    pub fn get_result_with_args(
+        &self,
+        input_file_path: Option<&PathBuf>,
+        extra_args: &[&Path],
+    ) -> Result<RunResult> {
         let cmd_iter = self.run_command.iter();
         let mut cmd_iter_clone = cmd_iter.clone();
         let cmd = cmd_iter_clone.next().context("Failed to get command")?;
@@ -241,22 +457,84 @@ impl RunCommand {
                 _ => final_cmd.arg(c),
             }
         }
-
-        let start_time = Instant::now();
+        for arg in extra_args {
+            final_cmd = final_cmd.arg(arg);
+        }
 
         if let Some(p) = input_file_path {
-            final_cmd = final_cmd
-                .stdin(File::open(p).context("Failed to get input file")?)
-                .stdout(Redirection::Pipe);
-        } else {
-            final_cmd = final_cmd.stdout(Redirection::Pipe);
+            final_cmd = final_cmd.stdin(File::open(p).context("Failed to get input file")?);
         }
-        let output = final_cmd.capture()?.stdout_str();
+        final_cmd = final_cmd
+            .stdout(Redirection::Pipe)
+            .stderr(Redirection::Pipe);
+        #[cfg(unix)]
+        if self.limits != RunLimits::default() {
+            use subprocess::ExecExt;
+
+            final_cmd = final_cmd.setpgid();
+        }
+
+        let start_time = Instant::now();
+        let mut job = final_cmd.start()?;
+
+        // Drain the output pipes on a separate thread so that the process does
+        // not block on a full pipe while we are polling it
+        let mut communicator = job.communicate()?;
+        let reader = thread::spawn(move || communicator.read_string());
+
+        let mut peak_memory = None;
+        let mut limit_exceeded = None;
+        let exit_status = loop {
+            if let Some(status) = job.wait_timeout(POLL_INTERVAL)? {
+                break status;
+            }
+
+            if let Some(memory) = read_peak_memory(job.pid()) {
+                peak_memory = peak_memory.max(Some(memory));
+            }
+
+            let memory_exceeded = matches!(
+                (peak_memory, self.limits.memory_limit),
+                (Some(memory), Some(limit)) if memory > limit
+            );
+            let time_exceeded = self
+                .limits
+                .time_limit
+                .is_some_and(|limit| start_time.elapsed() > limit);
+
+            if memory_exceeded || time_exceeded {
+                limit_exceeded = Some(if memory_exceeded {
+                    LimitExceeded::Memory
+                } else {
+                    LimitExceeded::Time
+                });
+                kill_job(&job).context("Failed to kill process")?;
+                break job.wait()?;
+            }
+        };
         let elapsed_time = start_time.elapsed();
 
+        // A process may exit on its own just after its time limit has passed
+        if limit_exceeded.is_none()
+            && self
+                .limits
+                .time_limit
+                .is_some_and(|limit| elapsed_time > limit)
+        {
+            limit_exceeded = Some(LimitExceeded::Time);
+        }
+
+        let (output, stderr) = reader
+            .join()
+            .map_err(|_| anyhow!("Failed to read process output"))??;
+
         Ok(RunResult {
             output,
+            stderr,
             elapsed_time,
+            peak_memory,
+            exit_status,
+            limit_exceeded,
         })
     }
 
@@ -268,3 +546,55 @@ impl RunCommand {
         Ok(())
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    use super::{LimitExceeded, RunCommand, RunLimits};
+
+    fn shell_command(script: &str) -> RunCommand {
+        RunCommand::from_command(
+            PathBuf::new(),
+            PathBuf::new(),
+            vec!["sh".to_owned(), "-c".to_owned(), script.to_owned()],
+        )
+        .expect("command should be valid")
+    }
+
+    #[test]
+    fn captures_output_and_exit_status() {
+        let result = shell_command("echo hello; echo oops >&2; exit 3")
+            .get_result(None)
+            .expect("command should run");
+
+        assert_eq!(result.output, "hello\n");
+        assert_eq!(result.stderr, "oops\n");
+        assert_eq!(result.exit_status.code(), Some(3));
+        assert_eq!(result.limit_exceeded, None);
+    }
+
+    #[test]
+    fn appends_extra_args_to_the_command() {
+        let result = shell_command("echo \"$0 $1\"")
+            .get_result_with_args(None, &[Path::new("input.txt"), Path::new("answer.txt")])
+            .expect("command should run");
+
+        assert_eq!(result.output, "input.txt answer.txt\n");
+    }
+
+    #[test]
+    fn kills_process_that_exceeds_time_limit() {
+        let result = shell_command("sleep 5")
+            .with_limits(RunLimits {
+                time_limit: Some(Duration::from_millis(200)),
+                memory_limit: None,
+            })
+            .get_result(None)
+            .expect("command should run");
+
+        assert_eq!(result.limit_exceeded, Some(LimitExceeded::Time));
+        assert!(result.elapsed_time < Duration::from_secs(5));
+    }
+}
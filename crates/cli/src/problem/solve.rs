@@ -1,21 +1,150 @@
-use std::fs::File;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
 use std::io::Write;
 use std::path::Path;
 use std::time::Duration;
 
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 
 use super::sync_mappings::get_problem;
-use crate::problem::run::{RunCommand, RunnableFile};
-use crate::util::get_project_root;
+use crate::problem::run::{print_stderr, RunCommand, RunnableFile};
+use crate::util::{get_project_root, run_ordered, sha256_hex};
 use crate::{config::Settings, util::get_input_files_in_directory};
 
+/// File in the problem folder that records what the test outputs were
+/// generated from, so that stale outputs can be found without running anything.
+pub const SOLVE_STAMP_FILE: &str = ".solve-stamp.json";
+
+/// Content hashes of a test's files.
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct TestHashes {
+    pub input: String,
+    pub output: String,
+}
+
+/// A record of the last time the test outputs were generated.
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct SolveStamp {
+    /// Path of the solution file relative to the problem folder, e.g. `solutions/solution.cpp`.
+    pub solution: String,
+    pub solution_hash: String,
+    /// Hashes of every test by test name.
+    pub tests: BTreeMap<String, TestHashes>,
+}
+
+fn hash_file(path: &Path) -> Result<String> {
+    let contents = fs::read(path).context(format!("Failed to read {}", path.display()))?;
+    Ok(sha256_hex(&contents))
+}
+
+/// Hash the tests of a problem that have an output.
+fn hash_tests(problem_path: &Path) -> Result<BTreeMap<String, TestHashes>> {
+    let mut tests = BTreeMap::new();
+    for test_file in get_input_files_in_directory(problem_path.join("tests"))? {
+        let test_name = test_file.trim_end_matches(".in");
+        let output_path = problem_path.join(format!("tests/{test_name}.out"));
+        if !output_path.exists() {
+            continue;
+        }
+        tests.insert(
+            test_name.to_owned(),
+            TestHashes {
+                input: hash_file(&problem_path.join(format!("tests/{test_file}")))?,
+                output: hash_file(&output_path)?,
+            },
+        );
+    }
+    Ok(tests)
+}
+
+impl SolveStamp {
+    /// Record the current solution file and tests of the problem.
+    pub fn create(problem_path: &Path, solution: &str) -> Result<Self> {
+        Ok(Self {
+            solution: solution.to_owned(),
+            solution_hash: hash_file(&problem_path.join(solution))?,
+            tests: hash_tests(problem_path)?,
+        })
+    }
+
+    /// Load the stamp of the problem, or `None` if the outputs were never
+    /// generated with `problem solve`.
+    pub fn load(problem_path: &Path) -> Result<Option<Self>> {
+        let path = problem_path.join(SOLVE_STAMP_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let contents =
+            fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))?;
+        let stamp = serde_json::from_str(&contents)
+            .context(format!("Failed to parse {}", path.display()))?;
+        Ok(Some(stamp))
+    }
+
+    pub fn write(&self, problem_path: &Path) -> Result<()> {
+        let path = problem_path.join(SOLVE_STAMP_FILE);
+        fs::write(&path, serde_json::to_string_pretty(self)? + "\n")
+            .context(format!("Failed to write {}", path.display()))
+    }
+
+    /// Compare the stamp with the current files of the problem, returning a
+    /// reason for every output that may be stale.
+    pub fn stale_reasons(&self, problem_path: &Path) -> Result<Vec<String>> {
+        let mut reasons = Vec::new();
+
+        let solution_path = problem_path.join(&self.solution);
+        if !solution_path.exists() {
+            reasons.push(format!(
+                "`{}` was removed since the outputs were generated",
+                self.solution
+            ));
+        } else if hash_file(&solution_path)? != self.solution_hash {
+            reasons.push(format!(
+                "`{}` changed since the outputs were generated",
+                self.solution
+            ));
+        }
+
+        let current = hash_tests(problem_path)?;
+        for test_file in get_input_files_in_directory(problem_path.join("tests"))? {
+            let test_name = test_file.trim_end_matches(".in");
+            match (self.tests.get(test_name), current.get(test_name)) {
+                // Missing outputs are reported as invalid tests instead
+                (_, None) => {}
+                (None, Some(_)) => reasons.push(format!(
+                    "Test '{test_name}' was added after the outputs were generated"
+                )),
+                (Some(stamped), Some(hashes)) => {
+                    if stamped.input != hashes.input {
+                        reasons.push(format!(
+                            "`tests/{test_name}.in` changed since its output was generated"
+                        ));
+                    }
+                    if stamped.output != hashes.output {
+                        reasons.push(format!(
+                            "`tests/{test_name}.out` was changed by hand after it was generated"
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(reasons)
+    }
+}
+
 /// Automatically generate test outputs for a problem, given pre-existing input files.
+///
+/// Up to `jobs` test cases are run at the same time. Afterwards, the hashes of
+/// the solution and tests are written to [`SOLVE_STAMP_FILE`].
 pub fn solve(
     settings: &Settings,
     problems_dir: &Path,
     problem_name: &str,
     solution_file: &RunnableFile,
+    jobs: usize,
 ) -> Result<()> {
     let project_root = get_project_root()?;
     let problem_path = project_root.join(get_problem(problems_dir, problem_name)?);
@@ -33,28 +162,39 @@ pub fn solve(
     eprintln!("Running the solution file for each test case...");
 
     let mut total_time = Duration::new(0, 0);
+    let mut reported_files = test_files.iter();
 
     // Run the file for every test input and generate the corresponding output
-    for test_file in test_files {
-        let input_file_path = problem_path.join(format!("tests/{test_file}"));
-        let output_file_path = problem_path.join(format!(
-            "tests/{}.out",
-            test_file
-                .strip_suffix(".in")
-                .context("Failed to strip suffix of test file")?
-        ));
-
-        let result = run_command.get_result(Some(&input_file_path))?;
-        let mut output_file = File::create(output_file_path)?;
-        output_file.write_all(result.output.as_bytes())?;
-        let elapsed_time = result.elapsed_time;
-
-        eprintln!(
-            "  - generated output for test file: {test_file}, time taken: {:.5}s",
-            elapsed_time.as_secs_f64()
-        );
-        total_time += elapsed_time;
-    }
+    run_ordered(
+        &test_files,
+        jobs,
+        |test_file| -> Result<(Duration, String)> {
+            let input_file_path = problem_path.join(format!("tests/{test_file}"));
+            let output_file_path = problem_path.join(format!(
+                "tests/{}.out",
+                test_file
+                    .strip_suffix(".in")
+                    .context("Failed to strip suffix of test file")?
+            ));
+
+            let result = run_command.get_result(Some(&input_file_path))?;
+            let mut output_file = File::create(output_file_path)?;
+            output_file.write_all(result.output.as_bytes())?;
+            Ok((result.elapsed_time, result.stderr))
+        },
+        |result| {
+            // Results arrive in the same order as the test files
+            let (elapsed_time, stderr) = result?;
+            let test_file = reported_files.next().context("Missing test file")?;
+            eprintln!(
+                "  - generated output for test file: {test_file}, time taken: {:.5}s",
+                elapsed_time.as_secs_f64()
+            );
+            print_stderr(&stderr);
+            total_time += elapsed_time;
+            Ok(())
+        },
+    )?;
     eprintln!(
         "Finished generating outputs for all test cases, time taken: {:.5}s",
         total_time.as_secs_f64()
@@ -62,5 +202,73 @@ pub fn solve(
 
     run_command.cleanup()?;
 
+    SolveStamp::create(&problem_path, &solution_file.to_string())?.write(&problem_path)?;
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::SolveStamp;
+
+    #[test]
+    fn finds_stale_outputs_from_stamp() {
+        let dir = TempDir::new().expect("tempdir should be created");
+        let problem_path = dir.path();
+        fs::create_dir_all(problem_path.join("solutions"))
+            .expect("solutions dir should be created");
+        fs::create_dir_all(problem_path.join("tests")).expect("tests dir should be created");
+        fs::write(
+            problem_path.join("solutions/solution.cpp"),
+            "int main() {}\n",
+        )
+        .expect("solution should be written");
+        for (name, contents) in [
+            ("a.in", "1 2\n"),
+            ("a.out", "3\n"),
+            ("b.in", "2 2\n"),
+            ("b.out", "4\n"),
+        ] {
+            fs::write(problem_path.join("tests").join(name), contents)
+                .expect("test should be written");
+        }
+
+        SolveStamp::create(problem_path, "solutions/solution.cpp")
+            .expect("stamp should be created")
+            .write(problem_path)
+            .expect("stamp should be written");
+        let stamp = SolveStamp::load(problem_path)
+            .expect("stamp should load")
+            .expect("stamp should exist");
+        assert!(stamp
+            .stale_reasons(problem_path)
+            .expect("stamp should be compared")
+            .is_empty());
+
+        fs::write(
+            problem_path.join("solutions/solution.cpp"),
+            "int main() { return 0; }\n",
+        )
+        .expect("solution should be written");
+        fs::write(problem_path.join("tests/a.in"), "1 3\n").expect("test should be written");
+        fs::write(problem_path.join("tests/b.out"), "5\n").expect("test should be written");
+        fs::write(problem_path.join("tests/c.in"), "0 0\n").expect("test should be written");
+        fs::write(problem_path.join("tests/c.out"), "0\n").expect("test should be written");
+
+        assert_eq!(
+            stamp
+                .stale_reasons(problem_path)
+                .expect("stamp should be compared"),
+            vec![
+                "`solutions/solution.cpp` changed since the outputs were generated",
+                "`tests/a.in` changed since its output was generated",
+                "`tests/b.out` was changed by hand after it was generated",
+                "Test 'c' was added after the outputs were generated",
+            ]
+        );
+    }
+}
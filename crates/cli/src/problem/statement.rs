@@ -0,0 +1,411 @@
+//! Parse the examples in a problem statement and keep them in sync with the
+//! sample tests.
+//!
+//! An example is a `### Input` heading followed by a code block, optionally
+//! followed by a `### Output` heading and code block. Sample tests are the tests
+//! whose name starts with `sample`, e.g. `tests/sample-1.in`, in test order.
+//!
+//! Examples must match the sample tests exactly. A code block can't show the
+//! final newline of a file, so it stands for its text followed by one newline.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use crate::problem::sync_mappings::get_problem;
+use crate::util::{get_input_files_in_directory, get_project_root};
+
+pub const PROBLEM_STATEMENT_FILE: &str = "problem.md";
+
+/// An example in a problem statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Example {
+    pub input: String,
+    pub output: Option<String>,
+}
+
+/// A part of a Markdown document that matters for finding examples.
+#[derive(Debug, PartialEq)]
+enum Block {
+    Heading { level: usize, text: String },
+    Code(String),
+}
+
+/// Split a Markdown document into headings and fenced code blocks, with the
+/// index of the line that each block starts on. Other text is skipped.
+fn parse_blocks(markdown: &str) -> Vec<(usize, Block)> {
+    let mut blocks = Vec::new();
+    let mut code: Option<(usize, Vec<&str>)> = None;
+
+    for (i, line) in markdown.lines().enumerate() {
+        if line.trim_start().starts_with("```") {
+            match code.take() {
+                Some((start, lines)) => blocks.push((start, Block::Code(lines.join("\n")))),
+                None => code = Some((i, Vec::new())),
+            }
+            continue;
+        }
+
+        if let Some((_, lines)) = &mut code {
+            lines.push(line);
+            continue;
+        }
+
+        let level = line.chars().take_while(|&c| c == '#').count();
+        if level > 0 && line[level..].starts_with(' ') {
+            blocks.push((
+                i,
+                Block::Heading {
+                    level,
+                    text: line[level..].trim().to_owned(),
+                },
+            ));
+        }
+    }
+
+    blocks
+}
+
+/// Get the code block right after the heading at `blocks[i]`, if any.
+fn code_after(blocks: &[(usize, Block)], i: usize) -> Option<&str> {
+    match blocks.get(i + 1) {
+        Some((_, Block::Code(code))) => Some(code),
+        _ => None,
+    }
+}
+
+/// Extract the examples from a problem statement. Input headings without a
+/// code block, e.g. in the template of a new problem, are skipped.
+pub fn parse_examples(markdown: &str) -> Vec<Example> {
+    let blocks = parse_blocks(markdown);
+    let mut examples: Vec<Example> = Vec::new();
+
+    for (i, (_, block)) in blocks.iter().enumerate() {
+        let Block::Heading { level: 3, text } = block else {
+            continue;
+        };
+        let Some(code) = code_after(&blocks, i) else {
+            continue;
+        };
+
+        if text.eq_ignore_ascii_case("input") {
+            examples.push(Example {
+                input: code.to_owned(),
+                output: None,
+            });
+        } else if text.eq_ignore_ascii_case("output") {
+            if let Some(example) = examples.last_mut().filter(|e| e.output.is_none()) {
+                example.output = Some(code.to_owned());
+            }
+        }
+    }
+
+    examples
+}
+
+/// The text of a file as shown in a code block, i.e. without its final newline.
+fn code_block_text(contents: &str) -> &str {
+    contents.strip_suffix('\n').unwrap_or(contents)
+}
+
+/// Render examples as Markdown sections, numbering them if there is more than one.
+pub fn examples_markdown(examples: &[Example]) -> String {
+    let sections: Vec<String> = examples
+        .iter()
+        .enumerate()
+        .map(|(i, example)| {
+            let heading = match examples.len() {
+                1 => "Example".to_owned(),
+                _ => format!("Example {}", i + 1),
+            };
+            let mut section = format!(
+                "## {heading}\n\n### Input\n\n```\n{}\n```",
+                code_block_text(&example.input)
+            );
+            if let Some(output) = &example.output {
+                section.push_str(&format!(
+                    "\n\n### Output\n\n```\n{}\n```",
+                    code_block_text(output)
+                ));
+            }
+            section
+        })
+        .collect();
+
+    sections.join("\n\n")
+}
+
+/// Split a statement around its `## Example` sections, returning the text
+/// before and after them, or `None` if it has none.
+pub fn split_examples(markdown: &str) -> Option<(String, String)> {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut start = None;
+    let mut end = lines.len();
+
+    for (line, block) in parse_blocks(markdown) {
+        let Block::Heading { level, text } = block else {
+            continue;
+        };
+        if level > 2 {
+            continue;
+        }
+        if level == 2 && text.to_lowercase().starts_with("example") {
+            start.get_or_insert(line);
+        } else if start.is_some() {
+            end = line;
+            break;
+        }
+    }
+
+    start.map(|start| (lines[..start].join("\n"), lines[end..].join("\n")))
+}
+
+/// Replace the `## Example` sections of a statement with the given examples,
+/// or add them at the end if it has none.
+pub fn replace_examples(markdown: &str, examples: &[Example]) -> String {
+    let examples = examples_markdown(examples);
+    let mut result = match split_examples(markdown) {
+        Some((before, after)) => {
+            let mut result = before.trim_end().to_owned();
+            result.push_str("\n\n");
+            result.push_str(&examples);
+            if !after.trim().is_empty() {
+                result.push_str("\n\n");
+                result.push_str(after.trim());
+            }
+            result
+        }
+        None => format!("{}\n\n{examples}", markdown.trim_end()),
+    };
+    result.push('\n');
+    result
+}
+
+/// Get the names of the sample tests of a problem, in test order.
+pub fn sample_test_names(problem_path: &Path) -> Result<Vec<String>> {
+    let names = get_input_files_in_directory(problem_path.join("tests"))?
+        .into_iter()
+        .filter(|file| file.starts_with("sample"))
+        .map(|file| file.trim_end_matches(".in").to_owned())
+        .collect();
+    Ok(names)
+}
+
+/// Read the sample tests of a problem as examples.
+fn sample_examples(problem_path: &Path, sample_names: &[String]) -> Result<Vec<Example>> {
+    sample_names
+        .iter()
+        .map(|name| {
+            let input_path = problem_path.join(format!("tests/{name}.in"));
+            let output_path = problem_path.join(format!("tests/{name}.out"));
+            let input = fs::read_to_string(&input_path)
+                .context(format!("Failed to read {}", input_path.display()))?;
+            let output = if output_path.exists() {
+                Some(
+                    fs::read_to_string(&output_path)
+                        .context(format!("Failed to read {}", output_path.display()))?,
+                )
+            } else {
+                None
+            };
+            Ok(Example { input, output })
+        })
+        .collect()
+}
+
+/// Check if a code block in the statement shows exactly the contents of a file.
+fn shows_file(code: &str, contents: &str) -> bool {
+    contents.strip_suffix('\n') == Some(code)
+}
+
+/// Check that the examples in the statement match the sample tests. Returns a
+/// description of every mismatch.
+pub fn check_samples(problem_path: &Path) -> Result<Vec<String>> {
+    let statement_path = problem_path.join(PROBLEM_STATEMENT_FILE);
+    let markdown = fs::read_to_string(&statement_path)
+        .context(format!("Failed to read {}", statement_path.display()))?;
+    let examples = parse_examples(&markdown);
+    let sample_names = sample_test_names(problem_path)?;
+    if sample_names.is_empty() {
+        if !examples.is_empty() {
+            eprintln!("Warning: There are no sample tests (`tests/sample*.in`), so the examples in {PROBLEM_STATEMENT_FILE} are not checked");
+        }
+        return Ok(Vec::new());
+    }
+    let samples = sample_examples(problem_path, &sample_names)?;
+
+    let mut mismatches = Vec::new();
+    if examples.len() != samples.len() {
+        mismatches.push(format!(
+            "{PROBLEM_STATEMENT_FILE} has {} example(s), but there are {} sample test(s)",
+            examples.len(),
+            samples.len()
+        ));
+    }
+
+    for (i, (example, (name, sample))) in examples
+        .iter()
+        .zip(sample_names.iter().zip(&samples))
+        .enumerate()
+    {
+        if !shows_file(&example.input, &sample.input) {
+            mismatches.push(format!(
+                "The input of example {} doesn't match `tests/{name}.in`",
+                i + 1
+            ));
+        }
+        match (&example.output, &sample.output) {
+            (Some(expected), Some(actual)) if !shows_file(expected, actual) => {
+                mismatches.push(format!(
+                    "The output of example {} doesn't match `tests/{name}.out`",
+                    i + 1
+                ));
+            }
+            (None, Some(_)) => mismatches.push(format!("Example {} has no output", i + 1)),
+            (_, None) => mismatches.push(format!("`tests/{name}.out` doesn't exist")),
+            _ => {}
+        }
+    }
+
+    Ok(mismatches)
+}
+
+/// Rewrite the examples in the statement of a problem from its sample tests.
+pub fn sync_samples(problems_dir: &Path, problem_name: &str) -> Result<()> {
+    let problem_path = get_project_root()?.join(get_problem(problems_dir, problem_name)?);
+    let sample_names = sample_test_names(&problem_path)?;
+    if sample_names.is_empty() {
+        bail!("Problem '{problem_name}' has no sample tests. Sample tests are named `tests/sample*.in`");
+    }
+
+    let samples = sample_examples(&problem_path, &sample_names)?;
+    if let Some(i) = samples.iter().position(|sample| sample.output.is_none()) {
+        bail!(
+            "`tests/{}.out` doesn't exist. Run `aucpl problem solve` to generate it",
+            sample_names[i]
+        );
+    }
+
+    let statement_path = problem_path.join(PROBLEM_STATEMENT_FILE);
+    let markdown = fs::read_to_string(&statement_path)
+        .context(format!("Failed to read {}", statement_path.display()))?;
+    fs::write(&statement_path, replace_examples(&markdown, &samples))
+        .context(format!("Failed to write {}", statement_path.display()))?;
+
+    eprintln!(
+        "Updated the examples of '{problem_name}' from {} sample test(s)",
+        samples.len()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{
+        check_samples, parse_examples, replace_examples, split_examples, sync_samples, Example,
+    };
+    use crate::problem::sync_mappings::sync_mappings;
+    use crate::problem::test_support::{create_problem_dir, with_test_project};
+
+    const STATEMENT: &str = "# Two Sum\n\nAdd two numbers.\n\n## Input\n\nTwo integers.\n\n## Example 1\n\n### Input\n\n```\n1 2\n```\n\n### Output\n\n```\n3\n```\n\n## Example 2\n\n### Input\n\n```\n# not a heading\n```\n\n## Notes\n\nNone.\n";
+
+    fn example(input: &str, output: &str) -> Example {
+        Example {
+            input: input.to_owned(),
+            output: Some(output.to_owned()),
+        }
+    }
+
+    #[test]
+    fn parses_examples() {
+        let examples = parse_examples(STATEMENT);
+
+        assert_eq!(
+            examples,
+            vec![
+                example("1 2", "3"),
+                Example {
+                    input: "# not a heading".to_owned(),
+                    output: None,
+                },
+            ]
+        );
+        assert!(parse_examples("## Example\n\n### Input\n\n### Output\n").is_empty());
+    }
+
+    #[test]
+    fn finds_example_sections_case_insensitively() {
+        assert_eq!(
+            split_examples("# A\n\n## EXAMPLES\n\n```\n1\n```\n\n## Notes\n"),
+            Some(("# A\n".to_owned(), "## Notes".to_owned()))
+        );
+    }
+
+    #[test]
+    fn replaces_examples_and_keeps_other_sections() {
+        let markdown = replace_examples(STATEMENT, &[example("5 6\n", "11\n")]);
+
+        assert_eq!(
+            markdown,
+            "# Two Sum\n\nAdd two numbers.\n\n## Input\n\nTwo integers.\n\n## Example\n\n### Input\n\n```\n5 6\n```\n\n### Output\n\n```\n11\n```\n\n## Notes\n\nNone.\n"
+        );
+        assert_eq!(
+            replace_examples("# A\n", &[example("1", "2")]),
+            "# A\n\n## Example\n\n### Input\n\n```\n1\n```\n\n### Output\n\n```\n2\n```\n"
+        );
+    }
+
+    #[test]
+    fn checks_and_syncs_samples() {
+        with_test_project(|problems_dir| {
+            create_problem_dir(problems_dir, "new", "easy", "two-sum");
+            sync_mappings(problems_dir).expect("mappings should sync");
+            let problem_path = problems_dir.join("new/easy/two-sum");
+            fs::write(problem_path.join("problem.md"), STATEMENT)
+                .expect("statement should be written");
+            fs::write(problem_path.join("tests/sample-1.in"), "1 2\n")
+                .expect("test should be written");
+            fs::write(problem_path.join("tests/sample-1.out"), "3\n")
+                .expect("test should be written");
+            fs::write(problem_path.join("tests/sample-2.in"), "4 5\n")
+                .expect("test should be written");
+            fs::write(problem_path.join("tests/sample-2.out"), "9\n")
+                .expect("test should be written");
+            fs::write(problem_path.join("tests/a.in"), "7 8\n").expect("test should be written");
+
+            let mismatches = check_samples(&problem_path).expect("samples should be checked");
+            assert_eq!(
+                mismatches,
+                vec![
+                    "The input of example 2 doesn't match `tests/sample-2.in`",
+                    "Example 2 has no output",
+                ]
+            );
+
+            sync_samples(problems_dir, "two-sum").expect("samples should sync");
+            assert!(check_samples(&problem_path)
+                .expect("samples should be checked")
+                .is_empty());
+
+            // Trailing whitespace must match too, and syncing keeps it
+            fs::write(problem_path.join("tests/sample-1.out"), "3  \n\n")
+                .expect("test should be written");
+            assert_eq!(
+                check_samples(&problem_path).expect("samples should be checked"),
+                vec!["The output of example 1 doesn't match `tests/sample-1.out`"]
+            );
+            sync_samples(problems_dir, "two-sum").expect("samples should sync");
+            assert!(check_samples(&problem_path)
+                .expect("samples should be checked")
+                .is_empty());
+            let markdown = fs::read_to_string(problem_path.join("problem.md"))
+                .expect("file should be readable");
+            assert!(markdown.contains("## Example 2\n\n### Input\n\n```\n4 5\n```"));
+            assert!(markdown.ends_with("## Notes\n\nNone.\n"));
+        });
+    }
+}
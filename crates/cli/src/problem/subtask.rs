@@ -0,0 +1,345 @@
+//! Subtasks (groups of tests) with partial scoring, declared in the problem
+//! metadata:
+//!
+//! ```toml
+//! [[subtasks]]
+//! name = "small"
+//! points = 30
+//!
+//! [[subtasks]]
+//! name = "full"
+//! points = 70
+//! tests = ["full/*", "big-*"]
+//! depends_on = ["small"]
+//! ```
+//!
+//! If `tests` is not given, the subtask is made up of the tests in the
+//! `tests/<name>/` folder. A subtask only earns its points if all of its tests
+//! pass and all of the subtasks it depends on earn their points.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use super::metadata::PROBLEM_METADATA_FILE;
+use super::report::TestCaseReport;
+
+/// A group of tests that is scored together.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Subtask {
+    pub name: String,
+    pub points: u32,
+    /// Names of the tests in the subtask, where `*` matches any characters,
+    /// e.g. `subtask1/*`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tests: Vec<String>,
+    /// Subtasks that must also pass for this subtask to earn its points.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+}
+
+impl Subtask {
+    /// Check if the test with the given name, e.g. `subtask1/a`, belongs to the subtask.
+    pub fn contains(&self, test_name: &str) -> bool {
+        if self.tests.is_empty() {
+            return test_name
+                .strip_prefix(&self.name)
+                .is_some_and(|rest| rest.starts_with('/'));
+        }
+
+        self.tests
+            .iter()
+            .any(|pattern| matches_pattern(pattern, test_name))
+    }
+}
+
+/// Match a name against a pattern where `*` matches any (possibly empty)
+/// sequence of characters.
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<_> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No `*` in the pattern
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(last)
+}
+
+/// Check that subtask names are unique and that the dependencies exist and
+/// have no cycles.
+pub fn validate_subtasks(subtasks: &[Subtask]) -> Result<()> {
+    let mut names = HashSet::new();
+    for subtask in subtasks {
+        if subtask.name.trim().is_empty() {
+            bail!("Subtask names in {PROBLEM_METADATA_FILE} must not be empty");
+        }
+        if !names.insert(subtask.name.as_str()) {
+            bail!(
+                "There is more than one subtask named '{}' in {PROBLEM_METADATA_FILE}",
+                subtask.name
+            );
+        }
+    }
+
+    for subtask in subtasks {
+        for dependency in &subtask.depends_on {
+            if !names.contains(dependency.as_str()) {
+                bail!(
+                    "Subtask '{}' depends on '{dependency}', which doesn't exist",
+                    subtask.name
+                );
+            }
+        }
+    }
+
+    // Depth-first search for a cycle, where `visiting` holds the subtasks on the current path
+    fn visit<'a>(
+        name: &'a str,
+        dependencies: &HashMap<&'a str, &'a [String]>,
+        visiting: &mut Vec<&'a str>,
+        done: &mut HashSet<&'a str>,
+    ) -> Result<()> {
+        if done.contains(name) {
+            return Ok(());
+        }
+        if let Some(start) = visiting.iter().position(|&visited| visited == name) {
+            let mut cycle = visiting[start..].to_vec();
+            cycle.push(name);
+            bail!("Subtask dependencies form a cycle: {}", cycle.join(" -> "));
+        }
+
+        visiting.push(name);
+        for dependency in dependencies[name] {
+            visit(dependency, dependencies, visiting, done)?;
+        }
+        visiting.pop();
+        done.insert(name);
+
+        Ok(())
+    }
+
+    let dependencies: HashMap<&str, &[String]> = subtasks
+        .iter()
+        .map(|subtask| (subtask.name.as_str(), subtask.depends_on.as_slice()))
+        .collect();
+    let mut done = HashSet::new();
+    for subtask in subtasks {
+        visit(&subtask.name, &dependencies, &mut Vec::new(), &mut done)?;
+    }
+
+    Ok(())
+}
+
+/// Check that every subtask has at least one test, and warn about tests that
+/// are not in any subtask, since they don't count towards the score.
+pub fn check_subtask_tests(subtasks: &[Subtask], test_names: &[&str]) -> Result<()> {
+    if subtasks.is_empty() {
+        return Ok(());
+    }
+
+    for subtask in subtasks {
+        if !test_names.iter().any(|name| subtask.contains(name)) {
+            bail!("Subtask '{}' has no tests", subtask.name);
+        }
+    }
+
+    for name in test_names {
+        if !subtasks.iter().any(|subtask| subtask.contains(name)) {
+            eprintln!("Warning: Test '{name}' is not in any subtask, so it doesn't count towards the score");
+        }
+    }
+
+    Ok(())
+}
+
+/// The score of a single subtask.
+pub struct SubtaskReport {
+    pub name: String,
+    pub points: u32,
+    /// The points earned, either `0` or all of the points.
+    pub earned: u32,
+    /// Tests of the subtask that failed.
+    pub failed_tests: Vec<String>,
+    /// Dependencies of the subtask that did not earn their points.
+    pub failed_dependencies: Vec<String>,
+}
+
+impl SubtaskReport {
+    pub fn passed(&self) -> bool {
+        self.failed_tests.is_empty() && self.failed_dependencies.is_empty()
+    }
+}
+
+/// Score every subtask from the results of the test cases. The subtasks must
+/// have been validated with [`validate_subtasks`].
+pub fn score_subtasks(subtasks: &[Subtask], tests: &[TestCaseReport]) -> Vec<SubtaskReport> {
+    fn passes<'a>(
+        name: &'a str,
+        subtasks: &HashMap<&'a str, (&'a Subtask, Vec<String>)>,
+        memo: &mut HashMap<&'a str, bool>,
+    ) -> bool {
+        if let Some(&passed) = memo.get(name) {
+            return passed;
+        }
+
+        let (subtask, failed_tests) = &subtasks[name];
+        let passed = failed_tests.is_empty()
+            && subtask
+                .depends_on
+                .iter()
+                .all(|dependency| passes(dependency, subtasks, memo));
+        memo.insert(name, passed);
+        passed
+    }
+
+    let by_name: HashMap<&str, (&Subtask, Vec<String>)> = subtasks
+        .iter()
+        .map(|subtask| {
+            let failed_tests = tests
+                .iter()
+                .filter(|test| subtask.contains(&test.name) && !test.verdict.is_accepted())
+                .map(|test| test.name.clone())
+                .collect();
+            (subtask.name.as_str(), (subtask, failed_tests))
+        })
+        .collect();
+
+    let mut memo = HashMap::new();
+    subtasks
+        .iter()
+        .map(|subtask| {
+            let failed_tests = by_name[subtask.name.as_str()].1.clone();
+            let failed_dependencies: Vec<String> = subtask
+                .depends_on
+                .iter()
+                .filter(|dependency| !passes(dependency, &by_name, &mut memo))
+                .cloned()
+                .collect();
+            let earned = if failed_tests.is_empty() && failed_dependencies.is_empty() {
+                subtask.points
+            } else {
+                0
+            };
+
+            SubtaskReport {
+                name: subtask.name.clone(),
+                points: subtask.points,
+                earned,
+                failed_tests,
+                failed_dependencies,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{check_subtask_tests, matches_pattern, score_subtasks, validate_subtasks, Subtask};
+    use crate::problem::report::TestCaseReport;
+    use crate::problem::verdict::Verdict;
+
+    fn subtask(name: &str, points: u32, tests: &[&str], depends_on: &[&str]) -> Subtask {
+        Subtask {
+            name: name.to_owned(),
+            points,
+            tests: tests.iter().map(|&s| s.to_owned()).collect(),
+            depends_on: depends_on.iter().map(|&s| s.to_owned()).collect(),
+        }
+    }
+
+    fn test_case(name: &str, verdict: Verdict) -> TestCaseReport {
+        TestCaseReport {
+            name: name.to_owned(),
+            verdict,
+            elapsed_time: Duration::ZERO,
+            peak_memory: None,
+            message: None,
+            stderr: String::new(),
+        }
+    }
+
+    #[test]
+    fn matches_test_names() {
+        assert!(matches_pattern("a", "a"));
+        assert!(!matches_pattern("a", "ab"));
+        assert!(matches_pattern("big-*", "big-1"));
+        assert!(matches_pattern("*-1", "big-1"));
+        assert!(matches_pattern("s*/*", "small/a"));
+        assert!(!matches_pattern("s*/*", "big-1"));
+        assert!(matches_pattern("*", ""));
+
+        let small = subtask("small", 30, &[], &[]);
+        assert!(small.contains("small/a"));
+        assert!(!small.contains("smaller/a"));
+        assert!(!small.contains("small"));
+    }
+
+    #[test]
+    fn rejects_invalid_subtasks() {
+        assert!(
+            validate_subtasks(&[subtask("a", 10, &[], &[]), subtask("a", 10, &[], &[])]).is_err()
+        );
+        assert!(validate_subtasks(&[subtask("a", 10, &[], &["b"])]).is_err());
+
+        let err = validate_subtasks(&[
+            subtask("a", 10, &[], &["b"]),
+            subtask("b", 10, &[], &["c"]),
+            subtask("c", 10, &[], &["a"]),
+        ])
+        .expect_err("cycle should be rejected");
+        assert_eq!(
+            err.to_string(),
+            "Subtask dependencies form a cycle: a -> b -> c -> a"
+        );
+
+        validate_subtasks(&[subtask("a", 10, &[], &[]), subtask("b", 10, &[], &["a"])])
+            .expect("subtasks should be valid");
+    }
+
+    #[test]
+    fn subtasks_must_have_tests() {
+        let subtasks = [subtask("small", 30, &[], &[])];
+        assert!(check_subtask_tests(&subtasks, &["small/a", "b"]).is_ok());
+        assert!(check_subtask_tests(&subtasks, &["a", "b"]).is_err());
+    }
+
+    #[test]
+    fn scores_subtasks_with_dependencies() {
+        let subtasks = [
+            subtask("small", 30, &[], &[]),
+            subtask("medium", 30, &["medium/*"], &["small"]),
+            subtask("large", 40, &["large-*"], &["medium"]),
+        ];
+        let tests = [
+            test_case("small/1", Verdict::Accepted),
+            test_case("medium/1", Verdict::Accepted),
+            test_case("medium/2", Verdict::WrongAnswer),
+            test_case("large-1", Verdict::Accepted),
+        ];
+
+        let scores = score_subtasks(&subtasks, &tests);
+        let earned: Vec<_> = scores.iter().map(|subtask| subtask.earned).collect();
+
+        assert_eq!(earned, vec![30, 0, 0]);
+        assert_eq!(scores[1].failed_tests, vec!["medium/2".to_owned()]);
+        assert!(scores[2].failed_tests.is_empty());
+        assert_eq!(scores[2].failed_dependencies, vec!["medium".to_owned()]);
+        assert!(!scores[2].passed());
+    }
+}
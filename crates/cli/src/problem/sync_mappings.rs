@@ -1,18 +1,23 @@
 use std::collections::{BTreeMap, HashMap};
-use std::fs::{self, File};
+use std::fs;
 use std::path::Path;
 
 use anyhow::{Context, Result};
 use normpath::PathExt;
-use serde_json::{from_reader, json, to_writer, to_writer_pretty};
 use walkdir::WalkDir;
 
 use crate::errors::CliError;
 use crate::paths::{convert_legacy_path, normalize_for_storage, resolve_stored_path};
 use crate::problem::PROBLEM_MAPPINGS_FILE;
+use crate::store::{read_json, write_json, StoreLock};
 use crate::suggest::suggest_corrections;
 use crate::util::get_project_root;
 
+/// Read the problem mappings, or none if the mappings file does not exist yet.
+fn read_mappings(path: &Path) -> Result<HashMap<String, String>> {
+    Ok(read_json(path)?.unwrap_or_default())
+}
+
 /// Sync problem mappings
 ///
 /// The problem mappings file, `problem-mappings.json` maps the problem name
@@ -20,14 +25,11 @@ use crate::util::get_project_root;
 pub fn sync_mappings(problems_dir: &Path) -> Result<()> {
     let project_root = get_project_root()?;
     let problems_dir = problems_dir.normalize()?;
-    let path = &problems_dir.join(PROBLEM_MAPPINGS_FILE);
-    if !fs::exists(path)? {
-        let file = File::create(path)?;
-        to_writer(&file, &json!({}))?;
-    }
-
-    let mut mappings_file = File::open(path)?;
-    let mut mappings: HashMap<String, String> = from_reader(&mappings_file)?;
+    let path = problems_dir.join(PROBLEM_MAPPINGS_FILE);
+    let path = path.as_path();
+    let _lock = StoreLock::acquire(path)?;
+    let mut mappings = read_mappings(path)?;
+    let original: BTreeMap<_, _> = mappings.clone().into_iter().collect();
 
     // Remove non-existent problems
     // Convert stored Unix paths to platform-native paths for existence check
@@ -57,9 +59,12 @@ pub fn sync_mappings(problems_dir: &Path) -> Result<()> {
         mappings.insert(folder_name, relative_path);
     }
 
+    // Only write the file if the mappings changed, so that the backup keeps the
+    // previous mappings
     let ordered_mappings: BTreeMap<_, _> = mappings.into_iter().collect();
-    mappings_file = File::options().write(true).truncate(true).open(path)?;
-    to_writer_pretty(mappings_file, &ordered_mappings)?;
+    if ordered_mappings != original || !fs::exists(path)? {
+        write_json(path, &ordered_mappings)?;
+    }
 
     Ok(())
 }
@@ -68,13 +73,7 @@ pub fn sync_mappings(problems_dir: &Path) -> Result<()> {
 /// If the problem doesn't exist, then the problem mappings file is synced.
 pub fn get_problem(problems_dir: &Path, problem: &str) -> Result<String> {
     let mappings_file_path = &problems_dir.join(PROBLEM_MAPPINGS_FILE);
-    if !fs::exists(mappings_file_path)? {
-        let file = File::create(mappings_file_path)?;
-        to_writer(&file, &json!({}))?;
-    }
-
-    let mut mappings_file = File::open(mappings_file_path)?;
-    let mut mappings: HashMap<String, String> = from_reader(&mappings_file)?;
+    let mut mappings = read_mappings(mappings_file_path)?;
 
     // Return problem name if possible
     // Convert legacy Windows paths (with backslashes) to Unix-style paths
@@ -83,8 +82,7 @@ pub fn get_problem(problems_dir: &Path, problem: &str) -> Result<String> {
         None => {
             // Sync mappings file and try again
             sync_mappings(problems_dir)?;
-            mappings_file = File::open(mappings_file_path)?;
-            mappings = from_reader(&mappings_file)?;
+            mappings = read_mappings(mappings_file_path)?;
 
             match mappings.get(problem) {
                 Some(val) => Ok(convert_legacy_path(val)),
@@ -108,35 +106,25 @@ pub fn get_problem(problems_dir: &Path, problem: &str) -> Result<String> {
 
 /// Check if a problem exists in the problem mappings file.
 pub fn problem_exists(problems_dir: &Path, problem: &str) -> Result<bool> {
-    let mappings_file_path = &problems_dir.join(PROBLEM_MAPPINGS_FILE);
-    if !fs::exists(mappings_file_path)? {
-        let file = File::create(mappings_file_path)?;
-        to_writer(&file, &json!({}))?;
-    }
-
-    let mappings_file = File::open(mappings_file_path)?;
-    let mappings: HashMap<String, String> = from_reader(&mappings_file)?;
+    let mappings = read_mappings(&problems_dir.join(PROBLEM_MAPPINGS_FILE))?;
 
     Ok(mappings.contains_key(problem))
 }
 
 /// Get all problem names from the mappings file.
 pub fn get_all_problem_names(problems_dir: &Path) -> Result<Vec<String>> {
-    let mappings_file_path = &problems_dir.join(PROBLEM_MAPPINGS_FILE);
-    if !fs::exists(mappings_file_path)? {
-        return Ok(vec![]);
-    }
-
-    let mappings_file = File::open(mappings_file_path)?;
-    let mappings: HashMap<String, String> = from_reader(&mappings_file)?;
+    let mappings = read_mappings(&problems_dir.join(PROBLEM_MAPPINGS_FILE))?;
 
     Ok(mappings.keys().cloned().collect())
 }
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
+
     use super::{get_problem, sync_mappings};
     use crate::problem::test_support::{create_problem_dir, with_test_project};
+    use crate::problem::PROBLEM_MAPPINGS_FILE;
 
     #[test]
     fn discovers_problems_at_status_category_problem_depth() {
@@ -161,4 +149,25 @@ mod tests {
             );
         });
     }
+
+    #[test]
+    fn backup_keeps_previous_mappings() {
+        with_test_project(|problems_dir| {
+            let backup_path = problems_dir.join(format!("{PROBLEM_MAPPINGS_FILE}.bak"));
+            create_problem_dir(problems_dir, "new", "easy", "alpha");
+            sync_mappings(problems_dir).expect("mappings should sync");
+            let first = fs::read_to_string(problems_dir.join(PROBLEM_MAPPINGS_FILE))
+                .expect("mappings should be written");
+
+            create_problem_dir(problems_dir, "new", "easy", "beta");
+            sync_mappings(problems_dir).expect("mappings should sync");
+            sync_mappings(problems_dir).expect("mappings should sync again");
+            get_problem(problems_dir, "gamma").expect_err("gamma should not exist");
+
+            assert_eq!(
+                fs::read_to_string(backup_path).expect("backup should be written"),
+                first
+            );
+        });
+    }
 }
@@ -1,171 +1,237 @@
-use std::fs::File;
-use std::io::Read;
 use std::path::Path;
-use std::path::PathBuf;
 use std::time::Duration;
 
-use anyhow::{bail, Context, Result};
+use anyhow::Result;
 
 use crate::config::Settings;
-use crate::problem::run::{get_python_executable, RunCommand, RunnableFile};
-use crate::util::{get_input_files_in_directory, get_project_root};
+use crate::problem::checker::OutputChecker;
+use crate::problem::comparator::Comparator;
+use crate::problem::metadata::{CheckerType, ProblemMetadata};
+use crate::problem::report::{ProblemReport, TestCaseReport};
+use crate::problem::run::{format_memory, print_stderr, RunCommand, RunLimits, RunnableFile};
+use crate::problem::subtask::{check_subtask_tests, score_subtasks};
+use crate::problem::verdict::Verdict;
+use crate::util::{get_input_files_in_directory, get_project_root, run_ordered};
 
 use super::sync_mappings::get_problem;
 
-const PYTHON_CHECKER_SCRIPT: &str = r#"
-import importlib.util
-import sys
-
-checker_path = sys.argv[1]
-process_output = sys.argv[2]
-judge_output = sys.argv[3]
-
-judge_input = sys.stdin.read()
-
-spec = importlib.util.spec_from_file_location("aucpl_checker", checker_path)
-if spec is None or spec.loader is None:
-    print("Could not load checker.py", file=sys.stderr)
-    sys.exit(2)
-
-module = importlib.util.module_from_spec(spec)
-spec.loader.exec_module(module)
-
-if not hasattr(module, "check"):
-    print("checker.py must define a `check` function", file=sys.stderr)
-    sys.exit(2)
-
-result = module.check(process_output, judge_output, judge_input=judge_input)
-
-print("true" if bool(result) else "false")
-"#;
-
-fn run_custom_checker(
-    settings: &Settings,
-    checker_path: &Path,
-    process_output: &str,
-    judge_output: &[u8],
-    input_file_path: &PathBuf,
-) -> Result<bool> {
-    let judge_output = String::from_utf8_lossy(judge_output).into_owned();
-    let python_cmd = get_python_executable(settings);
-
-    let checker_run = RunCommand::from_command(
-        PathBuf::new(),
-        checker_path.to_path_buf(),
-        vec![
-            python_cmd,
-            "-c".to_string(),
-            PYTHON_CHECKER_SCRIPT.to_string(),
-            "@script_file".to_string(),
-            process_output.to_string(),
-            judge_output,
-        ],
-    )
-    .context("Failed to prepare checker command")?;
-    let checker_result = checker_run
-        .get_result(Some(input_file_path))
-        .context("Failed to run checker.py")?
-        .output;
-
-    let passed = match checker_result.trim().to_ascii_lowercase().as_str() {
-        "true" => true,
-        "false" => false,
-        other => {
-            bail!(
-                "checker.py must return a bool-compatible result, got: {}",
-                other
-            )
+/// Run the solution on a single test case and judge its output.
+///
+/// If the test case can't be judged, e.g. because its expected output is
+/// missing or the checker failed, it is given a judge error instead of
+/// stopping the whole run.
+fn run_test_case(
+    run_command: &RunCommand,
+    checker: &OutputChecker,
+    problem_path: &Path,
+    test_file: &str,
+) -> TestCaseReport {
+    let test_name = test_file.trim_end_matches(".in");
+    let input_file_path = problem_path.join(format!("tests/{test_file}"));
+    let output_file_path = problem_path.join(format!("tests/{test_name}.out"));
+
+    let result = match run_command.get_result(Some(&input_file_path)) {
+        Ok(result) => result,
+        Err(err) => {
+            return TestCaseReport {
+                name: test_name.to_owned(),
+                verdict: Verdict::JudgeError,
+                elapsed_time: Duration::ZERO,
+                peak_memory: None,
+                message: Some(format!("{err:#}")),
+                stderr: String::new(),
+            }
         }
     };
 
-    Ok(passed)
+    // Only check the output if the solution ran to completion
+    let (verdict, message) = match Verdict::from_run_result(&result) {
+        Some(verdict) => (verdict, None),
+        None => match checker.check(&input_file_path, &output_file_path, &result.output) {
+            Ok(check) => (check.verdict, check.message),
+            Err(err) => (Verdict::JudgeError, Some(format!("{err:#}"))),
+        },
+    };
+
+    TestCaseReport {
+        name: test_name.to_owned(),
+        verdict,
+        elapsed_time: result.elapsed_time,
+        peak_memory: result.peak_memory,
+        message,
+        stderr: result.stderr,
+    }
 }
 
-/// Automatically run tests on the problem.
+/// Automatically run tests on the problem, returning the result of every test case.
+///
+/// Limits and a comparator given here take priority over the ones in the
+/// problem metadata. Up to `jobs` test cases are run at the same time, but
+/// results are always reported in test file order. If the problem has
+/// subtasks, the report also includes the score of each subtask.
 pub fn test(
     settings: &Settings,
     problems_dir: &Path,
     problem_name: &str,
     solution_file: &RunnableFile,
-) -> Result<()> {
+    limits: RunLimits,
+    comparator: Option<Comparator>,
+    jobs: usize,
+) -> Result<ProblemReport> {
     let project_root = get_project_root()?;
     let problem_path = project_root.join(get_problem(problems_dir, problem_name)?);
 
+    let metadata = ProblemMetadata::load_or_default(&problem_path)?;
+    let limits = limits.or(metadata.run_limits()?);
+    let checker_type = comparator.map_or(metadata.checker, CheckerType::Builtin);
+
+    let test_files = get_input_files_in_directory(problem_path.join("tests"))?;
+    let test_names: Vec<_> = test_files
+        .iter()
+        .map(|file| file.trim_end_matches(".in"))
+        .collect();
+    check_subtask_tests(&metadata.subtasks, &test_names)?;
+
     let run_command = RunCommand::new(
         settings,
         &problem_path,
         solution_file,
         problem_path.join("solutions/solution.out"),
         problem_path.join(format!("{solution_file}")),
-    )?;
-
-    let test_files = get_input_files_in_directory(problem_path.join("tests"))?;
-    let checker_path = problem_path.join("checker.py");
-    let use_custom_checker = checker_path.exists();
+    )?
+    .with_limits(limits);
+
+    let checker = match OutputChecker::for_problem(settings, &problem_path, checker_type) {
+        Ok(checker) => checker,
+        Err(err) => {
+            run_command.cleanup()?;
+            return Err(err);
+        }
+    };
 
     eprintln!("Running the solution file for each test case...");
-    if use_custom_checker {
-        eprintln!("Using custom checker at: {}", checker_path.display());
+    eprintln!("Using {}", checker.description());
+    if let Some(time_limit) = limits.time_limit {
+        eprintln!("Time limit: {:.5}s", time_limit.as_secs_f64());
+    }
+    if limits.memory_limit.is_some() {
+        eprintln!("Memory limit: {}", format_memory(limits.memory_limit));
     }
 
-    let mut tests_passed = 0;
-    let mut total_tests = 0;
-    let mut total_time = Duration::new(0, 0);
-
-    for test_file in test_files {
-        let input_file_path = problem_path.join(format!("tests/{test_file}"));
-        let output_file_path = problem_path.join(format!(
-            "tests/{}.out",
-            test_file
-                .strip_suffix(".in")
-                .context("Failed to strip suffix of test file")?
-        ));
-
-        let result = run_command.get_result(Some(&input_file_path))?;
-
-        let mut output_file = File::open(output_file_path)?;
-        let out_str = result.output;
-        let elapsed_time = result.elapsed_time;
-
-        // Compare the output with the expected output
-        let expected: &mut Vec<u8> = &mut Vec::new();
-        output_file.read_to_end(expected)?;
-
-        let passed = if use_custom_checker {
-            run_custom_checker(
-                settings,
-                &checker_path,
-                &out_str,
-                expected,
-                &input_file_path,
-            )?
-        } else {
-            expected == out_str.as_bytes()
-        };
-
-        if !passed {
-            eprintln!(
-                "  ! Test case failed: {test_file}, time taken: {:.5}s",
-                elapsed_time.as_secs_f64()
-            );
-        } else {
+    let mut report = ProblemReport::new(problem_name);
+
+    let run_result = run_ordered(
+        &test_files,
+        jobs,
+        |test_file| run_test_case(&run_command, &checker, &problem_path, test_file),
+        |test_case| {
+            let verdict = &test_case.verdict;
+            let marker = if verdict.is_accepted() { "+" } else { "!" };
             eprintln!(
-                "  + Test case passed: {test_file}, time taken: {:.5}s",
-                elapsed_time.as_secs_f64()
+                "  {marker} [{}] {}.in: {verdict}, time taken: {:.5}s, memory: {}",
+                verdict.code(),
+                test_case.name,
+                test_case.elapsed_time.as_secs_f64(),
+                format_memory(test_case.peak_memory)
             );
-            tests_passed += 1;
-        }
+            match (verdict, &test_case.message) {
+                (Verdict::Accepted, _) | (_, None) => {}
+                (Verdict::JudgeError, Some(message)) => eprintln!("      Error: {message}"),
+                (_, Some(message)) => eprintln!("      Checker: {message}"),
+            }
+            if !verdict.is_accepted() {
+                print_stderr(&test_case.stderr);
+            }
+
+            report.tests.push(test_case);
+            Ok(())
+        },
+    );
 
-        total_tests += 1;
-        total_time += elapsed_time;
-    }
+    // Clean up even if the run was stopped early so no binaries are left behind
+    let cleanup_result = run_command.cleanup().and(checker.cleanup());
+    run_result?;
+    cleanup_result?;
 
+    let summary = report.summary();
     eprintln!(
-        "{tests_passed} out of {total_tests} test cases passed, time taken: {:.5}s",
-        total_time.as_secs_f64()
+        "{} out of {} test cases passed, time taken: {:.5}s",
+        summary.count("AC"),
+        summary.total(),
+        report.total_time().as_secs_f64()
     );
+    if summary.total() > 0 {
+        eprintln!("Verdicts: {summary}");
+    }
+
+    report.subtasks = score_subtasks(&metadata.subtasks, &report.tests);
+    if !report.subtasks.is_empty() {
+        eprintln!("Subtasks:");
+        for subtask in &report.subtasks {
+            let marker = if subtask.passed() { "+" } else { "!" };
+            let mut reasons = Vec::new();
+            if !subtask.failed_tests.is_empty() {
+                reasons.push(format!("failed {}", subtask.failed_tests.join(", ")));
+            }
+            if !subtask.failed_dependencies.is_empty() {
+                reasons.push(format!(
+                    "depends on {}",
+                    subtask.failed_dependencies.join(", ")
+                ));
+            }
+            let reasons = if reasons.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", reasons.join("; "))
+            };
+            eprintln!(
+                "  {marker} {}: {}/{} points{reasons}",
+                subtask.name, subtask.earned, subtask.points
+            );
+        }
+        eprintln!("Score: {}/{}", report.score(), report.max_score());
+    }
 
-    run_command.cleanup()?;
+    Ok(report)
+}
 
-    Ok(())
+#[cfg(all(test, unix))]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use tempfile::TempDir;
+
+    use super::run_test_case;
+    use crate::problem::checker::OutputChecker;
+    use crate::problem::comparator::Comparator;
+    use crate::problem::run::RunCommand;
+    use crate::problem::verdict::Verdict;
+
+    #[test]
+    fn gives_tests_that_cannot_be_judged_a_judge_error() {
+        let dir = TempDir::new().expect("tempdir should be created");
+        let problem_path = dir.path();
+        fs::create_dir_all(problem_path.join("tests")).expect("tests dir should be created");
+        fs::write(problem_path.join("tests/1.in"), "42\n").expect("input should be written");
+        fs::write(problem_path.join("tests/1.out"), "42\n").expect("answer should be written");
+        fs::write(problem_path.join("tests/2.in"), "7\n").expect("input should be written");
+
+        let run_command =
+            RunCommand::from_command(PathBuf::new(), PathBuf::new(), vec!["cat".to_owned()])
+                .expect("run command should be created");
+        let checker = OutputChecker::Builtin(Comparator::Exact);
+
+        let judged = run_test_case(&run_command, &checker, problem_path, "1.in");
+        assert_eq!(judged.name, "1");
+        assert_eq!(judged.verdict, Verdict::Accepted);
+
+        let unjudged = run_test_case(&run_command, &checker, problem_path, "2.in");
+        assert_eq!(unjudged.name, "2");
+        assert_eq!(unjudged.verdict, Verdict::JudgeError);
+        assert!(unjudged.message.is_some());
+
+        let missing_input = run_test_case(&run_command, &checker, problem_path, "3.in");
+        assert_eq!(missing_input.verdict, Verdict::JudgeError);
+    }
 }
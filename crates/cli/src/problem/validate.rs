@@ -0,0 +1,227 @@
+//! Validate test inputs against the problem constraints with a validator.
+//!
+//! A validator is a `validator.<lang>` file in the problem folder, written in any
+//! language in the settings (e.g. a C++ validator using testlib). It reads a test
+//! input from stdin and exits with code 0 if the input is valid. Otherwise, it
+//! should exit with a non-zero code and describe the violated constraint on stderr.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use crate::config::Settings;
+use crate::problem::run::{find_file_langs, RunCommand, RunnableCategory, RunnableFile};
+use crate::problem::sync_mappings::get_problem;
+use crate::util::{get_input_files_in_directory, get_project_root};
+
+/// A compiled validator for a problem.
+pub struct Validator {
+    run_command: RunCommand,
+}
+
+impl Validator {
+    /// Get the validator for a problem, compiling it if needed. Returns `None`
+    /// if the problem has no validator.
+    pub fn for_problem(settings: &Settings, problem_path: &Path) -> Result<Option<Self>> {
+        let langs = find_file_langs(settings, problem_path, "validator")?;
+        let lang = match langs.as_slice() {
+            [] => return Ok(None),
+            [lang] => lang,
+            _ => bail!(
+                "Found multiple validators: {}. Only one validator is allowed per problem",
+                langs
+                    .iter()
+                    .map(|lang| format!("validator.{lang}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+
+        let file = RunnableFile::new(settings, RunnableCategory::Validator, None, Some(lang))?;
+        let run_command = RunCommand::new(
+            settings,
+            problem_path,
+            &file,
+            problem_path.join("validator.out"),
+            problem_path.join(format!("{file}")),
+        )
+        .context("Failed to prepare validator")?;
+
+        Ok(Some(Self { run_command }))
+    }
+
+    /// Run the validator on a test input. Returns `None` if the input is valid,
+    /// or the reason it is invalid otherwise.
+    pub fn validate_input(&self, input_file_path: &PathBuf) -> Result<Option<String>> {
+        let result = self
+            .run_command
+            .get_result(Some(input_file_path))
+            .context("Failed to run validator")?;
+
+        if result.exit_status.success() {
+            return Ok(None);
+        }
+
+        let message = result.stderr.trim();
+        let reason = if !message.is_empty() {
+            message.to_owned()
+        } else if let Some(signal) = result.exit_status.signal() {
+            format!("validator was killed by signal {signal}")
+        } else {
+            format!(
+                "validator exited with code {}",
+                result.exit_status.code().unwrap_or_default()
+            )
+        };
+
+        Ok(Some(reason))
+    }
+
+    /// Cleans up the compiled validator binary if it exists.
+    pub fn cleanup(&self) -> Result<()> {
+        self.run_command.cleanup()
+    }
+}
+
+/// Run the validator on every test input of the problem, returning the number
+/// of invalid inputs.
+pub fn validate_tests(validator: &Validator, problem_path: &Path) -> Result<usize> {
+    let test_files = get_input_files_in_directory(problem_path.join("tests"))?;
+
+    let mut invalid_inputs = 0;
+    for test_file in &test_files {
+        let input_file_path = problem_path.join(format!("tests/{test_file}"));
+        match validator.validate_input(&input_file_path)? {
+            None => eprintln!("  + {test_file} is valid"),
+            Some(reason) => {
+                eprintln!("  ! {test_file} is invalid: {reason}");
+                invalid_inputs += 1;
+            }
+        }
+    }
+
+    Ok(invalid_inputs)
+}
+
+/// Validate every test input of a problem with its validator.
+pub fn validate(settings: &Settings, problems_dir: &Path, problem_name: &str) -> Result<()> {
+    let project_root = get_project_root()?;
+    let problem_path = project_root.join(get_problem(problems_dir, problem_name)?);
+
+    let validator = Validator::for_problem(settings, &problem_path)?.context(format!(
+        "No validator found for '{problem_name}'. Add a `validator.<lang>` file to the problem folder"
+    ))?;
+
+    eprintln!("Validating test inputs...");
+    let invalid_inputs = validate_tests(&validator, &problem_path);
+    validator.cleanup()?;
+    let invalid_inputs = invalid_inputs?;
+
+    let total = get_input_files_in_directory(problem_path.join("tests"))?.len();
+    if invalid_inputs > 0 {
+        bail!("{invalid_inputs} out of {total} test inputs are invalid");
+    }
+    eprintln!("All {total} test inputs are valid");
+
+    Ok(())
+}
+
+/// Validate a newly generated test input, deleting it if it is invalid.
+/// Returns the reason the input was rejected, if it was.
+pub fn validate_generated_input(
+    validator: &Validator,
+    input_file_path: &PathBuf,
+) -> Result<Option<String>> {
+    let reason = validator.validate_input(input_file_path)?;
+    if reason.is_some() {
+        fs::remove_file(input_file_path).context("Failed to delete invalid test input")?;
+    }
+
+    Ok(reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::Validator;
+    use crate::config::get_settings;
+    use crate::problem::test_support::{create_problem_dir, with_test_project};
+
+    #[test]
+    fn finds_validator_in_problem_folder() {
+        with_test_project(|problems_dir| {
+            create_problem_dir(problems_dir, "new", "easy", "two-sum");
+            let problem_path = problems_dir.join("new/easy/two-sum");
+            let settings = get_settings().expect("settings should load");
+
+            let validator =
+                Validator::for_problem(&settings, &problem_path).expect("lookup should succeed");
+            assert!(validator.is_none());
+
+            fs::write(problem_path.join("validator.py"), "").expect("validator should be written");
+            fs::write(problem_path.join("validator.cpp"), "").expect("validator should be written");
+            let err = Validator::for_problem(&settings, &problem_path)
+                .err()
+                .expect("multiple validators should be rejected");
+            assert!(err
+                .to_string()
+                .contains("Found multiple validators: validator.cpp, validator.py"));
+        });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reports_reason_for_invalid_inputs() {
+        use std::path::PathBuf;
+
+        use tempfile::TempDir;
+
+        use super::{validate_generated_input, RunCommand};
+
+        let dir = TempDir::new().expect("tempdir should be created");
+        let valid_path = dir.path().join("valid.in");
+        let invalid_path = dir.path().join("invalid.in");
+        fs::write(&valid_path, "5\n").expect("input should be written");
+        fs::write(&invalid_path, "0\n").expect("input should be written");
+
+        let validator = Validator {
+            run_command: RunCommand::from_command(
+                PathBuf::new(),
+                PathBuf::new(),
+                vec![
+                    "sh".to_owned(),
+                    "-c".to_owned(),
+                    r#"read n; [ "$n" -ge 1 ] || { echo "n must be at least 1, got $n" >&2; exit 1; }"#
+                        .to_owned(),
+                ],
+            )
+            .expect("command should be valid"),
+        };
+
+        assert_eq!(
+            validator
+                .validate_input(&valid_path)
+                .expect("validator should run"),
+            None
+        );
+        assert_eq!(
+            validator
+                .validate_input(&invalid_path)
+                .expect("validator should run")
+                .as_deref(),
+            Some("n must be at least 1, got 0")
+        );
+
+        assert_eq!(
+            validate_generated_input(&validator, &valid_path).expect("validator should run"),
+            None
+        );
+        assert!(valid_path.exists());
+        assert!(validate_generated_input(&validator, &invalid_path)
+            .expect("validator should run")
+            .is_some());
+        assert!(!invalid_path.exists());
+    }
+}
@@ -0,0 +1,170 @@
+//! Verdicts for a solution run against a single test case.
+
+use std::fmt;
+
+use super::run::{LimitExceeded, RunResult};
+
+/// The outcome of running a solution on a test case.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Verdict {
+    Accepted,
+    WrongAnswer,
+    /// The output is almost right, but not formatted as expected. Only given
+    /// by custom checkers.
+    PresentationError,
+    TimeLimitExceeded,
+    MemoryLimitExceeded,
+    /// The solution crashed, either exiting with a non-zero exit code or
+    /// being killed by a signal.
+    RuntimeError {
+        exit_code: Option<u32>,
+        signal: Option<i32>,
+    },
+    /// The test case couldn't be judged, e.g. because its expected output is
+    /// missing or the checker failed.
+    JudgeError,
+}
+
+/// Short verdict codes in the order they are shown in summaries.
+pub const VERDICT_CODES: &[&str] = &["AC", "WA", "PE", "TLE", "MLE", "RE", "JE"];
+
+impl Verdict {
+    /// Get the verdict that a run has already earned before its output is
+    /// checked, i.e. if it exceeded a limit or crashed.
+    pub fn from_run_result(result: &RunResult) -> Option<Self> {
+        match result.limit_exceeded {
+            Some(LimitExceeded::Time) => return Some(Verdict::TimeLimitExceeded),
+            Some(LimitExceeded::Memory) => return Some(Verdict::MemoryLimitExceeded),
+            None => {}
+        }
+
+        if result.exit_status.success() {
+            None
+        } else {
+            Some(Verdict::RuntimeError {
+                exit_code: result.exit_status.code(),
+                signal: result.exit_status.signal(),
+            })
+        }
+    }
+
+    /// Short verdict code, e.g. `AC` or `TLE`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Verdict::Accepted => "AC",
+            Verdict::WrongAnswer => "WA",
+            Verdict::PresentationError => "PE",
+            Verdict::TimeLimitExceeded => "TLE",
+            Verdict::MemoryLimitExceeded => "MLE",
+            Verdict::RuntimeError { .. } => "RE",
+            Verdict::JudgeError => "JE",
+        }
+    }
+
+    pub fn is_accepted(&self) -> bool {
+        *self == Verdict::Accepted
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Accepted => write!(f, "Accepted"),
+            Verdict::WrongAnswer => write!(f, "Wrong answer"),
+            Verdict::PresentationError => write!(f, "Presentation error"),
+            Verdict::TimeLimitExceeded => write!(f, "Time limit exceeded"),
+            Verdict::MemoryLimitExceeded => write!(f, "Memory limit exceeded"),
+            Verdict::RuntimeError {
+                signal: Some(signal),
+                ..
+            } => write!(f, "Runtime error (signal {signal})"),
+            Verdict::RuntimeError {
+                exit_code: Some(code),
+                ..
+            } => write!(f, "Runtime error (exit code {code})"),
+            Verdict::RuntimeError { .. } => write!(f, "Runtime error"),
+            Verdict::JudgeError => write!(f, "Judge error"),
+        }
+    }
+}
+
+/// Count of each verdict over a set of test cases.
+#[derive(Debug, Default)]
+pub struct VerdictSummary {
+    counts: Vec<(&'static str, usize)>,
+}
+
+impl VerdictSummary {
+    pub fn add(&mut self, verdict: &Verdict) {
+        let code = verdict.code();
+        match self.counts.iter_mut().find(|(c, _)| *c == code) {
+            Some((_, count)) => *count += 1,
+            None => self.counts.push((code, 1)),
+        }
+    }
+
+    pub fn count(&self, code: &str) -> usize {
+        self.counts
+            .iter()
+            .find(|(c, _)| *c == code)
+            .map_or(0, |(_, count)| *count)
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.iter().map(|(_, count)| count).sum()
+    }
+}
+
+impl fmt::Display for VerdictSummary {
+    /// Formats the non-zero counts in a fixed order, e.g. `AC: 3, WA: 1, RE: 1`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = VERDICT_CODES
+            .iter()
+            .filter(|code| self.count(code) > 0)
+            .map(|code| format!("{code}: {}", self.count(code)))
+            .collect();
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Verdict, VerdictSummary};
+
+    #[test]
+    fn formats_runtime_errors_with_exit_code_or_signal() {
+        let exit = Verdict::RuntimeError {
+            exit_code: Some(3),
+            signal: None,
+        };
+        let signal = Verdict::RuntimeError {
+            exit_code: None,
+            signal: Some(11),
+        };
+
+        assert_eq!(exit.to_string(), "Runtime error (exit code 3)");
+        assert_eq!(signal.to_string(), "Runtime error (signal 11)");
+        assert_eq!(exit.code(), "RE");
+    }
+
+    #[test]
+    fn summary_lists_counts_in_fixed_order() {
+        let mut summary = VerdictSummary::default();
+        for verdict in [
+            Verdict::RuntimeError {
+                exit_code: Some(1),
+                signal: None,
+            },
+            Verdict::Accepted,
+            Verdict::WrongAnswer,
+            Verdict::Accepted,
+        ] {
+            summary.add(&verdict);
+        }
+
+        assert_eq!(summary.to_string(), "AC: 2, WA: 1, RE: 1");
+        assert_eq!(summary.count("AC"), 2);
+        assert_eq!(summary.count("TLE"), 0);
+        assert_eq!(summary.total(), 4);
+    }
+}
@@ -0,0 +1,186 @@
+//! Build judge-ready archives of problems.
+//!
+//! Each problem is stored in its own folder in the archive, containing the
+//! statement, the metadata (with the checker and limits), any checker and
+//! validator source files with the headers they include, and the test cases:
+//!
+//! ```text
+//! two-sum/
+//!     problem.md
+//!     problem.toml
+//!     checker.cpp
+//!     testlib.h
+//!     tests/
+//!         a.in
+//!         a.out
+//! ```
+
+use std::fs::{self, File};
+use std::io::{Seek, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::config::Settings;
+use crate::problem::check::SUPPORT_HEADERS;
+use crate::problem::checker::PYTHON_CHECKER_FILE;
+use crate::problem::metadata::{metadata_template, title_from_name, PROBLEM_METADATA_FILE};
+use crate::problem::run::find_file_langs;
+use crate::problem::sync_mappings::get_problem;
+use crate::util::{get_files_in_directory_recursive, get_project_root};
+
+/// Get the files of a problem to put in its archive, relative to the problem folder.
+fn problem_files(settings: &Settings, problem_path: &Path) -> Result<Vec<String>> {
+    let mut files = vec!["problem.md".to_owned()];
+
+    if problem_path.join(PYTHON_CHECKER_FILE).exists() {
+        files.push(PYTHON_CHECKER_FILE.to_owned());
+    }
+    for stem in ["checker", "validator"] {
+        for lang in find_file_langs(settings, problem_path, stem)? {
+            let file = format!("{stem}.{lang}");
+            if !files.contains(&file) {
+                files.push(file);
+            }
+        }
+    }
+    for header in SUPPORT_HEADERS {
+        if problem_path.join(header).exists() {
+            files.push((*header).to_owned());
+        }
+    }
+
+    for file in get_files_in_directory_recursive(problem_path.join("tests"))? {
+        if file.ends_with(".in") || file.ends_with(".out") {
+            files.push(format!("tests/{file}"));
+        }
+    }
+
+    Ok(files)
+}
+
+/// Add a problem to the archive, in a folder named after the problem.
+fn add_problem<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    settings: &Settings,
+    problem_path: &Path,
+    problem_name: &str,
+) -> Result<()> {
+    let options = SimpleFileOptions::default();
+
+    for file in problem_files(settings, problem_path)? {
+        let path = problem_path.join(&file);
+        let contents = fs::read(&path).context(format!("Failed to read {}", path.display()))?;
+        zip.start_file(format!("{problem_name}/{file}"), options)?;
+        zip.write_all(&contents)?;
+    }
+
+    // The judge needs the limits and checker, so fall back to the defaults
+    let metadata_path = problem_path.join(PROBLEM_METADATA_FILE);
+    let metadata = if metadata_path.exists() {
+        fs::read_to_string(&metadata_path)
+            .context(format!("Failed to read {}", metadata_path.display()))?
+    } else {
+        eprintln!(
+            "Warning: '{problem_name}' has no {PROBLEM_METADATA_FILE}, so default metadata will be published"
+        );
+        metadata_template(&title_from_name(problem_name))
+    };
+    zip.start_file(format!("{problem_name}/{PROBLEM_METADATA_FILE}"), options)?;
+    zip.write_all(metadata.as_bytes())?;
+
+    Ok(())
+}
+
+/// Build an archive of the given problems at the given path.
+pub fn build_archive(
+    settings: &Settings,
+    problems_dir: &Path,
+    problem_names: &[String],
+    archive_path: &Path,
+) -> Result<()> {
+    let project_root = get_project_root()?;
+
+    if let Some(parent) = archive_path.parent() {
+        fs::create_dir_all(parent).context(format!("Failed to create {}", parent.display()))?;
+    }
+    let file = File::create(archive_path)
+        .context(format!("Failed to create {}", archive_path.display()))?;
+    let mut zip = ZipWriter::new(file);
+
+    for problem_name in problem_names {
+        let problem_path = project_root.join(get_problem(problems_dir, problem_name)?);
+        add_problem(&mut zip, settings, &problem_path, problem_name)
+            .context(format!("Failed to add '{problem_name}' to the archive"))?;
+    }
+
+    zip.finish()
+        .context(format!("Failed to write {}", archive_path.display()))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, File};
+
+    use zip::ZipArchive;
+
+    use super::build_archive;
+    use crate::config::get_settings;
+    use crate::problem::sync_mappings::sync_mappings;
+    use crate::problem::test_support::{create_problem_dir, with_test_project};
+
+    #[test]
+    fn archives_statement_metadata_checker_and_tests() {
+        with_test_project(|problems_dir| {
+            create_problem_dir(problems_dir, "new", "easy", "two-sum");
+            let problem_path = problems_dir.join("new/easy/two-sum");
+            fs::write(problem_path.join("checker.cpp"), "").expect("checker should be written");
+            fs::write(problem_path.join("checker.out"), "").expect("binary should be written");
+            fs::write(problem_path.join("testlib.h"), "").expect("header should be written");
+            fs::write(problem_path.join("tests/a.in"), "1 2\n").expect("test should be written");
+            fs::write(problem_path.join("tests/a.out"), "3\n").expect("test should be written");
+            fs::write(problem_path.join("solutions/solution.cpp"), "")
+                .expect("solution should be written");
+            sync_mappings(problems_dir).expect("mappings should sync");
+            let settings = get_settings().expect("settings should load");
+
+            let archive_path = problems_dir.join("../dist/two-sum.zip");
+            build_archive(
+                &settings,
+                problems_dir,
+                &["two-sum".to_owned()],
+                &archive_path,
+            )
+            .expect("archive should be built");
+
+            let mut archive =
+                ZipArchive::new(File::open(&archive_path).expect("archive should open"))
+                    .expect("archive should be valid");
+            let mut names: Vec<&str> = archive.file_names().collect();
+            names.sort();
+            assert_eq!(
+                names,
+                vec![
+                    "two-sum/checker.cpp",
+                    "two-sum/problem.md",
+                    "two-sum/problem.toml",
+                    "two-sum/testlib.h",
+                    "two-sum/tests/a.in",
+                    "two-sum/tests/a.out",
+                ]
+            );
+
+            let metadata = std::io::read_to_string(
+                archive
+                    .by_name("two-sum/problem.toml")
+                    .expect("metadata should be archived"),
+            )
+            .expect("metadata should be readable");
+            assert!(metadata.contains("title = \"Two Sum\""));
+        });
+    }
+}
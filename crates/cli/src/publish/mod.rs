@@ -1 +1,113 @@
+//! Package problems and competitions and publish them to an online judge.
 
+use std::env;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use crate::comp::read_competitions;
+use crate::config::Settings;
+use crate::problem::check::{check, CheckOptions};
+use crate::util::get_project_root;
+
+pub mod archive;
+pub mod upload;
+
+/// Folder in the project root that archives are written to by default.
+pub const DIST_DIR: &str = "dist";
+
+/// What to publish.
+pub enum PublishTarget {
+    Problem(String),
+    Competition(String),
+}
+
+impl PublishTarget {
+    fn name(&self) -> &str {
+        match self {
+            PublishTarget::Problem(name) | PublishTarget::Competition(name) => name,
+        }
+    }
+}
+
+/// Arguments for the publish command.
+pub struct PublishArgs<'a> {
+    pub problems_dir: &'a Path,
+    pub target: PublishTarget,
+    /// Where to write the archive (default: `dist/<name>.zip`).
+    pub output: Option<PathBuf>,
+    /// Overrides the endpoint from the settings.
+    pub endpoint: Option<String>,
+    /// Only build the archive, without uploading it.
+    pub no_upload: bool,
+}
+
+/// Check, package, and upload a problem or all problems in a competition.
+/// Returns the path to the archive.
+pub fn publish(settings: &Settings, publish_args: &PublishArgs) -> Result<PathBuf> {
+    let PublishArgs {
+        problems_dir,
+        target,
+        output,
+        endpoint,
+        no_upload,
+    } = publish_args;
+
+    let problem_names = match target {
+        PublishTarget::Problem(name) => vec![name.to_owned()],
+        PublishTarget::Competition(comp_name) => {
+            let competitions = read_competitions(problems_dir)?;
+            let comp = competitions
+                .get(comp_name)
+                .context(format!("Competition '{comp_name}' not found"))?;
+            if comp.problems.is_empty() {
+                bail!("Competition '{comp_name}' has no problems to publish");
+            }
+            comp.problem_names().map(str::to_owned).collect()
+        }
+    };
+
+    for problem_name in &problem_names {
+        let summary = check(
+            settings,
+            problems_dir.to_path_buf(),
+            problem_name,
+            CheckOptions::default(),
+        )?;
+        if summary.errors() > 0 {
+            bail!(
+                "Problem '{problem_name}' has {} invalid tests and {} invalid files. Fix them before publishing",
+                summary.invalid_tests,
+                summary.invalid_files
+            );
+        }
+    }
+
+    let name = target.name();
+    let archive_path = match output {
+        Some(path) => path.to_owned(),
+        None => get_project_root()?
+            .join(DIST_DIR)
+            .join(format!("{name}.zip")),
+    };
+    archive::build_archive(settings, problems_dir, &problem_names, &archive_path)?;
+    eprintln!("Packaged '{name}' into {}", archive_path.display());
+
+    if *no_upload {
+        return Ok(archive_path);
+    }
+    let Some(endpoint) = endpoint.as_ref().or(settings.publish.endpoint.as_ref()) else {
+        eprintln!("No publish endpoint is set in the settings file, skipping upload");
+        return Ok(archive_path);
+    };
+
+    let token = settings
+        .publish
+        .token_env
+        .as_ref()
+        .and_then(|var| env::var(var).ok());
+    upload::upload(endpoint, token.as_deref(), name, &archive_path)?;
+    eprintln!("Published '{name}' to {endpoint}");
+
+    Ok(archive_path)
+}
@@ -0,0 +1,132 @@
+//! Upload archives to an online judge over HTTP.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+/// Header with the name of the problem or competition being published.
+pub const NAME_HEADER: &str = "X-Aucpl-Name";
+
+/// Upload an archive to the endpoint with a POST request. The token, if any, is
+/// sent as a bearer token.
+pub fn upload(endpoint: &str, token: Option<&str>, name: &str, archive_path: &Path) -> Result<()> {
+    let contents =
+        fs::read(archive_path).context(format!("Failed to read {}", archive_path.display()))?;
+
+    let mut request = ureq::post(endpoint)
+        .set("Content-Type", "application/zip")
+        .set(NAME_HEADER, name);
+    if let Some(token) = token {
+        request = request.set("Authorization", &format!("Bearer {token}"));
+    }
+
+    match request.send_bytes(&contents) {
+        Ok(_) => Ok(()),
+        Err(ureq::Error::Status(code, response)) => {
+            let body = response.into_string().unwrap_or_default();
+            bail!(
+                "The server rejected the upload with status {code}: {}",
+                body.trim()
+            )
+        }
+        Err(err) => Err(err).context(format!("Failed to upload to {endpoint}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    use tempfile::TempDir;
+
+    use super::upload;
+
+    /// A request received by the mock server.
+    struct Request {
+        head: String,
+        body: Vec<u8>,
+    }
+
+    /// Start a server that accepts a single request and replies with the given
+    /// status line. Returns the endpoint URL and a handle to get the request.
+    fn mock_server(status: &'static str) -> (String, JoinHandle<Request>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("listener should bind");
+        let endpoint = format!(
+            "http://{}/upload",
+            listener.local_addr().expect("address should be known")
+        );
+
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().expect("connection should be accepted");
+            let mut reader = BufReader::new(stream);
+
+            let mut head = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).expect("request should be read");
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+                head.push_str(&line);
+            }
+            let content_length = head
+                .lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("content-length")
+                        .then(|| value.trim().parse::<usize>().ok())?
+                })
+                .unwrap_or_default();
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).expect("body should be read");
+
+            let response = format!("HTTP/1.1 {status}\r\nContent-Length: 6\r\n\r\nstatus");
+            reader
+                .get_mut()
+                .write_all(response.as_bytes())
+                .expect("response should be written");
+
+            Request { head, body }
+        });
+
+        (endpoint, handle)
+    }
+
+    #[test]
+    fn posts_archive_with_name_and_token() {
+        let dir = TempDir::new().expect("tempdir should be created");
+        let archive_path = dir.path().join("two-sum.zip");
+        fs::write(&archive_path, b"archive contents").expect("archive should be written");
+
+        let (endpoint, server) = mock_server("200 OK");
+        upload(&endpoint, Some("secret"), "two-sum", &archive_path).expect("upload should succeed");
+
+        let request = server.join().expect("server should not panic");
+        assert!(request.head.starts_with("POST /upload HTTP/1.1\r\n"));
+        assert!(request.head.contains("X-Aucpl-Name: two-sum\r\n"));
+        assert!(request.head.contains("Authorization: Bearer secret\r\n"));
+        assert!(request.head.contains("Content-Type: application/zip\r\n"));
+        assert_eq!(request.body, b"archive contents");
+    }
+
+    #[test]
+    fn reports_rejected_uploads() {
+        let dir = TempDir::new().expect("tempdir should be created");
+        let archive_path = dir.path().join("two-sum.zip");
+        fs::write(&archive_path, b"archive contents").expect("archive should be written");
+
+        let (endpoint, server) = mock_server("403 Forbidden");
+        let err = upload(&endpoint, None, "two-sum", &archive_path)
+            .expect_err("upload should be rejected");
+        server.join().expect("server should not panic");
+
+        assert_eq!(
+            err.to_string(),
+            "The server rejected the upload with status 403: status"
+        );
+    }
+}
@@ -0,0 +1,174 @@
+//! Reads and writes of the JSON files that keep track of the project, such as
+//! `competitions.json` and `problem-mappings.json`.
+//!
+//! Files are written to a temporary file in the same folder that is then
+//! renamed over the original, so an interrupted write never leaves an empty or
+//! half-written file, and the previous version is kept as `<file>.bak`. A
+//! change that reads a file and writes it back should hold the file's
+//! [`StoreLock`] throughout, so that two `aucpl` processes running at the same
+//! time can't overwrite each other's changes.
+
+use std::ffi::OsString;
+use std::fs::{self, File, TryLockError};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Get the path of a file that sits next to `path`, e.g. `<path>.bak`.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// An advisory lock on a store file, held on `<file>.lock` until it is dropped.
+pub struct StoreLock {
+    _file: File,
+}
+
+impl StoreLock {
+    /// Lock the given file, waiting for any other process that holds the lock.
+    pub fn acquire(path: &Path) -> Result<Self> {
+        let lock_path = with_suffix(path, ".lock");
+        let file = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .context(format!("Failed to open {}", lock_path.display()))?;
+
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                eprintln!(
+                    "Waiting for another aucpl process to finish with {}...",
+                    path.display()
+                );
+                file.lock()
+                    .context(format!("Failed to lock {}", path.display()))?;
+            }
+            Err(TryLockError::Error(err)) => {
+                return Err(err).context(format!("Failed to lock {}", path.display()));
+            }
+        }
+
+        Ok(Self { _file: file })
+    }
+}
+
+/// Read a JSON file, or `None` if it does not exist.
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>> {
+    if !fs::exists(path)? {
+        return Ok(None);
+    }
+
+    let contents =
+        fs::read_to_string(path).context(format!("Failed to read {}", path.display()))?;
+    let value =
+        serde_json::from_str(&contents).context(format!("Failed to parse {}", path.display()))?;
+    Ok(Some(value))
+}
+
+fn write_temp_file(path: &Path, contents: &[u8]) -> Result<()> {
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(&file);
+    writer.write_all(contents)?;
+    writer.flush()?;
+    drop(writer);
+    file.sync_all()?;
+
+    Ok(())
+}
+
+/// Replace a JSON file atomically, keeping the previous version as `<file>.bak`.
+/// Nothing is written if the file already has the same contents, so that the
+/// backup is always an earlier version.
+pub fn write_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<()> {
+    let contents = serde_json::to_vec_pretty(value)
+        .context(format!("Failed to serialize {}", path.display()))?;
+    if fs::exists(path)? && fs::read(path)? == contents {
+        return Ok(());
+    }
+
+    let temp_path = with_suffix(path, &format!(".{}.tmp", Uuid::new_v4()));
+    if let Err(err) = write_temp_file(&temp_path, &contents) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(err).context(format!("Failed to write {}", path.display()));
+    }
+
+    if fs::exists(path)? {
+        let backup_path = with_suffix(path, ".bak");
+        fs::copy(path, &backup_path).context(format!("Failed to back up {}", path.display()))?;
+    }
+
+    fs::rename(&temp_path, path).context(format!("Failed to replace {}", path.display()))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, File, TryLockError};
+
+    use serde_json::{json, Value};
+    use tempfile::TempDir;
+
+    use super::{read_json, write_json, StoreLock};
+
+    #[test]
+    fn replaces_file_and_keeps_backup() {
+        let dir = TempDir::new().expect("tempdir should be created");
+        let path = dir.path().join("competitions.json");
+
+        assert_eq!(
+            read_json::<Value>(&path).expect("missing file should be read"),
+            None
+        );
+
+        write_json(&path, &json!({ "version": 1 })).expect("file should be written");
+        assert!(!dir.path().join("competitions.json.bak").exists());
+        write_json(&path, &json!({ "version": 2 })).expect("file should be replaced");
+        write_json(&path, &json!({ "version": 2 })).expect("unchanged file should be kept");
+
+        assert_eq!(
+            read_json::<Value>(&path).expect("file should be read"),
+            Some(json!({ "version": 2 }))
+        );
+        assert_eq!(
+            read_json::<Value>(&dir.path().join("competitions.json.bak"))
+                .expect("backup should be read"),
+            Some(json!({ "version": 1 }))
+        );
+
+        let mut names: Vec<_> = fs::read_dir(dir.path())
+            .expect("dir should be readable")
+            .map(|entry| {
+                entry
+                    .expect("entry should be readable")
+                    .file_name()
+                    .into_string()
+                    .expect("name should be UTF-8")
+            })
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["competitions.json", "competitions.json.bak"]);
+    }
+
+    #[test]
+    fn lock_is_held_until_dropped() {
+        let dir = TempDir::new().expect("tempdir should be created");
+        let path = dir.path().join("problem-mappings.json");
+
+        let lock = StoreLock::acquire(&path).expect("lock should be taken");
+        let other =
+            File::open(dir.path().join("problem-mappings.json.lock")).expect("lock file exists");
+        assert!(matches!(other.try_lock(), Err(TryLockError::WouldBlock)));
+
+        drop(lock);
+        other.try_lock().expect("lock should be released");
+    }
+}
@@ -1 +1,776 @@
+//! Two-way sync of the local problem set with a remote problem store.
+//!
+//! Problems are compared by a hash of their location and the contents of every
+//! file in their folder, apart from build artefacts and the local record of
+//! generating the outputs. The hashes at the last sync are kept in
+//! `sync-state.json`, which tells apart a problem that was added on one side
+//! from one that was removed on the other, and a change on one side from a
+//! conflicting change on both.
 
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{Cursor, Write};
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use walkdir::{DirEntry, WalkDir};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::paths::to_unix_path;
+use crate::problem::category::validate_category;
+use crate::problem::check::is_artefact;
+use crate::problem::solve::SOLVE_STAMP_FILE;
+use crate::problem::sync_mappings::{get_all_problem_names, get_problem, sync_mappings};
+use crate::problem::{problem_location_from_path, remove_dir_if_empty};
+use crate::store::{read_json, write_json};
+use crate::util::get_project_root;
+
+pub mod remote;
+
+use remote::{ProblemState, Remote};
+
+pub const SYNC_STATE_FILE: &str = "sync-state.json";
+
+/// Which way changes are applied.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Direction {
+    /// Apply remote changes to the local problem set.
+    Pull,
+    /// Apply local changes to the remote problem store.
+    Push,
+}
+
+/// How a problem differs between the local problem set and the remote store.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ChangeKind {
+    AddedLocally,
+    AddedRemotely,
+    ChangedLocally,
+    ChangedRemotely,
+    RemovedLocally,
+    RemovedRemotely,
+    /// Changed on both sides since the last sync.
+    Conflict,
+}
+
+impl ChangeKind {
+    /// Classify a problem from its hash locally, remotely, and at the last
+    /// sync. Returns `None` if the problem is in sync.
+    pub fn classify(local: Option<&str>, remote: Option<&str>, base: Option<&str>) -> Option<Self> {
+        match (local, remote) {
+            (None, None) => None,
+            (Some(local), Some(remote)) if local == remote => None,
+            (Some(local), Some(remote)) => Some(if base == Some(remote) {
+                ChangeKind::ChangedLocally
+            } else if base == Some(local) {
+                ChangeKind::ChangedRemotely
+            } else {
+                ChangeKind::Conflict
+            }),
+            (Some(local), None) => Some(match base {
+                None => ChangeKind::AddedLocally,
+                Some(base) if base == local => ChangeKind::RemovedRemotely,
+                Some(_) => ChangeKind::Conflict,
+            }),
+            (None, Some(remote)) => Some(match base {
+                None => ChangeKind::AddedRemotely,
+                Some(base) if base == remote => ChangeKind::RemovedLocally,
+                Some(_) => ChangeKind::Conflict,
+            }),
+        }
+    }
+
+    /// The direction that applies the change, or `None` for conflicts.
+    pub fn direction(&self) -> Option<Direction> {
+        match self {
+            ChangeKind::AddedLocally | ChangeKind::ChangedLocally | ChangeKind::RemovedLocally => {
+                Some(Direction::Push)
+            }
+            ChangeKind::AddedRemotely
+            | ChangeKind::ChangedRemotely
+            | ChangeKind::RemovedRemotely => Some(Direction::Pull),
+            ChangeKind::Conflict => None,
+        }
+    }
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            ChangeKind::AddedLocally => "added locally",
+            ChangeKind::AddedRemotely => "added remotely",
+            ChangeKind::ChangedLocally => "changed locally",
+            ChangeKind::ChangedRemotely => "changed remotely",
+            ChangeKind::RemovedLocally => "removed locally",
+            ChangeKind::RemovedRemotely => "removed remotely",
+            ChangeKind::Conflict => "changed on both sides",
+        };
+        write!(f, "{description}")
+    }
+}
+
+/// A problem that differs between the local problem set and the remote store.
+#[derive(Debug)]
+pub struct Change {
+    pub name: String,
+    pub kind: ChangeKind,
+    pub local: Option<ProblemState>,
+    pub remote: Option<ProblemState>,
+}
+
+/// Arguments for the sync command.
+pub struct SyncArgs {
+    /// Which way to apply changes, or `None` to only show them.
+    pub direction: Option<Direction>,
+    /// Only sync these problems, or all problems if empty.
+    pub problems: Vec<String>,
+    /// Also apply conflicting changes, overwriting the other side.
+    pub force: bool,
+}
+
+/// Hash the location of a problem and the paths and contents of its files.
+pub fn problem_hash(problem_path: &Path, location: &str) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(location.as_bytes());
+    hasher.update([0]);
+
+    for (path, contents) in problem_files(problem_path)? {
+        hasher.update(path.as_bytes());
+        hasher.update([0]);
+        hasher.update((contents.len() as u64).to_le_bytes());
+        hasher.update(&contents);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+/// Walk the files and folders of a problem folder that are synced, leaving
+/// out build artefacts and the solve stamp, which are local to a checkout.
+fn walk_problem(problem_path: &Path) -> impl Iterator<Item = walkdir::Result<DirEntry>> + '_ {
+    WalkDir::new(problem_path)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(move |entry| {
+            let relative_path = entry
+                .path()
+                .strip_prefix(problem_path)
+                .map(to_unix_path)
+                .unwrap_or_default();
+            relative_path != SOLVE_STAMP_FILE && !is_artefact(&relative_path)
+        })
+}
+
+/// Read the synced files of a problem folder, keyed by their path in the folder.
+fn problem_files(problem_path: &Path) -> Result<BTreeMap<String, Vec<u8>>> {
+    let mut files = BTreeMap::new();
+    for entry in walk_problem(problem_path) {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative_path = to_unix_path(entry.path().strip_prefix(problem_path)?);
+        let contents =
+            fs::read(entry.path()).context(format!("Failed to read {}", entry.path().display()))?;
+        files.insert(relative_path, contents);
+    }
+    Ok(files)
+}
+
+/// Build a zip archive of a problem folder.
+fn archive_problem(problem_path: &Path) -> Result<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    // Keep empty folders such as `solutions` so the pulled problem is complete
+    for entry in walk_problem(problem_path) {
+        let entry = entry?;
+        if entry.file_type().is_dir() {
+            let relative_path = to_unix_path(entry.path().strip_prefix(problem_path)?);
+            zip.add_directory(relative_path, SimpleFileOptions::default())?;
+        }
+    }
+    for (path, contents) in problem_files(problem_path)? {
+        zip.start_file(path, SimpleFileOptions::default())?;
+        zip.write_all(&contents)?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+/// Extract a zip archive of a problem into its folder.
+fn extract_problem(archive: &[u8], problem_path: &Path) -> Result<()> {
+    let mut zip = ZipArchive::new(Cursor::new(archive)).context("Invalid problem archive")?;
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i)?;
+        let Some(relative_path) = entry.enclosed_name() else {
+            bail!("The archive contains an unsafe path '{}'", entry.name());
+        };
+        let path = problem_path.join(relative_path);
+        if entry.is_dir() {
+            fs::create_dir_all(&path)?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file =
+            File::create(&path).context(format!("Failed to create {}", path.display()))?;
+        std::io::copy(&mut entry, &mut file)?;
+    }
+    Ok(())
+}
+
+/// Replace a local problem with one pulled from the remote store. The archive
+/// is extracted into a temporary folder in the problems folder first and then
+/// swapped in, so the local problem is kept if extracting or moving fails.
+fn replace_problem(
+    problems_dir: &Path,
+    archive: &[u8],
+    old_path: Option<&Path>,
+    new_path: &Path,
+) -> Result<()> {
+    let staging_dir = problems_dir.join(format!(".sync-{}.tmp", Uuid::new_v4()));
+    let extracted_path = staging_dir.join("problem");
+    let extracted = fs::create_dir_all(&extracted_path)
+        .map_err(anyhow::Error::from)
+        .and_then(|()| extract_problem(archive, &extracted_path));
+    if let Err(err) = extracted {
+        let _ = fs::remove_dir_all(&staging_dir);
+        return Err(err).context("Failed to extract the pulled problem");
+    }
+
+    // Move the local problem aside rather than removing it, so it can be put back
+    let previous_path = staging_dir.join("previous");
+    if let Some(old_path) = old_path {
+        if let Err(err) = fs::rename(old_path, &previous_path) {
+            let _ = fs::remove_dir_all(&staging_dir);
+            return Err(err).context(format!("Failed to move {}", old_path.display()));
+        }
+    }
+
+    let moved = match new_path.parent() {
+        Some(category_dir) => fs::create_dir_all(category_dir),
+        None => Ok(()),
+    }
+    .and_then(|()| fs::rename(&extracted_path, new_path));
+    if let Err(err) = moved {
+        if let Some(old_path) = old_path {
+            fs::rename(&previous_path, old_path).context(format!(
+                "Failed to restore {}, it was kept in {}",
+                old_path.display(),
+                previous_path.display()
+            ))?;
+        }
+        let _ = fs::remove_dir_all(&staging_dir);
+        return Err(err).context(format!("Failed to create {}", new_path.display()));
+    }
+
+    if let Some(category_dir) = old_path.and_then(Path::parent) {
+        remove_dir_if_empty(category_dir)?;
+    }
+    fs::remove_dir_all(&staging_dir)?;
+    Ok(())
+}
+
+/// Get the location and hash of every local problem.
+pub fn local_problems(problems_dir: &Path) -> Result<BTreeMap<String, ProblemState>> {
+    let project_root = get_project_root()?;
+    let mut problems = BTreeMap::new();
+    for name in get_all_problem_names(problems_dir)? {
+        let problem_path = project_root.join(get_problem(problems_dir, &name)?);
+        let location = problem_location_from_path(problems_dir, &problem_path)?;
+        let path = format!(
+            "{}/{}/{}",
+            location.status, location.category, location.problem_name
+        );
+        let hash = problem_hash(&problem_path, &path)?;
+        problems.insert(name.clone(), ProblemState { name, path, hash });
+    }
+    Ok(problems)
+}
+
+/// Read the hashes of the problems at the last sync.
+fn read_sync_state(problems_dir: &Path) -> Result<BTreeMap<String, String>> {
+    Ok(read_json(&problems_dir.join(SYNC_STATE_FILE))?.unwrap_or_default())
+}
+
+fn write_sync_state(problems_dir: &Path, state: &BTreeMap<String, String>) -> Result<()> {
+    write_json(&problems_dir.join(SYNC_STATE_FILE), state)
+}
+
+/// Find the problems that differ between the local problem set and the remote
+/// store, given the hashes at the last sync.
+pub fn diff(
+    local: &BTreeMap<String, ProblemState>,
+    remote: &BTreeMap<String, ProblemState>,
+    base: &BTreeMap<String, String>,
+) -> Vec<Change> {
+    let names: BTreeSet<&String> = local.keys().chain(remote.keys()).collect();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let local = local.get(name);
+            let remote = remote.get(name);
+            let kind = ChangeKind::classify(
+                local.map(|p| p.hash.as_str()),
+                remote.map(|p| p.hash.as_str()),
+                base.get(name).map(|h| h.as_str()),
+            )?;
+            Some(Change {
+                name: name.to_owned(),
+                kind,
+                local: local.cloned(),
+                remote: remote.cloned(),
+            })
+        })
+        .collect()
+}
+
+fn print_changes(changes: &[Change]) {
+    if changes.is_empty() {
+        eprintln!("Local problems are in sync with the remote");
+        return;
+    }
+
+    let width = changes.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    for change in changes {
+        let action = match change.kind.direction() {
+            Some(Direction::Pull) => "pull",
+            Some(Direction::Push) => "push",
+            None => "conflict",
+        };
+        let locations = match (&change.local, &change.remote) {
+            (Some(local), Some(remote)) if local.path != remote.path => {
+                format!(" ({} locally, {} remotely)", local.path, remote.path)
+            }
+            (Some(problem), _) | (_, Some(problem)) => format!(" ({})", problem.path),
+            (None, None) => String::new(),
+        };
+        println!(
+            "{action:<8}  {:<width$}  {}{locations}",
+            change.name, change.kind
+        );
+    }
+}
+
+/// Check that a remote problem's location is a valid problem folder, so that
+/// pulling it cannot write outside the problems folder.
+fn validate_remote_path(problem: &ProblemState) -> Result<()> {
+    let components: Vec<&str> = problem.path.split('/').collect();
+    let valid = matches!(
+        components.as_slice(),
+        [status, category, name]
+            if matches!(*status, "new" | "archive")
+                && validate_category(category).is_ok()
+                && *name == problem.name
+    );
+    if !valid {
+        bail!(
+            "The remote problem store has an invalid location '{}' for '{}'",
+            problem.path,
+            problem.name
+        );
+    }
+    Ok(())
+}
+
+/// Apply a change in the given direction, returning the problem's hash after
+/// the change, or `None` if it was removed.
+fn apply_change(
+    problems_dir: &Path,
+    remote: &Remote,
+    change: &Change,
+    direction: Direction,
+) -> Result<Option<String>> {
+    match direction {
+        Direction::Pull => {
+            // Download before changing anything, so a failure keeps the local problem
+            let archive = match &change.remote {
+                Some(problem) => {
+                    validate_remote_path(problem)?;
+                    Some(remote.download(&change.name)?)
+                }
+                None => None,
+            };
+
+            let local_path = match &change.local {
+                Some(_) => Some(get_project_root()?.join(get_problem(problems_dir, &change.name)?)),
+                None => None,
+            };
+
+            match (&change.remote, archive) {
+                (Some(problem), Some(archive)) => {
+                    replace_problem(
+                        problems_dir,
+                        &archive,
+                        local_path.as_deref(),
+                        &problems_dir.join(&problem.path),
+                    )?;
+                    eprintln!("Pulled '{}'", change.name);
+                    Ok(Some(problem.hash.clone()))
+                }
+                _ => {
+                    if let Some(problem_path) = &local_path {
+                        fs::remove_dir_all(problem_path)?;
+                        if let Some(category_dir) = problem_path.parent() {
+                            remove_dir_if_empty(category_dir)?;
+                        }
+                    }
+                    eprintln!("Removed '{}' locally", change.name);
+                    Ok(None)
+                }
+            }
+        }
+        Direction::Push => {
+            let project_root = get_project_root()?;
+            match &change.local {
+                Some(problem) => {
+                    let problem_path = project_root.join(get_problem(problems_dir, &change.name)?);
+                    remote.upload(problem, &archive_problem(&problem_path)?)?;
+                    eprintln!("Pushed '{}'", change.name);
+                    Ok(Some(problem.hash.clone()))
+                }
+                None => {
+                    remote.delete(&change.name)?;
+                    eprintln!("Removed '{}' remotely", change.name);
+                    Ok(None)
+                }
+            }
+        }
+    }
+}
+
+/// Show the differences with the remote problem store, and pull or push them.
+pub fn sync(problems_dir: &Path, remote: &Remote, sync_args: &SyncArgs) -> Result<()> {
+    let SyncArgs {
+        direction,
+        problems,
+        force,
+    } = sync_args;
+
+    let local = local_problems(problems_dir)?;
+    let remote_problems: BTreeMap<String, ProblemState> = remote
+        .list()?
+        .into_iter()
+        .map(|problem| (problem.name.clone(), problem))
+        .collect();
+    let mut state = read_sync_state(problems_dir)?;
+
+    for name in problems {
+        if !local.contains_key(name) && !remote_problems.contains_key(name) {
+            bail!("Problem '{name}' does not exist locally or remotely");
+        }
+    }
+
+    // Problems that are the same on both sides are in sync, whatever the state says
+    for (name, problem) in &local {
+        if remote_problems.get(name) == Some(problem) {
+            state.insert(name.to_owned(), problem.hash.clone());
+        }
+    }
+    state.retain(|name, _| local.contains_key(name) || remote_problems.contains_key(name));
+
+    let changes: Vec<Change> = diff(&local, &remote_problems, &state)
+        .into_iter()
+        .filter(|change| problems.is_empty() || problems.contains(&change.name))
+        .collect();
+    print_changes(&changes);
+
+    if let Some(direction) = direction {
+        let mut skipped_conflicts = 0;
+        for change in &changes {
+            match change.kind.direction() {
+                Some(change_direction) if change_direction == *direction => {}
+                None if *force => {}
+                None => {
+                    skipped_conflicts += 1;
+                    continue;
+                }
+                Some(_) => continue,
+            }
+
+            match apply_change(problems_dir, remote, change, *direction)? {
+                Some(hash) => state.insert(change.name.clone(), hash),
+                None => state.remove(&change.name),
+            };
+            // Keep the state up to date in case a later change fails
+            write_sync_state(problems_dir, &state)?;
+        }
+
+        if *direction == Direction::Pull {
+            sync_mappings(problems_dir)?;
+        }
+        if skipped_conflicts > 0 {
+            eprintln!(
+                "Skipped {skipped_conflicts} conflicting problems. Use --force to overwrite the other side"
+            );
+        }
+    }
+
+    write_sync_state(problems_dir, &state)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::io::{BufRead, BufReader, Cursor, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    use zip::ZipArchive;
+
+    use super::remote::{ProblemState, Remote, HASH_HEADER, PATH_HEADER};
+    use super::{archive_problem, local_problems, sync, ChangeKind, Direction, SyncArgs};
+    use crate::problem::solve::SOLVE_STAMP_FILE;
+    use crate::problem::sync_mappings::sync_mappings;
+    use crate::problem::test_support::{create_problem_dir, with_test_project};
+
+    /// Problems in the mock store, with their archive.
+    type Store = Arc<Mutex<HashMap<String, (ProblemState, Vec<u8>)>>>;
+
+    /// Start a mock problem store. Returns its endpoint and its problems.
+    fn mock_store() -> (String, Store) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("listener should bind");
+        let endpoint = format!(
+            "http://{}/api",
+            listener.local_addr().expect("address should be known")
+        );
+        let store: Store = Arc::default();
+
+        let server_store = Arc::clone(&store);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut reader = BufReader::new(stream.expect("connection should be accepted"));
+
+                let mut head = String::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).expect("request should be read");
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                    head.push_str(&line);
+                }
+                let header = |name: &str| {
+                    head.lines().find_map(|line| {
+                        let (key, value) = line.split_once(':')?;
+                        key.eq_ignore_ascii_case(name)
+                            .then(|| value.trim().to_owned())
+                    })
+                };
+                let content_length = header("content-length")
+                    .and_then(|len| len.parse().ok())
+                    .unwrap_or_default();
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).expect("body should be read");
+
+                let mut parts = head.split_whitespace();
+                let method = parts.next().unwrap_or_default();
+                let path = parts.next().unwrap_or_default();
+                let name = path.strip_prefix("/api/problems/").unwrap_or_default();
+
+                let mut store = server_store.lock().expect("store should not be poisoned");
+                let (status, response) = match (method, path) {
+                    ("GET", "/api/problems") => {
+                        let mut problems: Vec<&ProblemState> =
+                            store.values().map(|(problem, _)| problem).collect();
+                        problems.sort_by(|a, b| a.name.cmp(&b.name));
+                        ("200 OK", serde_json::to_vec(&problems).unwrap())
+                    }
+                    ("GET", _) => match store.get(name) {
+                        Some((_, archive)) => ("200 OK", archive.clone()),
+                        None => ("404 Not Found", b"not found".to_vec()),
+                    },
+                    ("PUT", _) => {
+                        let problem = ProblemState {
+                            name: name.to_owned(),
+                            path: header(PATH_HEADER).unwrap_or_default(),
+                            hash: header(HASH_HEADER).unwrap_or_default(),
+                        };
+                        store.insert(name.to_owned(), (problem, body));
+                        ("200 OK", Vec::new())
+                    }
+                    ("DELETE", _) => {
+                        store.remove(name);
+                        ("200 OK", Vec::new())
+                    }
+                    _ => ("400 Bad Request", Vec::new()),
+                };
+                drop(store);
+
+                let stream = reader.get_mut();
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    response.len()
+                )
+                .expect("response should be written");
+                stream
+                    .write_all(&response)
+                    .expect("response should be written");
+            }
+        });
+
+        (endpoint, store)
+    }
+
+    fn sync_args(direction: Option<Direction>) -> SyncArgs {
+        SyncArgs {
+            direction,
+            problems: Vec::new(),
+            force: false,
+        }
+    }
+
+    #[test]
+    fn classifies_changes_against_last_sync() {
+        use ChangeKind::*;
+
+        let cases = [
+            (Some("a"), Some("a"), None, None),
+            (Some("a"), None, None, Some(AddedLocally)),
+            (None, Some("a"), None, Some(AddedRemotely)),
+            (Some("b"), Some("a"), Some("a"), Some(ChangedLocally)),
+            (Some("a"), Some("b"), Some("a"), Some(ChangedRemotely)),
+            (None, Some("a"), Some("a"), Some(RemovedLocally)),
+            (Some("a"), None, Some("a"), Some(RemovedRemotely)),
+            (Some("b"), Some("c"), Some("a"), Some(Conflict)),
+            (Some("b"), None, Some("a"), Some(Conflict)),
+        ];
+        for (local, remote, base, expected) in cases {
+            assert_eq!(
+                ChangeKind::classify(local, remote, base),
+                expected,
+                "local {local:?}, remote {remote:?}, base {base:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn pushes_and_pulls_problems() {
+        with_test_project(|problems_dir| {
+            let (endpoint, store) = mock_store();
+            let remote = Remote::new(&endpoint, None);
+
+            create_problem_dir(problems_dir, "new", "easy", "two-sum");
+            sync_mappings(problems_dir).expect("mappings should sync");
+            sync(problems_dir, &remote, &sync_args(Some(Direction::Push)))
+                .expect("push should succeed");
+
+            let local = local_problems(problems_dir).expect("problems should be listed");
+            assert_eq!(
+                store.lock().unwrap().get("two-sum").map(|(p, _)| p),
+                local.get("two-sum")
+            );
+
+            // Another setter pulls it into an empty project
+            fs::remove_dir_all(problems_dir.join("new")).expect("problems should be removed");
+            fs::remove_file(problems_dir.join(super::SYNC_STATE_FILE))
+                .expect("state should be removed");
+            sync_mappings(problems_dir).expect("mappings should sync");
+            sync(problems_dir, &remote, &sync_args(Some(Direction::Pull)))
+                .expect("pull should succeed");
+
+            assert_eq!(
+                fs::read_to_string(problems_dir.join("new/easy/two-sum/problem.md"))
+                    .expect("statement should be pulled"),
+                "# Problem\n"
+            );
+            assert!(problems_dir.join("new/easy/two-sum/solutions").is_dir());
+            assert_eq!(
+                local_problems(problems_dir).expect("problems should be listed"),
+                local
+            );
+
+            // Removing it locally and pushing removes it remotely
+            fs::remove_dir_all(problems_dir.join("new/easy/two-sum"))
+                .expect("problem should be removed");
+            sync_mappings(problems_dir).expect("mappings should sync");
+            sync(problems_dir, &remote, &sync_args(Some(Direction::Push)))
+                .expect("push should succeed");
+            assert!(store.lock().unwrap().is_empty());
+        });
+    }
+
+    #[test]
+    fn skips_conflicts_unless_forced() {
+        with_test_project(|problems_dir| {
+            let (endpoint, store) = mock_store();
+            let remote = Remote::new(&endpoint, None);
+
+            create_problem_dir(problems_dir, "new", "easy", "two-sum");
+            sync_mappings(problems_dir).expect("mappings should sync");
+            sync(problems_dir, &remote, &sync_args(Some(Direction::Push)))
+                .expect("push should succeed");
+
+            // Change the problem on both sides
+            store
+                .lock()
+                .unwrap()
+                .get_mut("two-sum")
+                .expect("problem should be pushed")
+                .0
+                .hash = "remote".to_owned();
+            let statement_path = problems_dir.join("new/easy/two-sum/problem.md");
+            fs::write(&statement_path, "# Changed\n").expect("statement should be written");
+
+            sync(problems_dir, &remote, &sync_args(Some(Direction::Pull)))
+                .expect("pull should succeed");
+            assert_eq!(fs::read_to_string(&statement_path).unwrap(), "# Changed\n");
+
+            let args = SyncArgs {
+                force: true,
+                ..sync_args(Some(Direction::Pull))
+            };
+            sync(problems_dir, &remote, &args).expect("pull should succeed");
+            assert_eq!(fs::read_to_string(&statement_path).unwrap(), "# Problem\n");
+
+            // The pulled problem is swapped in without leaving its staging folder behind
+            let leftovers: Vec<_> = fs::read_dir(problems_dir)
+                .expect("problems folder should be readable")
+                .map(|entry| entry.expect("entry should be readable").file_name())
+                .filter(|name| name.to_string_lossy().starts_with(".sync-"))
+                .collect();
+            assert!(leftovers.is_empty());
+        });
+    }
+
+    #[test]
+    fn ignores_build_artefacts() {
+        with_test_project(|problems_dir| {
+            create_problem_dir(problems_dir, "new", "easy", "two-sum");
+            sync_mappings(problems_dir).expect("mappings should sync");
+            let before = local_problems(problems_dir).expect("problems should be listed");
+
+            let problem_path = problems_dir.join("new/easy/two-sum");
+            fs::create_dir(problem_path.join("__pycache__")).expect("folder should be created");
+            for file in [
+                "checker.out",
+                "__pycache__/checker.pyc",
+                "solutions/solution.out",
+                SOLVE_STAMP_FILE,
+            ] {
+                fs::write(problem_path.join(file), "").expect("artefact should be written");
+            }
+            assert_eq!(
+                local_problems(problems_dir).expect("problems should be listed"),
+                before
+            );
+
+            let archive = archive_problem(&problem_path).expect("problem should be archived");
+            let archive = ZipArchive::new(Cursor::new(archive)).expect("archive should be valid");
+            let mut names: Vec<&str> = archive.file_names().collect();
+            names.sort();
+            assert_eq!(names, vec!["problem.md", "solutions/", "tests/"]);
+        });
+    }
+}
@@ -0,0 +1,118 @@
+//! Client for a remote problem store over HTTP.
+//!
+//! The store has the following endpoints, relative to its URL:
+//!
+//! - `GET /problems` lists the problems as a JSON array of
+//!   `{"name": ..., "path": ..., "hash": ...}`, where `path` is the location of
+//!   the problem in the problems folder, e.g. `new/easy/two-sum`.
+//! - `GET /problems/<name>` downloads a zip archive of the problem folder.
+//! - `PUT /problems/<name>` uploads a zip archive of the problem folder, with
+//!   its location and hash in the [`PATH_HEADER`] and [`HASH_HEADER`] headers.
+//! - `DELETE /problems/<name>` removes the problem.
+
+use std::io::Read;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Header with the location of an uploaded problem.
+pub const PATH_HEADER: &str = "X-Aucpl-Path";
+/// Header with the content hash of an uploaded problem.
+pub const HASH_HEADER: &str = "X-Aucpl-Hash";
+
+/// A problem in a problem set, either local or remote.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ProblemState {
+    pub name: String,
+    /// Location in the problems folder, e.g. `new/easy/two-sum`.
+    pub path: String,
+    pub hash: String,
+}
+
+/// A remote problem store.
+pub struct Remote {
+    endpoint: String,
+    token: Option<String>,
+}
+
+impl Remote {
+    pub fn new(endpoint: &str, token: Option<String>) -> Self {
+        Remote {
+            endpoint: endpoint.trim_end_matches('/').to_owned(),
+            token,
+        }
+    }
+
+    fn request(&self, method: &str, path: &str) -> ureq::Request {
+        let request = ureq::request(method, &format!("{}{path}", self.endpoint));
+        match &self.token {
+            Some(token) => request.set("Authorization", &format!("Bearer {token}")),
+            None => request,
+        }
+    }
+
+    /// Turn a failed request into an error with the server's message.
+    fn error(&self, err: ureq::Error) -> anyhow::Error {
+        match err {
+            ureq::Error::Status(code, response) => {
+                let body = response.into_string().unwrap_or_default();
+                anyhow::anyhow!(
+                    "The remote problem store returned status {code}: {}",
+                    body.trim()
+                )
+            }
+            err => {
+                anyhow::Error::new(err).context(format!("Failed to connect to {}", self.endpoint))
+            }
+        }
+    }
+
+    /// List the problems in the store.
+    pub fn list(&self) -> Result<Vec<ProblemState>> {
+        let response = self
+            .request("GET", "/problems")
+            .call()
+            .map_err(|err| self.error(err))?;
+        let body = response
+            .into_string()
+            .context("Failed to read the problem list")?;
+
+        serde_json::from_str(&body).context("Failed to parse the problem list")
+    }
+
+    /// Download the zip archive of a problem.
+    pub fn download(&self, name: &str) -> Result<Vec<u8>> {
+        let response = self
+            .request("GET", &format!("/problems/{name}"))
+            .call()
+            .map_err(|err| self.error(err))?;
+
+        let mut archive = Vec::new();
+        response
+            .into_reader()
+            .read_to_end(&mut archive)
+            .context(format!("Failed to download '{name}'"))?;
+        Ok(archive)
+    }
+
+    /// Upload the zip archive of a problem.
+    pub fn upload(&self, problem: &ProblemState, archive: &[u8]) -> Result<()> {
+        self.request("PUT", &format!("/problems/{}", problem.name))
+            .set("Content-Type", "application/zip")
+            .set(PATH_HEADER, &problem.path)
+            .set(HASH_HEADER, &problem.hash)
+            .send_bytes(archive)
+            .map_err(|err| self.error(err))?;
+        Ok(())
+    }
+
+    /// Remove a problem from the store.
+    pub fn delete(&self, name: &str) -> Result<()> {
+        match self.request("DELETE", &format!("/problems/{name}")).call() {
+            Ok(_) => Ok(()),
+            // Someone else already removed it
+            Err(ureq::Error::Status(404, _)) => Ok(()),
+            Err(err) => Err(self.error(err)),
+        }
+    }
+}
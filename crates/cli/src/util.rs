@@ -1,8 +1,13 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
 
 use anyhow::{Context, Result};
 use numeric_sort::sort_unstable;
+use sha2::{Digest, Sha256};
 
 use crate::config::SETTINGS_FILE_NAME;
 use crate::errors::CliError;
@@ -125,8 +130,32 @@ pub fn get_files_in_directory<P: AsRef<Path>>(path: P) -> Result<Vec<String>> {
     Ok(file_names)
 }
 
+/// Get a list of files in a directory and its subdirectories, as paths
+/// relative to the directory with `/` separators, e.g. `subtask1/a.in`.
+pub fn get_files_in_directory_recursive<P: AsRef<Path>>(path: P) -> Result<Vec<String>> {
+    let path = path.as_ref();
+    let mut file_names = get_files_in_directory(path)?;
+
+    for entry in fs::read_dir(path)? {
+        let entry_path = entry?.path();
+        if !entry_path.is_dir() {
+            continue;
+        }
+        let Some(dir_name) = entry_path.file_name().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        for file in get_files_in_directory_recursive(&entry_path)? {
+            file_names.push(format!("{dir_name}/{file}"));
+        }
+    }
+
+    Ok(file_names)
+}
+
+/// Get the test input files in a directory, including ones grouped into
+/// subdirectories (e.g. `subtask1/a.in`), in numeric order.
 pub fn get_input_files_in_directory<P: AsRef<Path>>(path: P) -> Result<Vec<String>> {
-    let mut files: Vec<_> = get_files_in_directory(path)?
+    let mut files: Vec<_> = get_files_in_directory_recursive(path)?
         .into_iter()
         .filter(|name| name.ends_with(".in"))
         .collect();
@@ -134,6 +163,14 @@ pub fn get_input_files_in_directory<P: AsRef<Path>>(path: P) -> Result<Vec<Strin
     Ok(files)
 }
 
+/// Get the SHA-256 hash of some data as a hex string.
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
 pub fn is_file_empty<P: AsRef<Path>>(path: P) -> Result<bool> {
     let metadata = fs::metadata(path)?;
     Ok(metadata.len() == 0)
@@ -148,3 +185,128 @@ pub fn get_lang_from_extension<P: AsRef<Path>>(path: P) -> Result<String> {
         .to_owned();
     Ok(lang)
 }
+
+/// Get the number of worker threads to use, where `0` means one per available CPU.
+pub fn resolve_jobs(jobs: usize) -> usize {
+    if jobs == 0 {
+        thread::available_parallelism().map_or(1, |n| n.get())
+    } else {
+        jobs
+    }
+}
+
+/// Run `work` on every item using up to `jobs` worker threads.
+///
+/// `on_result` is called on the current thread with each result in the same
+/// order as `items`, regardless of the order that the work finishes in, so
+/// that output stays deterministic. If `on_result` returns an error, no new
+/// work is started and the error is returned.
+pub fn run_ordered<T, R, W, F>(items: &[T], jobs: usize, work: W, mut on_result: F) -> Result<()>
+where
+    T: Sync,
+    R: Send,
+    W: Fn(&T) -> R + Sync,
+    F: FnMut(R) -> Result<()>,
+{
+    let jobs = jobs.clamp(1, items.len().max(1));
+    let next_item = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            let sender = sender.clone();
+            let next_item = &next_item;
+            let work = &work;
+            scope.spawn(move || loop {
+                let i = next_item.fetch_add(1, Ordering::Relaxed);
+                if i >= items.len() || sender.send((i, work(&items[i]))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        // Buffer results that finish early until all earlier results are in
+        let mut pending = BTreeMap::new();
+        let mut next_result = 0;
+        for (i, result) in receiver {
+            pending.insert(i, result);
+            while let Some(result) = pending.remove(&next_result) {
+                on_result(result)?;
+                next_result += 1;
+            }
+        }
+
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::thread;
+    use std::time::Duration;
+
+    use tempfile::TempDir;
+
+    use super::{get_input_files_in_directory, run_ordered};
+
+    #[test]
+    fn input_files_include_subdirectories() {
+        let dir = TempDir::new().expect("tempdir should be created");
+        fs::create_dir(dir.path().join("subtask1")).expect("subtask dir should be created");
+        for file in ["10.in", "2.in", "2.out", "subtask1/1.in", "subtask1/1.out"] {
+            fs::write(dir.path().join(file), "").expect("test should be written");
+        }
+
+        let files = get_input_files_in_directory(dir.path()).expect("files should be listed");
+
+        assert_eq!(files, vec!["2.in", "10.in", "subtask1/1.in"]);
+    }
+
+    #[test]
+    fn run_ordered_preserves_input_order() {
+        let items: Vec<u64> = (0..8).collect();
+        let mut results = Vec::new();
+
+        run_ordered(
+            &items,
+            4,
+            |&i| {
+                // Make earlier items finish later
+                thread::sleep(Duration::from_millis((8 - i) * 5));
+                i * 10
+            },
+            |result| {
+                results.push(result);
+                Ok(())
+            },
+        )
+        .expect("work should succeed");
+
+        assert_eq!(results, vec![0, 10, 20, 30, 40, 50, 60, 70]);
+    }
+
+    #[test]
+    fn run_ordered_stops_on_error() {
+        let items: Vec<u64> = (0..4).collect();
+        let mut seen = Vec::new();
+
+        let err = run_ordered(
+            &items,
+            1,
+            |&i| i,
+            |result| {
+                seen.push(result);
+                if result == 1 {
+                    anyhow::bail!("stop");
+                }
+                Ok(())
+            },
+        )
+        .expect_err("error should be returned");
+
+        assert_eq!(err.to_string(), "stop");
+        assert_eq!(seen, vec![0, 1]);
+    }
+}